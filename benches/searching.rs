@@ -0,0 +1,90 @@
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use rand::{Rng, SeedableRng, StdRng};
+
+use algs4_rs::searching::*;
+
+fn fill<T: SymbolTable<u32, u32>>(mut st: T, keys: &[u32]) -> T {
+    for &k in keys {
+        st.put(k, k);
+    }
+    st
+}
+
+// `no_get` skips the get bench, for tables whose reads by `&self` don't
+// rebalance (splay tree), so their gets time the shape left by puts
+macro_rules! make_bench {
+    ($group:ident, $input:ident, $kind:literal, $name:literal, $new:expr) => {{
+        make_bench!($group, $input, $kind, $name, $new, no_get);
+
+        let st = fill($new, &$input);
+        $group.bench_with_input(
+            BenchmarkId::new(format!("get/{}", $kind), $name),
+            &$input,
+            |b, i| b.iter(|| black_box(i.iter().filter(|k| st.get(k).is_some()).count())),
+        );
+    }};
+    ($group:ident, $input:ident, $kind:literal, $name:literal, $new:expr, no_get) => {{
+        $group.bench_with_input(
+            BenchmarkId::new(format!("put/{}", $kind), $name),
+            &$input,
+            |b, i| b.iter(|| black_box(fill($new, i))),
+        );
+
+        $group.bench_with_input(
+            BenchmarkId::new(format!("delete/{}", $kind), $name),
+            &$input,
+            |b, i| {
+                b.iter_batched(
+                    || fill($new, i),
+                    |mut st| {
+                        for k in i.iter() {
+                            st.delete(k);
+                        }
+                        st
+                    },
+                    BatchSize::SmallInput,
+                )
+            },
+        );
+    }};
+}
+
+fn random_benches(c: &mut Criterion) {
+    let mut group = c.benchmark_group("searching");
+    let input = StdRng::from_seed(&[46][..])
+        .gen_iter::<u32>()
+        .take(10000)
+        .collect::<Vec<_>>();
+
+    make_bench!(group, input, "random", "bst", BSTree::new());
+    make_bench!(group, input, "random", "rbtree", RBTree::new());
+    make_bench!(group, input, "random", "avl", AVLTree::new());
+    make_bench!(group, input, "random", "treap", Treap::with_seed(46));
+    make_bench!(group, input, "random", "splay", SplayTree::new(), no_get);
+    make_bench!(group, input, "random", "skip_list", SkipList::with_seed(46));
+    make_bench!(group, input, "random", "btree", BTree::new());
+
+    group.finish();
+}
+
+// unbalanced BST is a path for sorted keys, so it's excluded
+fn sorted_benches(c: &mut Criterion) {
+    let mut group = c.benchmark_group("searching");
+    let input = (0..10000).collect::<Vec<_>>();
+
+    make_bench!(group, input, "sorted", "rbtree", RBTree::new());
+    make_bench!(group, input, "sorted", "avl", AVLTree::new());
+    make_bench!(group, input, "sorted", "treap", Treap::with_seed(46));
+    make_bench!(group, input, "sorted", "splay", SplayTree::new(), no_get);
+    make_bench!(group, input, "sorted", "skip_list", SkipList::with_seed(46));
+    make_bench!(group, input, "sorted", "btree", BTree::new());
+
+    group.finish();
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default().sample_size(10);
+    targets = random_benches, sorted_benches
+}
+criterion_main!(benches);
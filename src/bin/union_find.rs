@@ -1,36 +1,119 @@
+use std::env;
 use std::error::Error;
 use std::io::{self, BufRead};
+use std::time::Instant;
 
-use algs4_rs::graphs::UnionFind;
+use algs4_rs::graphs::{
+    KeyedUnionFind, QuickFindUF, QuickUnionUF, UnionFind, UnionFindAlgo, WeightedQuickUnionUF,
+};
 
+const USAGE: &str =
+    "Usage: union_find [--algo quick-find|quick-union|weighted|rank] [--print] [--stats] < input";
+
+/// reads `tinyUF.txt` format (a count line, then pairs of ids in 0..count),
+/// or pairs of arbitrary string tokens without the count line
 fn main() -> Result<(), Box<dyn Error>> {
+    let mut algo = String::from("rank");
+    let (mut print, mut stats) = (false, false);
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--algo" => algo = args.next().ok_or(USAGE)?,
+            "--print" => print = true,
+            "--stats" => stats = true,
+            _ => return Err(USAGE.into()),
+        }
+    }
+
     let stdin = io::stdin();
-    let mut iter = stdin.lock().lines();
-    let n = iter
+    let mut lines = stdin.lock().lines();
+    let first = lines
         .next()
-        .unwrap_or_else(|| Err(io::Error::new(io::ErrorKind::Other, "No line")))?
-        .parse::<usize>()?;
-
-    let mut uf = UnionFind::new(n);
-    for line in iter {
-        let line = line.unwrap();
-        let mut parts = line.split_whitespace().map(|s| s.parse::<usize>());
-        let (p, q) = match (parts.next(), parts.next()) {
-            (Some(Ok(p)), Some(Ok(q))) => (p, q),
-
-            _ => {
-                return Err("Parse error".into());
-            }
-        };
-
-        if let Some(true) = uf.connected(p, q) {
-            continue;
+        .unwrap_or_else(|| Err(io::Error::new(io::ErrorKind::Other, "No line")))?;
+
+    let mut pairs = Vec::new();
+    let n = match first.trim().parse::<usize>() {
+        Ok(n) => Some(n),
+        Err(_) => {
+            pairs.push(parse_pair(&first)?);
+            None
         }
+    };
+    for line in lines {
+        let line = line?;
+        if !line.trim().is_empty() {
+            pairs.push(parse_pair(&line)?);
+        }
+    }
 
-        uf.union(p, q);
-        // println!("{} - {}", p, q);
+    let ids = pairs
+        .iter()
+        .map(|(p, q)| Ok((p.parse::<usize>()?, q.parse::<usize>()?)))
+        .collect::<Result<Vec<_>, std::num::ParseIntError>>();
+    let (n, ids, keys) = match (n, ids) {
+        (Some(n), Ok(ids)) => (n, ids, None),
+        _ => {
+            // string tokens are numbered on first sight
+            let mut keys = KeyedUnionFind::new();
+            let ids = pairs
+                .into_iter()
+                .map(|(p, q)| (keys.insert(p), keys.insert(q)))
+                .collect();
+            (keys.len(), ids, Some(keys))
+        }
+    };
+    let name = |i: usize| match &keys {
+        Some(keys) => keys.key(i).unwrap().clone(),
+        None => i.to_string(),
+    };
+
+    let run = match algo.as_str() {
+        "quick-find" => run::<QuickFindUF>,
+        "quick-union" => run::<QuickUnionUF>,
+        "weighted" => run::<WeightedQuickUnionUF>,
+        "rank" => run::<UnionFind>,
+        _ => return Err(USAGE.into()),
+    };
+    let start = Instant::now();
+    let (count, accesses) = run(n, &ids, &mut |p, q| {
+        if print {
+            println!("{} - {}", name(p), name(q));
+        }
+    })?;
+    let elapsed = start.elapsed();
+
+    println!("{} nodes within {} components", n, count);
+    if stats {
+        match accesses {
+            Some(accesses) => println!("{}: {:?}, {} array accesses", algo, elapsed, accesses),
+            None => println!("{}: {:?}", algo, elapsed),
+        }
     }
-    println!("{} nodes within {} components", n, uf.count());
 
     Ok(())
 }
+
+// (components, array accesses)
+fn run<U: UnionFindAlgo>(
+    n: usize,
+    pairs: &[(usize, usize)],
+    connect: &mut dyn FnMut(usize, usize),
+) -> Result<(usize, Option<usize>), Box<dyn Error>> {
+    let mut uf = U::new(n);
+    for &(p, q) in pairs {
+        match uf.union(p, q) {
+            Some(true) => connect(p, q),
+            Some(false) => {}
+            None => return Err("Out of range".into()),
+        }
+    }
+    Ok((uf.count(), uf.accesses()))
+}
+
+fn parse_pair(line: &str) -> Result<(String, String), Box<dyn Error>> {
+    let mut parts = line.split_whitespace();
+    match (parts.next(), parts.next()) {
+        (Some(p), Some(q)) => Ok((p.to_string(), q.to_string())),
+        _ => Err("Parse error".into()),
+    }
+}
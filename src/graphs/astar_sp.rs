@@ -0,0 +1,126 @@
+//! A* Shortest Path
+//!
+//! Dijkstra's algorithm guided by a heuristic h(v), an estimated distance
+//! from v to the target, vertices are visited in order of dist_to[v] + h(v).
+use super::super::sorting::IndexMinPQ;
+use super::base::*;
+use super::weighted_path::*;
+
+/// HasAStarSP
+///
+/// Directed non-negative graph can calculate the shortest path s->t by A*, the
+/// heuristic must be admissible (never overestimates), otherwise the path may
+/// not be the shortest. Vertices are re-opened when a shorter path is found,
+/// so an inconsistent heuristic is fine.
+pub trait HasAStarSP<E>
+where
+    E: Directed + NonNegative,
+{
+    fn astar_sp<H>(&self, s: usize, t: usize, h: H) -> WeightedPath<E>
+    where
+        H: Fn(Vertex) -> f64;
+}
+impl<G, E> HasAStarSP<E> for G
+where
+    E: Directed + NonNegative,
+    G: Graph<Edge = E>,
+{
+    fn astar_sp<H>(&self, s: usize, t: usize, h: H) -> WeightedPath<E>
+    where
+        H: Fn(Vertex) -> f64,
+    {
+        let mut p = WeightedPath::new(self.v_size(), f64::INFINITY);
+        astar_sp(&mut p, self, s, t, h);
+        p
+    }
+}
+
+// stops once t is popped, distances of other vertices are upper bounds
+fn astar_sp<G, E, H>(p: &mut WeightedPath<E>, g: &G, s: usize, t: usize, h: H)
+where
+    E: Directed + NonNegative,
+    G: Graph<Edge = E>,
+    H: Fn(Vertex) -> f64,
+{
+    let mut pq = IndexMinPQ::new(g.v_size());
+    pq.upsert(s, h(s));
+    p.dist_to[s] = 0.0;
+    while let Some((v, _)) = pq.pop() {
+        if v == t {
+            break;
+        }
+        for e in g.adj(v) {
+            let w = e.to();
+            if p.dist_to[v] + e.weight() < p.dist_to[w] {
+                p.dist_to[w] = p.dist_to[v] + e.weight();
+                p.edge_to[w] = Some(e);
+                pq.upsert(w, p.dist_to[w] + h(w));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::dijkstra_sp::HasDijkstraSP;
+    use super::super::{BFSPath, Connectivity, GridGraph};
+    use super::*;
+
+    const MAZE: [&str; 7] = [
+        "S....#....",
+        ".###.#.##.",
+        "...#...#..",
+        "##.#####.#",
+        "...#.....#",
+        ".#...###..",
+        ".#.#.....T",
+    ];
+
+    #[test]
+    fn empty() {
+        let g = GridGraph::new(1, 2, Connectivity::Four);
+        let sp = g.astar_sp(0, 0, |_| 0.0);
+        assert_eq!(0, sp.dist_to(0).round() as usize);
+        assert_eq!(None, sp.path_to(0).next());
+    }
+
+    #[test]
+    fn maze_four() {
+        let g = GridGraph::from_maze(&MAZE, Connectivity::Four);
+        let (s, t) = (g.vertex(0, 0), g.vertex(6, 9));
+
+        let bfs = BFSPath::new(&g, s);
+        let dijkstra = g.dijkstra_sp(s);
+        let astar = g.astar_sp(s, t, |v| g.heuristic(v, t));
+
+        let hops = bfs.dist_to(t).unwrap();
+        assert_eq!(hops, dijkstra.dist_to(t).round() as usize);
+        assert_eq!(hops, astar.dist_to(t).round() as usize);
+        assert_eq!(hops, astar.path_to(t).count());
+        for e in astar.path_to(t) {
+            let (r, c) = g.cell(e.to());
+            assert!(!g.is_blocked(r, c));
+        }
+    }
+
+    #[test]
+    fn maze_eight() {
+        let g = GridGraph::from_maze(&MAZE, Connectivity::Eight);
+        let (s, t) = (g.vertex(0, 0), g.vertex(6, 9));
+
+        let dijkstra = g.dijkstra_sp(s);
+        let astar = g.astar_sp(s, t, |v| g.heuristic(v, t));
+        assert_eq!(
+            (dijkstra.dist_to(t) * 1000.0).round(),
+            (astar.dist_to(t) * 1000.0).round()
+        );
+        assert!(astar.dist_to(t) < BFSPath::new(&g, s).dist_to(t).unwrap() as f64 + 1e-9);
+    }
+
+    #[test]
+    fn unreachable() {
+        let g = GridGraph::from_maze(&[".#."], Connectivity::Eight);
+        let sp = g.astar_sp(0, 2, |v| g.heuristic(v, 2));
+        assert!(!sp.has_path_to(2));
+    }
+}
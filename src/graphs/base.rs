@@ -52,6 +52,13 @@ pub trait Weighted: Edge + PartialOrd {
 /// Concept non-negative has zero or possitive weight value on edge
 pub trait NonNegative: Weighted {}
 
+/// Relabel
+///
+/// Concept an edge can be copied with other vertices, so views can remap ids
+pub trait Relabel: Edge {
+    fn relabel(&self, v: Vertex, w: Vertex) -> Self;
+}
+
 /// Graph
 ///
 /// A graph consists of vertices and edges.
@@ -0,0 +1,629 @@
+//! Binary Format
+//!
+//! A compact, versioned binary format for graphs, all numbers little-endian:
+//!
+//! | field        | size                | notes                               |
+//! | ------------ | ------------------- | ----------------------------------- |
+//! | magic        | 8                   | `b"ALG4GRPH"`                       |
+//! | version      | u16                 | `VERSION`                           |
+//! | kind         | u8                  | `EdgeKind`                          |
+//! | weight width | u8                  | 0 (unweighted), 4 (f32) or 8 (f64)  |
+//! | V, E         | u64, u64            | `v_size()`, `e_size()`              |
+//! | offsets      | (V+1) * u64         | CSR: row v is `offsets[v]..[v+1]`   |
+//! | targets      | offsets[V] * u64    | `e.other(v)` of each edge in adj(v) |
+//! | weights      | offsets[V] * width  | only for weighted kinds             |
+//! | checksum     | u32                 | CRC-32 of all bytes above           |
+//!
+//! Rows keep the order of `adj(v)`, an undirected edge is stored in rows of
+//! both ends (so a self-loop twice in its row), and loaded back as
+//! (min, max) vertices.
+use super::acyclic_graph::EdgeWeightedDAG;
+use super::base::*;
+use super::edge::*;
+use super::graph::*;
+use super::{DirectedGraph, UndirectedGraph};
+
+use std::convert::TryFrom;
+use std::fmt;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+
+pub const MAGIC: &[u8; 8] = b"ALG4GRPH";
+pub const VERSION: u16 = 1;
+
+// do not trust counts in the header for allocation
+const MAX_PREALLOCATION: usize = 1 << 16;
+
+/// Kind of edges, the graph type is not recorded, so a file can be loaded as
+/// any graph type with the same kind of edges
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EdgeKind {
+    Undirected = 1,
+    Directed = 2,
+    WeightedUndirected = 3,
+    WeightedDirected = 4,
+    NonNegativeWeightedDirected = 5,
+}
+impl EdgeKind {
+    fn is_weighted(self) -> bool {
+        self as u8 >= EdgeKind::WeightedUndirected as u8
+    }
+    fn is_undirected(self) -> bool {
+        self == EdgeKind::Undirected || self == EdgeKind::WeightedUndirected
+    }
+}
+
+/// Width of weights on disk, f32 halves the size but loses precision
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WeightWidth {
+    F32 = 4,
+    F64 = 8,
+}
+
+#[derive(Debug)]
+pub enum FormatError {
+    Io(io::Error),
+    BadMagic,
+    UnsupportedVersion(u16),
+    KindMismatch {
+        expected: EdgeKind,
+        found: u8,
+    },
+    BadWeightWidth(u8),
+    /// the input ended early
+    Truncated,
+    /// well-formed header, but inconsistent content
+    Corrupt(&'static str),
+    ChecksumMismatch,
+}
+impl fmt::Display for FormatError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FormatError::Io(e) => write!(f, "io error: {}", e),
+            FormatError::BadMagic => write!(f, "not a graph file"),
+            FormatError::UnsupportedVersion(v) => write!(f, "unsupported version {}", v),
+            FormatError::KindMismatch { expected, found } => {
+                write!(f, "expected {:?} edges, found kind {}", expected, found)
+            }
+            FormatError::BadWeightWidth(w) => write!(f, "bad weight width {}", w),
+            FormatError::Truncated => write!(f, "truncated file"),
+            FormatError::Corrupt(reason) => write!(f, "corrupt file: {}", reason),
+            FormatError::ChecksumMismatch => write!(f, "checksum mismatch"),
+        }
+    }
+}
+impl std::error::Error for FormatError {}
+impl From<io::Error> for FormatError {
+    fn from(e: io::Error) -> Self {
+        if e.kind() == io::ErrorKind::UnexpectedEof {
+            FormatError::Truncated
+        } else {
+            FormatError::Io(e)
+        }
+    }
+}
+
+/// BinaryFormat
+///
+/// Graphs can be saved to any `io::Write` and loaded from any `io::Read`.
+/// Both are buffered internally, so `load` may read past the end of the
+/// graph, the rest of `r` should be considered consumed.
+pub trait BinaryFormat: Sized {
+    fn save_with<W: Write>(&self, w: W, width: WeightWidth) -> Result<(), FormatError>;
+    fn load<R: Read>(r: R) -> Result<Self, FormatError>;
+
+    /// weights are saved as f64
+    fn save<W: Write>(&self, w: W) -> Result<(), FormatError> {
+        self.save_with(w, WeightWidth::F64)
+    }
+}
+
+macro_rules! impl_binary_format {
+    ($graph:ty, $kind:expr, $weight:expr, $load:expr) => {
+        impl BinaryFormat for $graph {
+            fn save_with<W: Write>(&self, w: W, width: WeightWidth) -> Result<(), FormatError> {
+                save(self, w, $kind, width, $weight)
+            }
+            fn load<R: Read>(r: R) -> Result<Self, FormatError> {
+                load(r, $kind).and_then($load)
+            }
+        }
+    };
+}
+
+impl_binary_format!(
+    UndirectedGraph,
+    EdgeKind::Undirected,
+    |_| 0.0,
+    |csr: Csr| Ok(UndirectedGraph::from_adj(
+        csr.e,
+        csr.rows(|v, w, _| Some(UndirectedEdge::new(v.min(w), v.max(w))))
+    ))
+);
+impl_binary_format!(DirectedGraph, EdgeKind::Directed, |_| 0.0, |csr: Csr| Ok(
+    DirectedGraph::from_adj(csr.e, csr.rows(|v, w, _| Some(DirectedEdge::new(v, w))))
+));
+impl_binary_format!(
+    EdgeWeightedUndirectedGraph,
+    EdgeKind::WeightedUndirected,
+    |e: &WeightedUndirectedEdge| e.weight(),
+    |csr: Csr| Ok(EdgeWeightedUndirectedGraph::from_adj(
+        csr.e,
+        csr.rows(|v, w, weight| Some(WeightedUndirectedEdge::new(v.min(w), v.max(w), weight)))
+    ))
+);
+impl_binary_format!(
+    EdgeWeightedDirectedGraph,
+    EdgeKind::WeightedDirected,
+    |e: &WeightedDirectedEdge| e.weight(),
+    |csr: Csr| Ok(EdgeWeightedDirectedGraph::from_adj(
+        csr.e,
+        csr.rows(|v, w, weight| Some(WeightedDirectedEdge::new(v, w, weight)))
+    ))
+);
+impl_binary_format!(
+    EdgeNonNegativeWeightedDirectedGraph,
+    EdgeKind::NonNegativeWeightedDirected,
+    |e: &NonNegativeWeightedDirectedEdge| e.weight(),
+    |csr: Csr| {
+        let adj = csr.rows(NonNegativeWeightedDirectedEdge::new);
+        if adj.iter().map(|row| row.len()).sum::<usize>() != csr.targets.len() {
+            return Err(FormatError::Corrupt("negative weight"));
+        }
+        Ok(EdgeNonNegativeWeightedDirectedGraph::from_adj(csr.e, adj))
+    }
+);
+impl_binary_format!(
+    EdgeWeightedDAG<EdgeWeightedDirectedGraph>,
+    EdgeKind::WeightedDirected,
+    |e: &WeightedDirectedEdge| e.weight(),
+    |csr: Csr| {
+        let g = EdgeWeightedDirectedGraph::from_adj(
+            csr.e,
+            csr.rows(|v, w, weight| Some(WeightedDirectedEdge::new(v, w, weight))),
+        );
+        EdgeWeightedDAG::try_from(g).map_err(|_| FormatError::Corrupt("cycle in a DAG"))
+    }
+);
+
+fn save<G, W, F>(
+    g: &G,
+    w: W,
+    kind: EdgeKind,
+    width: WeightWidth,
+    weight: F,
+) -> Result<(), FormatError>
+where
+    G: Graph,
+    W: Write,
+    F: Fn(&G::Edge) -> f64,
+{
+    let mut w = CrcWriter::new(BufWriter::new(w));
+    let width = if kind.is_weighted() { width as u8 } else { 0 };
+    w.write_all(MAGIC)?;
+    w.write_all(&VERSION.to_le_bytes())?;
+    w.write_all(&[kind as u8, width])?;
+    w.write_u64(g.v_size())?;
+    w.write_u64(g.e_size())?;
+
+    let mut offset = 0;
+    w.write_u64(offset)?;
+    for v in 0..g.v_size() {
+        offset += g.adj(v).count();
+        w.write_u64(offset)?;
+    }
+    for v in 0..g.v_size() {
+        for e in g.adj(v) {
+            w.write_u64(e.other(v))?;
+        }
+    }
+    for v in 0..g.v_size() {
+        for e in g.adj(v) {
+            match width {
+                4 => w.write_all(&(weight(&e) as f32).to_le_bytes())?,
+                8 => w.write_all(&weight(&e).to_le_bytes())?,
+                _ => {}
+            }
+        }
+    }
+
+    let crc = w.crc.sum();
+    w.inner.write_all(&crc.to_le_bytes())?;
+    w.inner.flush()?;
+    Ok(())
+}
+
+/// Compressed sparse rows of a graph file
+struct Csr {
+    e: usize,
+    offsets: Vec<usize>,
+    targets: Vec<Vertex>,
+    weights: Vec<f64>,
+}
+impl Csr {
+    // edges by (v, target, weight) of each row, None ones dropped
+    fn rows<E, F>(&self, edge: F) -> Vec<Vec<E>>
+    where
+        F: Fn(Vertex, Vertex, f64) -> Option<E>,
+    {
+        self.offsets
+            .windows(2)
+            .enumerate()
+            .map(|(v, range)| {
+                (range[0]..range[1])
+                    .filter_map(|i| {
+                        let weight = self.weights.get(i).cloned().unwrap_or(0.0);
+                        edge(v, self.targets[i], weight)
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+fn load<R: Read>(r: R, kind: EdgeKind) -> Result<Csr, FormatError> {
+    let mut r = CrcReader::new(BufReader::new(r));
+
+    let mut magic = [0; 8];
+    r.read_exact(&mut magic)?;
+    if &magic != MAGIC {
+        return Err(FormatError::BadMagic);
+    }
+    let mut header = [0; 4];
+    r.read_exact(&mut header)?;
+    let version = u16::from_le_bytes([header[0], header[1]]);
+    if version != VERSION {
+        return Err(FormatError::UnsupportedVersion(version));
+    }
+    if header[2] != kind as u8 {
+        return Err(FormatError::KindMismatch {
+            expected: kind,
+            found: header[2],
+        });
+    }
+    let width = header[3];
+    match (kind.is_weighted(), width) {
+        (false, 0) | (true, 4) | (true, 8) => {}
+        _ => return Err(FormatError::BadWeightWidth(width)),
+    }
+
+    let v = r.read_usize()?;
+    let e = r.read_usize()?;
+    let mut offsets = Vec::with_capacity(v.saturating_add(1).min(MAX_PREALLOCATION));
+    for _ in 0..=v {
+        let offset = r.read_usize()?;
+        if offset < offsets.last().cloned().unwrap_or(0) {
+            return Err(FormatError::Corrupt("offsets not sorted"));
+        }
+        offsets.push(offset);
+    }
+    let n = *offsets.last().unwrap();
+    if offsets[0] != 0 {
+        return Err(FormatError::Corrupt("offsets not from 0"));
+    }
+    if Some(n) != e.checked_mul(if kind.is_undirected() { 2 } else { 1 }) {
+        return Err(FormatError::Corrupt("edge count"));
+    }
+
+    let mut targets = Vec::with_capacity(n.min(MAX_PREALLOCATION));
+    for _ in 0..n {
+        let w = r.read_usize()?;
+        if w >= v {
+            return Err(FormatError::Corrupt("vertex out of range"));
+        }
+        targets.push(w);
+    }
+    let mut weights = Vec::with_capacity(if width > 0 { n } else { 0 }.min(MAX_PREALLOCATION));
+    for _ in 0..if width > 0 { n } else { 0 } {
+        if width == 4 {
+            let mut b = [0; 4];
+            r.read_exact(&mut b)?;
+            weights.push(f32::from_le_bytes(b) as f64);
+        } else {
+            let mut b = [0; 8];
+            r.read_exact(&mut b)?;
+            weights.push(f64::from_le_bytes(b));
+        }
+    }
+
+    let crc = r.crc.sum();
+    let mut b = [0; 4];
+    r.inner.read_exact(&mut b)?;
+    if u32::from_le_bytes(b) != crc {
+        return Err(FormatError::ChecksumMismatch);
+    }
+
+    Ok(Csr {
+        e,
+        offsets,
+        targets,
+        weights,
+    })
+}
+
+/// CRC-32 (IEEE 802.3), bitwise, no table
+struct Crc32(u32);
+impl Crc32 {
+    fn new() -> Self {
+        Crc32(0xffff_ffff)
+    }
+    fn update(&mut self, bytes: &[u8]) {
+        for &b in bytes {
+            self.0 ^= b as u32;
+            for _ in 0..8 {
+                let mask = (self.0 & 1).wrapping_neg();
+                self.0 = (self.0 >> 1) ^ (0xedb8_8320 & mask);
+            }
+        }
+    }
+    fn sum(&self) -> u32 {
+        !self.0
+    }
+}
+
+struct CrcWriter<W> {
+    inner: W,
+    crc: Crc32,
+}
+impl<W: Write> CrcWriter<W> {
+    fn new(inner: W) -> Self {
+        CrcWriter {
+            inner,
+            crc: Crc32::new(),
+        }
+    }
+    fn write_all(&mut self, bytes: &[u8]) -> io::Result<()> {
+        self.crc.update(bytes);
+        self.inner.write_all(bytes)
+    }
+    fn write_u64(&mut self, n: usize) -> io::Result<()> {
+        self.write_all(&(n as u64).to_le_bytes())
+    }
+}
+
+struct CrcReader<R> {
+    inner: R,
+    crc: Crc32,
+}
+impl<R: Read> CrcReader<R> {
+    fn new(inner: R) -> Self {
+        CrcReader {
+            inner,
+            crc: Crc32::new(),
+        }
+    }
+    fn read_exact(&mut self, bytes: &mut [u8]) -> io::Result<()> {
+        self.inner.read_exact(bytes)?;
+        self.crc.update(bytes);
+        Ok(())
+    }
+    fn read_usize(&mut self) -> Result<usize, FormatError> {
+        let mut b = [0; 8];
+        self.read_exact(&mut b)?;
+        usize::try_from(u64::from_le_bytes(b)).map_err(|_| FormatError::Corrupt("too large"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{Rng, SeedableRng, StdRng};
+
+    fn adj<G: Graph>(g: &G) -> Vec<Vec<(Vertex, Vertex)>> {
+        (0..g.v_size())
+            .map(|v| g.adj(v).map(|e| e.vertices()).collect())
+            .collect()
+    }
+
+    fn round_trip<G: BinaryFormat>(g: &G) -> G {
+        let mut buf = Vec::new();
+        g.save(&mut buf).unwrap();
+        G::load(&buf[..]).unwrap()
+    }
+
+    fn weighted() -> EdgeWeightedDirectedGraph {
+        let mut g = EdgeWeightedDirectedGraph::new(4);
+        g.add_edge(WeightedDirectedEdge::new(0, 1, 0.5));
+        g.add_edge(WeightedDirectedEdge::new(1, 2, -1.25));
+        g.add_edge(WeightedDirectedEdge::new(0, 2, 0.125));
+        g.add_edge(WeightedDirectedEdge::new(2, 3, 3.0));
+        g
+    }
+
+    #[test]
+    fn crc32() {
+        let mut crc = Crc32::new();
+        crc.update(b"123456789");
+        assert_eq!(0xcbf4_3926, crc.sum());
+    }
+
+    #[test]
+    fn empty() {
+        let g = round_trip(&DirectedGraph::new(0));
+        assert_eq!(0, g.v_size());
+        assert_eq!(0, g.e_size());
+
+        let mut buf = Vec::new();
+        UndirectedGraph::new(3).save(&mut buf).unwrap();
+        // header, 4 offsets, checksum
+        assert_eq!(8 + 4 + 16 + 4 * 8 + 4, buf.len());
+    }
+
+    #[test]
+    fn unweighted() {
+        let mut g = UndirectedGraph::new(4);
+        g.add_edge(0, 1);
+        g.add_edge(2, 1);
+        g.add_edge(3, 3);
+        let h = round_trip(&g);
+        assert_eq!(3, h.e_size());
+        assert_eq!(
+            vec![
+                vec![(0, 1)],
+                vec![(0, 1), (1, 2)],
+                vec![(1, 2)],
+                vec![(3, 3), (3, 3)]
+            ],
+            adj(&h)
+        );
+
+        let mut g = DirectedGraph::new(3);
+        g.add_edge(2, 1);
+        g.add_edge(2, 0);
+        g.add_edge(0, 2);
+        let h = round_trip(&g);
+        assert_eq!(3, h.e_size());
+        assert_eq!(adj(&g), adj(&h));
+    }
+
+    #[test]
+    fn weighted_kinds() {
+        let g = weighted();
+        let h = round_trip(&g);
+        assert_eq!(adj(&g), adj(&h));
+        let weights =
+            |g: &EdgeWeightedDirectedGraph| g.edges().map(|e| e.weight()).collect::<Vec<_>>();
+        assert_eq!(weights(&g), weights(&h));
+
+        let mut buf = Vec::new();
+        g.save_with(&mut buf, WeightWidth::F32).unwrap();
+        let h = EdgeWeightedDirectedGraph::load(&buf[..]).unwrap();
+        assert_eq!(weights(&g), weights(&h));
+
+        let mut g = EdgeWeightedUndirectedGraph::new(3);
+        g.add_edge(&WeightedUndirectedEdge::new(0, 1, 1.5));
+        g.add_edge(&WeightedUndirectedEdge::new(2, 1, 2.5));
+        let h = round_trip(&g);
+        assert_eq!(2, h.e_size());
+        assert_eq!(2.5, h.adj(2).next().unwrap().weight());
+
+        let mut g = EdgeNonNegativeWeightedDirectedGraph::new(2);
+        g.add_edge(NonNegativeWeightedDirectedEdge::new(0, 1, 2.0).unwrap());
+        assert_eq!(adj(&g), adj(&round_trip(&g)));
+
+        let dag = EdgeWeightedDAG::try_from(weighted()).ok().unwrap();
+        let h = round_trip(&dag);
+        assert_eq!(vec![0, 1, 2, 3], h.topo_order().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn errors() {
+        let mut buf = Vec::new();
+        weighted().save(&mut buf).unwrap();
+
+        match DirectedGraph::load(&buf[..]) {
+            Err(FormatError::KindMismatch { expected, found }) => {
+                assert_eq!(EdgeKind::Directed, expected);
+                assert_eq!(EdgeKind::WeightedDirected as u8, found);
+            }
+            _ => panic!("kind mismatch expected"),
+        }
+        // negative weight
+        assert!(EdgeNonNegativeWeightedDirectedGraph::load(&buf[..]).is_err());
+
+        let mut bad = buf.clone();
+        bad[0] = b'X';
+        assert!(matches!(
+            EdgeWeightedDirectedGraph::load(&bad[..]),
+            Err(FormatError::BadMagic)
+        ));
+
+        let mut bad = buf.clone();
+        bad[8] = 2;
+        assert!(matches!(
+            EdgeWeightedDirectedGraph::load(&bad[..]),
+            Err(FormatError::UnsupportedVersion(2))
+        ));
+
+        let mut bad = buf.clone();
+        bad[11] = 3;
+        assert!(matches!(
+            EdgeWeightedDirectedGraph::load(&bad[..]),
+            Err(FormatError::BadWeightWidth(3))
+        ));
+
+        assert!(matches!(
+            EdgeWeightedDirectedGraph::load(&buf[..buf.len() - 1]),
+            Err(FormatError::Truncated)
+        ));
+
+        // last weight
+        let mut bad = buf.clone();
+        let i = bad.len() - 5;
+        bad[i] ^= 0x10;
+        assert!(matches!(
+            EdgeWeightedDirectedGraph::load(&bad[..]),
+            Err(FormatError::ChecksumMismatch)
+        ));
+
+        // a cycle, with a valid checksum
+        let mut g = weighted();
+        g.add_edge(WeightedDirectedEdge::new(3, 0, 1.0));
+        let mut buf = Vec::new();
+        g.save(&mut buf).unwrap();
+        assert!(matches!(
+            EdgeWeightedDAG::<EdgeWeightedDirectedGraph>::load(&buf[..]),
+            Err(FormatError::Corrupt(_))
+        ));
+    }
+
+    #[test]
+    fn huge_counts() {
+        // V = 2^40 in the header must not allocate
+        let mut buf = Vec::new();
+        DirectedGraph::new(1).save(&mut buf).unwrap();
+        buf[12..20].copy_from_slice(&(1u64 << 40).to_le_bytes());
+        assert!(matches!(
+            DirectedGraph::load(&buf[..]),
+            Err(FormatError::Truncated)
+        ));
+    }
+
+    #[test]
+    fn fuzz_corruptions() {
+        let mut g = EdgeWeightedUndirectedGraph::new(20);
+        let mut rng = StdRng::from_seed(&[36][..]);
+        for _ in 0..50 {
+            let (v, w) = (rng.gen_range(0, 20), rng.gen_range(0, 20));
+            g.add_edge(&WeightedUndirectedEdge::new(v, w, rng.gen_range(0.0, 1.0)));
+        }
+        let mut buf = Vec::new();
+        g.save(&mut buf).unwrap();
+        assert!(EdgeWeightedUndirectedGraph::load(&buf[..]).is_ok());
+
+        for _ in 0..2000 {
+            let mut bad = buf.clone();
+            match rng.gen_range(0, 3) {
+                // flip bits of one byte
+                0 => {
+                    let i = rng.gen_range(0, bad.len());
+                    bad[i] ^= rng.gen_range(1, 256) as u8;
+                }
+                // a burst of random bytes
+                1 => {
+                    let i = rng.gen_range(0, bad.len() - 4);
+                    let b = rng.gen_range(0, 256) as u8;
+                    if bad[i..i + 4].iter().all(|&x| x == b) {
+                        continue;
+                    }
+                    for x in bad[i..i + 4].iter_mut() {
+                        *x = b;
+                    }
+                }
+                // truncated
+                _ => {
+                    let n = rng.gen_range(0, bad.len());
+                    bad.truncate(n);
+                }
+            }
+            assert!(EdgeWeightedUndirectedGraph::load(&bad[..]).is_err());
+        }
+
+        // random garbage after a valid header
+        for _ in 0..200 {
+            let mut bad = buf[..12].to_vec();
+            let n = rng.gen_range(0, 200);
+            bad.extend((0..n).map(|_| rng.gen_range(0, 256) as u8));
+            assert!(EdgeWeightedUndirectedGraph::load(&bad[..]).is_err());
+        }
+    }
+}
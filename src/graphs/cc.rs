@@ -40,6 +40,16 @@ impl CC {
         c.init(g);
         c
     }
+    // `ids[]` numbered in order of the first vertex of each component
+    #[cfg(feature = "parallel")]
+    pub(super) fn from_ids(ids: Vec<usize>, count: usize) -> Self {
+        let mut sizes = vec![0; count];
+        for &id in ids.iter() {
+            sizes[id] += 1;
+        }
+        CC { ids, sizes }
+    }
+
     pub fn count(&self) -> usize {
         self.sizes.len()
     }
@@ -0,0 +1,408 @@
+//! Centrality
+//!
+//! Measures of how important a vertex is in a graph: PageRank, betweenness
+//! (Brandes' algorithm), closeness, harmonic and degree centrality.
+//!
+//! All measures follow `adj`, i.e. out-going edges for directed graphs, an
+//! undirected graph is treated as a directed graph with edges both ways.
+//! So betweenness is accumulated over ordered pairs (s,t), for undirected
+//! graphs every pair is counted twice, halve it for the conventional value.
+use super::super::sorting::IndexMinPQ;
+use super::base::*;
+
+use std::collections::VecDeque;
+
+const PAGERANK_MAX_ITERATIONS: usize = 1000;
+
+/// HasCentrality
+///
+/// Centrality measures for all graphs.
+pub trait HasCentrality {
+    fn pagerank(&self, damping: f64, tolerance: f64) -> Vec<f64>;
+    fn betweenness(&self) -> Vec<f64>;
+    fn closeness(&self) -> Vec<f64>;
+    fn harmonic(&self) -> Vec<f64>;
+
+    fn out_degrees(&self) -> Vec<usize>;
+    fn in_degrees(&self) -> Vec<usize>;
+    fn out_degree_distribution(&self) -> Vec<usize> {
+        distribution(&self.out_degrees())
+    }
+    fn in_degree_distribution(&self) -> Vec<usize> {
+        distribution(&self.in_degrees())
+    }
+}
+impl<G: Graph> HasCentrality for G {
+    fn pagerank(&self, damping: f64, tolerance: f64) -> Vec<f64> {
+        pagerank(self, damping, tolerance)
+    }
+    fn betweenness(&self) -> Vec<f64> {
+        let mut cb = vec![0.0; self.v_size()];
+        let mut b = Brandes::new(self.v_size());
+        for s in 0..self.v_size() {
+            b.bfs(self, s);
+            b.accumulate(s, &mut cb);
+        }
+        cb
+    }
+    fn closeness(&self) -> Vec<f64> {
+        (0..self.v_size())
+            .map(|v| {
+                let dist = bfs_dist(self, v);
+                let (n, sum) = dist
+                    .iter()
+                    .filter_map(|d| *d)
+                    .fold((0, 0), |(n, sum), d| (n + 1, sum + d));
+                if sum == 0 {
+                    0.0
+                } else {
+                    (n - 1) as f64 / sum as f64
+                }
+            })
+            .collect()
+    }
+    fn harmonic(&self) -> Vec<f64> {
+        (0..self.v_size())
+            .map(|v| {
+                bfs_dist(self, v)
+                    .iter()
+                    .filter_map(|d| *d)
+                    .filter(|&d| d > 0)
+                    .map(|d| 1.0 / d as f64)
+                    .sum()
+            })
+            .collect()
+    }
+
+    fn out_degrees(&self) -> Vec<usize> {
+        (0..self.v_size()).map(|v| self.adj(v).count()).collect()
+    }
+    fn in_degrees(&self) -> Vec<usize> {
+        let mut degrees = vec![0; self.v_size()];
+        for v in 0..self.v_size() {
+            for e in self.adj(v) {
+                degrees[e.other(v)] += 1;
+            }
+        }
+        degrees
+    }
+}
+
+/// HasWeightedCentrality
+///
+/// Centrality measures taking edge weights as distances, weights must be
+/// non-negative.
+pub trait HasWeightedCentrality {
+    fn weighted_betweenness(&self) -> Vec<f64>;
+}
+impl<G, E> HasWeightedCentrality for G
+where
+    E: NonNegative,
+    G: Graph<Edge = E>,
+{
+    fn weighted_betweenness(&self) -> Vec<f64> {
+        let mut cb = vec![0.0; self.v_size()];
+        let mut b = Brandes::new(self.v_size());
+        for s in 0..self.v_size() {
+            b.dijkstra(self, s);
+            b.accumulate(s, &mut cb);
+        }
+        cb
+    }
+}
+
+// counts[d] is the number of vertices with degree d
+fn distribution(degrees: &[usize]) -> Vec<usize> {
+    let max = degrees.iter().max().map_or(0, |&d| d + 1);
+    let mut counts = vec![0; max];
+    for &d in degrees {
+        counts[d] += 1;
+    }
+    counts
+}
+
+fn bfs_dist<G: Graph>(g: &G, s: Vertex) -> Vec<Option<usize>> {
+    let mut dist = vec![None; g.v_size()];
+    let mut queue = VecDeque::new();
+    dist[s] = Some(0);
+    queue.push_back(s);
+    while let Some(v) = queue.pop_front() {
+        let d = dist[v].unwrap() + 1;
+        for w in g.adj(v).map(|e| e.other(v)) {
+            if dist[w].is_none() {
+                dist[w] = Some(d);
+                queue.push_back(w);
+            }
+        }
+    }
+    dist
+}
+
+// power iteration, rank of dangling vertices is spread to all vertices
+fn pagerank<G: Graph>(g: &G, damping: f64, tolerance: f64) -> Vec<f64> {
+    let n = g.v_size();
+    if n == 0 {
+        return Vec::new();
+    }
+    let out = g.out_degrees();
+    let mut rank = vec![1.0 / n as f64; n];
+    for _ in 0..PAGERANK_MAX_ITERATIONS {
+        let dangling: f64 = (0..n).filter(|&v| out[v] == 0).map(|v| rank[v]).sum();
+        let base = (1.0 - damping) / n as f64 + damping * dangling / n as f64;
+        let mut next = vec![base; n];
+        for v in (0..n).filter(|&v| out[v] > 0) {
+            let share = damping * rank[v] / out[v] as f64;
+            for e in g.adj(v) {
+                next[e.other(v)] += share;
+            }
+        }
+
+        let diff: f64 = next
+            .iter()
+            .zip(rank.iter())
+            .map(|(a, b)| (a - b).abs())
+            .sum();
+        rank = next;
+        if diff < tolerance {
+            break;
+        }
+    }
+    rank
+}
+
+/// Brandes' algorithm
+///
+/// Single source shortest paths from s record the number of shortest paths
+/// `sigma[]` and predecessors on them, then dependencies are accumulated
+/// back in non-increasing distance order (the `stack`).
+struct Brandes {
+    stack: Vec<Vertex>,
+    preds: Vec<Vec<Vertex>>,
+    sigma: Vec<f64>,
+    delta: Vec<f64>,
+}
+impl Brandes {
+    fn new(v: usize) -> Self {
+        Brandes {
+            stack: Vec::with_capacity(v),
+            preds: vec![Vec::new(); v],
+            sigma: vec![0.0; v],
+            delta: vec![0.0; v],
+        }
+    }
+
+    fn reset(&mut self, s: Vertex) {
+        self.stack.clear();
+        for p in self.preds.iter_mut() {
+            p.clear();
+        }
+        for x in self.sigma.iter_mut() {
+            *x = 0.0;
+        }
+        self.sigma[s] = 1.0;
+    }
+
+    fn bfs<G: Graph>(&mut self, g: &G, s: Vertex) {
+        self.reset(s);
+        let mut dist = vec![None; g.v_size()];
+        let mut queue = VecDeque::new();
+        dist[s] = Some(0);
+        queue.push_back(s);
+        while let Some(v) = queue.pop_front() {
+            self.stack.push(v);
+            let d = dist[v].unwrap() + 1;
+            for w in g.adj(v).map(|e| e.other(v)) {
+                if dist[w].is_none() {
+                    dist[w] = Some(d);
+                    queue.push_back(w);
+                }
+                if dist[w] == Some(d) {
+                    self.sigma[w] += self.sigma[v];
+                    self.preds[w].push(v);
+                }
+            }
+        }
+    }
+
+    fn dijkstra<G, E>(&mut self, g: &G, s: Vertex)
+    where
+        E: NonNegative,
+        G: Graph<Edge = E>,
+    {
+        self.reset(s);
+        let mut dist = vec![f64::INFINITY; g.v_size()];
+        let mut pq = IndexMinPQ::new(g.v_size());
+        dist[s] = 0.0;
+        pq.upsert(s, 0.0);
+        while let Some((v, _)) = pq.pop() {
+            self.stack.push(v);
+            for e in g.adj(v) {
+                let w = e.other(v);
+                let d = dist[v] + e.weight();
+                if d < dist[w] {
+                    dist[w] = d;
+                    pq.upsert(w, d);
+                    self.sigma[w] = 0.0;
+                    self.preds[w].clear();
+                }
+                if d == dist[w] {
+                    self.sigma[w] += self.sigma[v];
+                    self.preds[w].push(v);
+                }
+            }
+        }
+    }
+
+    fn accumulate(&mut self, s: Vertex, cb: &mut [f64]) {
+        for x in self.delta.iter_mut() {
+            *x = 0.0;
+        }
+        while let Some(w) = self.stack.pop() {
+            for &v in self.preds[w].iter() {
+                self.delta[v] += self.sigma[v] / self.sigma[w] * (1.0 + self.delta[w]);
+            }
+            if w != s {
+                cb[w] += self.delta[w];
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::DirectedGraph;
+    use super::super::EdgeNonNegativeWeightedDirectedGraph as ENNWDG;
+    use super::super::NonNegativeWeightedDirectedEdge as NNWDE;
+    use super::super::UndirectedGraph;
+    use super::*;
+
+    fn round(a: &[f64]) -> Vec<isize> {
+        a.iter().map(|x| (x * 100.0).round() as isize).collect()
+    }
+
+    #[test]
+    fn empty() {
+        let g = UndirectedGraph::new(0);
+        assert!(g.pagerank(0.85, 1e-9).is_empty());
+        assert!(g.betweenness().is_empty());
+        assert!(g.closeness().is_empty());
+        assert!(g.out_degree_distribution().is_empty());
+
+        let g = UndirectedGraph::new(2);
+        assert_eq!(vec![50, 50], round(&g.pagerank(0.85, 1e-9)));
+        assert_eq!(vec![0, 0], round(&g.betweenness()));
+        assert_eq!(vec![0, 0], round(&g.closeness()));
+        assert_eq!(vec![0, 0], round(&g.harmonic()));
+        assert_eq!(vec![2], g.in_degree_distribution());
+    }
+
+    #[test]
+    fn pagerank_cycle() {
+        let mut g = DirectedGraph::new(4);
+        for v in 0..4 {
+            g.add_edge(v, (v + 1) % 4);
+        }
+        assert_eq!(vec![25, 25, 25, 25], round(&g.pagerank(0.85, 1e-9)));
+    }
+
+    #[test]
+    fn pagerank_dangling() {
+        let mut g = DirectedGraph::new(3);
+        g.add_edge(0, 2);
+        g.add_edge(1, 2);
+        let r = g.pagerank(0.85, 1e-12);
+        assert_eq!(100, (r.iter().sum::<f64>() * 100.0).round() as isize);
+        assert!(r[2] > r[0]);
+        assert_eq!(round(&r[..1]), round(&r[1..2]));
+        // r0 = (1-d)/3 + d*r2/3, r2 = r0 + 2*d*r0
+        let r0: f64 = 1.0 / (1.0 + 1.0 + 1.0 + 2.0 * 0.85);
+        assert_eq!((r0 * 1e6).round(), (r[0] * 1e6).round());
+    }
+
+    #[test]
+    fn betweenness_path() {
+        let mut g = UndirectedGraph::new(4);
+        g.add_edge(0, 1);
+        g.add_edge(1, 2);
+        g.add_edge(2, 3);
+        // ordered pairs: (0,2),(0,3),(2,0),(3,0) pass 1
+        assert_eq!(vec![0, 400, 400, 0], round(&g.betweenness()));
+
+        let mut g = DirectedGraph::new(3);
+        g.add_edge(0, 1);
+        g.add_edge(1, 2);
+        assert_eq!(vec![0, 100, 0], round(&g.betweenness()));
+    }
+
+    #[test]
+    fn betweenness_star_and_square() {
+        let mut g = UndirectedGraph::new(5);
+        for v in 1..5 {
+            g.add_edge(0, v);
+        }
+        assert_eq!(vec![1200, 0, 0, 0, 0], round(&g.betweenness()));
+
+        // two shortest paths between opposite corners
+        let mut g = UndirectedGraph::new(4);
+        g.add_edge(0, 1);
+        g.add_edge(1, 3);
+        g.add_edge(3, 2);
+        g.add_edge(2, 0);
+        assert_eq!(vec![100, 100, 100, 100], round(&g.betweenness()));
+    }
+
+    #[test]
+    fn weighted_betweenness() {
+        // both directions of each edge, i.e. an undirected graph
+        let graph = |n, edges: &[(usize, usize, f64)]| {
+            let mut g = ENNWDG::new(n);
+            for &(v, w, weight) in edges {
+                g.add_edge(NNWDE::new(v, w, weight).unwrap());
+                g.add_edge(NNWDE::new(w, v, weight).unwrap());
+            }
+            g
+        };
+
+        let g = graph(3, &[(0, 1, 1.0), (1, 2, 1.0), (0, 2, 3.0)]);
+        assert_eq!(vec![0, 200, 0], round(&g.weighted_betweenness()));
+        assert_eq!(vec![0, 0, 0], round(&g.betweenness()));
+
+        let g = graph(4, &[(0, 1, 0.5), (1, 3, 1.5), (3, 2, 1.0), (2, 0, 1.0)]);
+        // 0-3 has two shortest paths of 2.0
+        assert_eq!(vec![200, 100, 100, 0], round(&g.weighted_betweenness()));
+    }
+
+    #[test]
+    fn closeness_harmonic() {
+        let mut g = UndirectedGraph::new(4);
+        g.add_edge(0, 1);
+        g.add_edge(1, 2);
+        assert_eq!(vec![67, 100, 67, 0], round(&g.closeness()));
+        assert_eq!(vec![150, 200, 150, 0], round(&g.harmonic()));
+
+        let mut g = DirectedGraph::new(3);
+        g.add_edge(0, 1);
+        g.add_edge(1, 2);
+        assert_eq!(vec![67, 100, 0], round(&g.closeness()));
+        assert_eq!(vec![150, 100, 0], round(&g.harmonic()));
+    }
+
+    #[test]
+    fn degrees() {
+        let mut g = DirectedGraph::new(4);
+        g.add_edge(0, 1);
+        g.add_edge(0, 2);
+        g.add_edge(0, 3);
+        g.add_edge(1, 2);
+        assert_eq!(vec![3, 1, 0, 0], g.out_degrees());
+        assert_eq!(vec![0, 1, 2, 1], g.in_degrees());
+        assert_eq!(vec![2, 1, 0, 1], g.out_degree_distribution());
+        assert_eq!(vec![1, 2, 1], g.in_degree_distribution());
+
+        let mut g = UndirectedGraph::new(3);
+        g.add_edge(0, 1);
+        g.add_edge(0, 2);
+        assert_eq!(g.out_degrees(), g.in_degrees());
+        assert_eq!(vec![0, 2, 1], g.out_degree_distribution());
+    }
+}
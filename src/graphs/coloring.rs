@@ -0,0 +1,429 @@
+//! Graph Coloring
+//!
+//! Assigns colors `0..count` to vertices of an undirected graph, so that no
+//! two adjacent vertices share a color. Greedy coloring with several vertex
+//! orderings is fast, the exact backtracking colorer is for small graphs.
+//!
+//! A vertex with a self-loop can never be colored properly: greedy coloring
+//! ignores self-loops, exact coloring and k-colorability report failure.
+use super::base::*;
+
+use std::collections::BTreeSet;
+
+/// Order of vertices to be colored greedily
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColoringOrder {
+    /// vertices in index order
+    Natural,
+    /// vertices in non-increasing degree order
+    LargestFirst,
+    /// repeatedly remove a vertex of minimum degree, color in reversed order
+    SmallestLast,
+    /// next vertex has the most distinct colors among neighbors (saturation),
+    /// ties broken by degree
+    DSatur,
+}
+
+/// HasColoring
+///
+/// Undirected graph can be colored.
+pub trait HasColoring {
+    fn greedy_coloring(&self, order: ColoringOrder) -> Coloring;
+    fn exact_coloring(&self) -> Option<Coloring>;
+    fn is_k_colorable(&self, k: usize) -> bool;
+    /// lower bound by a greedy clique, upper bound by greedy colorings
+    fn chromatic_bounds(&self) -> (usize, usize);
+}
+impl<G> HasColoring for G
+where
+    G::Edge: Undirected,
+    G: Graph,
+{
+    fn greedy_coloring(&self, order: ColoringOrder) -> Coloring {
+        Neighbors::new(self).greedy(order)
+    }
+    fn exact_coloring(&self) -> Option<Coloring> {
+        Neighbors::new(self).exact()
+    }
+    fn is_k_colorable(&self, k: usize) -> bool {
+        let nb = Neighbors::new(self);
+        !nb.self_loop && nb.k_coloring(k).is_some()
+    }
+    fn chromatic_bounds(&self) -> (usize, usize) {
+        let nb = Neighbors::new(self);
+        let upper = [
+            ColoringOrder::Natural,
+            ColoringOrder::LargestFirst,
+            ColoringOrder::SmallestLast,
+            ColoringOrder::DSatur,
+        ]
+        .iter()
+        .map(|&o| nb.greedy(o).count())
+        .min()
+        .unwrap();
+        (nb.clique_size().min(upper), upper)
+    }
+}
+
+/// Coloring
+///
+/// `colors[]` contains color of each vertex, in `0..count`.
+#[derive(Debug, Clone)]
+pub struct Coloring {
+    colors: Vec<usize>,
+    count: usize,
+}
+impl Coloring {
+    fn new(colors: Vec<usize>) -> Self {
+        let count = colors.iter().max().map_or(0, |c| c + 1);
+        Coloring { colors, count }
+    }
+
+    pub fn count(&self) -> usize {
+        self.count
+    }
+    pub fn color(&self, v: usize) -> usize {
+        self.colors[v]
+    }
+    pub fn colors(&self) -> &[usize] {
+        &self.colors
+    }
+}
+
+/// Checks each vertex has a color, and no edge joins two vertices of the
+/// same color (so a self-loop makes it improper).
+pub fn is_proper_coloring<G: Graph>(g: &G, colors: &[usize]) -> bool {
+    colors.len() == g.v_size()
+        && g.edges().all(|e| {
+            let (v, w) = e.vertices();
+            colors[v] != colors[w]
+        })
+}
+
+/// Sorted distinct neighbors of each vertex, without self-loops
+struct Neighbors {
+    adj: Vec<Vec<Vertex>>,
+    self_loop: bool,
+}
+impl Neighbors {
+    fn new<G: Graph>(g: &G) -> Self {
+        let mut self_loop = false;
+        let adj = (0..g.v_size())
+            .map(|v| {
+                let mut adj = g
+                    .adj(v)
+                    .map(|e| e.other(v))
+                    .filter(|&w| {
+                        self_loop |= w == v;
+                        w != v
+                    })
+                    .collect::<Vec<_>>();
+                adj.sort_unstable();
+                adj.dedup();
+                adj
+            })
+            .collect();
+        Neighbors { adj, self_loop }
+    }
+
+    fn len(&self) -> usize {
+        self.adj.len()
+    }
+    fn degree(&self, v: Vertex) -> usize {
+        self.adj[v].len()
+    }
+
+    fn greedy(&self, order: ColoringOrder) -> Coloring {
+        match order {
+            ColoringOrder::Natural => self.greedy_in((0..self.len()).collect()),
+            ColoringOrder::LargestFirst => {
+                let mut vs = (0..self.len()).collect::<Vec<_>>();
+                vs.sort_by_key(|&v| std::cmp::Reverse(self.degree(v)));
+                self.greedy_in(vs)
+            }
+            ColoringOrder::SmallestLast => self.greedy_in(self.smallest_last()),
+            ColoringOrder::DSatur => self.dsatur(),
+        }
+    }
+
+    // color vertices in order with the smallest color unused by neighbors
+    fn greedy_in(&self, order: Vec<Vertex>) -> Coloring {
+        let mut colors: Vec<Option<usize>> = vec![None; self.len()];
+        for v in order {
+            colors[v] = Some(self.smallest_free(v, &colors));
+        }
+        Coloring::new(colors.into_iter().map(Option::unwrap).collect())
+    }
+
+    fn smallest_free(&self, v: Vertex, colors: &[Option<usize>]) -> usize {
+        let mut used = vec![false; self.degree(v) + 1];
+        for c in self.adj[v].iter().filter_map(|&w| colors[w]) {
+            if c < used.len() {
+                used[c] = true;
+            }
+        }
+        used.iter().position(|&u| !u).unwrap()
+    }
+
+    fn smallest_last(&self) -> Vec<Vertex> {
+        let mut degree = (0..self.len()).map(|v| self.degree(v)).collect::<Vec<_>>();
+        let mut queue = (0..self.len())
+            .map(|v| (degree[v], v))
+            .collect::<BTreeSet<_>>();
+        let mut removed = vec![false; self.len()];
+        let mut order = Vec::with_capacity(self.len());
+        while let Some(&(d, v)) = queue.iter().next() {
+            queue.remove(&(d, v));
+            removed[v] = true;
+            order.push(v);
+            for &w in self.adj[v].iter().filter(|&&w| !removed[w]) {
+                queue.remove(&(degree[w], w));
+                degree[w] -= 1;
+                queue.insert((degree[w], w));
+            }
+        }
+        order.reverse();
+        order
+    }
+
+    fn dsatur(&self) -> Coloring {
+        let mut colors: Vec<Option<usize>> = vec![None; self.len()];
+        let mut saturation: Vec<BTreeSet<usize>> = vec![BTreeSet::new(); self.len()];
+        for _ in 0..self.len() {
+            let v = (0..self.len())
+                .filter(|&v| colors[v].is_none())
+                .max_by_key(|&v| {
+                    // prefer lower index on ties
+                    (saturation[v].len(), self.degree(v), std::cmp::Reverse(v))
+                })
+                .unwrap();
+            let c = self.smallest_free(v, &colors);
+            colors[v] = Some(c);
+            for &w in self.adj[v].iter() {
+                saturation[w].insert(c);
+            }
+        }
+        Coloring::new(colors.into_iter().map(Option::unwrap).collect())
+    }
+
+    // a maximal clique grown greedily from each vertex, in degree order
+    fn clique_size(&self) -> usize {
+        let mut best = 0;
+        for v in 0..self.len() {
+            let mut candidates = self.adj[v].clone();
+            candidates.sort_by_key(|&w| std::cmp::Reverse(self.degree(w)));
+            let mut clique = vec![v];
+            for w in candidates {
+                if clique
+                    .iter()
+                    .all(|&u| self.adj[w].binary_search(&u).is_ok())
+                {
+                    clique.push(w);
+                }
+            }
+            best = best.max(clique.len());
+        }
+        best
+    }
+
+    // try k = lower bound, ..., until colorable, DSatur bounds the search
+    fn exact(&self) -> Option<Coloring> {
+        if self.self_loop {
+            return None;
+        }
+        let upper = self.dsatur();
+        for k in self.clique_size()..upper.count() {
+            if let Some(c) = self.k_coloring(k) {
+                return Some(c);
+            }
+        }
+        Some(upper)
+    }
+
+    fn k_coloring(&self, k: usize) -> Option<Coloring> {
+        if self.len() == 0 {
+            return Some(Coloring::new(Vec::new()));
+        }
+        // color the hard vertices first
+        let order = self.dsatur_order();
+        let mut colors = vec![None; self.len()];
+        if self.backtrack(&order, 0, k, 0, &mut colors) {
+            Some(Coloring::new(
+                colors.into_iter().map(Option::unwrap).collect(),
+            ))
+        } else {
+            None
+        }
+    }
+
+    fn dsatur_order(&self) -> Vec<Vertex> {
+        let c = self.dsatur();
+        let mut order = (0..self.len()).collect::<Vec<_>>();
+        order.sort_by_key(|&v| c.color(v));
+        order
+    }
+
+    // colors above `used` are interchangeable, only try one of them
+    fn backtrack(
+        &self,
+        order: &[Vertex],
+        i: usize,
+        k: usize,
+        used: usize,
+        colors: &mut Vec<Option<usize>>,
+    ) -> bool {
+        if i == order.len() {
+            return true;
+        }
+        let v = order[i];
+        for c in 0..k.min(used + 1) {
+            if self.adj[v].iter().any(|&w| colors[w] == Some(c)) {
+                continue;
+            }
+            colors[v] = Some(c);
+            if self.backtrack(order, i + 1, k, used.max(c + 1), colors) {
+                return true;
+            }
+        }
+        colors[v] = None;
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::UndirectedGraph;
+    use super::*;
+
+    const ORDERS: [ColoringOrder; 4] = [
+        ColoringOrder::Natural,
+        ColoringOrder::LargestFirst,
+        ColoringOrder::SmallestLast,
+        ColoringOrder::DSatur,
+    ];
+
+    fn graph(v: usize, edges: &[(usize, usize)]) -> UndirectedGraph {
+        let mut g = UndirectedGraph::new(v);
+        for &(v, w) in edges {
+            g.add_edge(v, w);
+        }
+        g
+    }
+
+    fn cycle(n: usize) -> UndirectedGraph {
+        let edges = (0..n).map(|i| (i, (i + 1) % n)).collect::<Vec<_>>();
+        graph(n, &edges)
+    }
+
+    fn petersen() -> UndirectedGraph {
+        let mut edges = Vec::new();
+        for i in 0..5 {
+            edges.push((i, (i + 1) % 5));
+            edges.push((i, i + 5));
+            edges.push((i + 5, (i + 2) % 5 + 5));
+        }
+        graph(10, &edges)
+    }
+
+    // bipartite, natural order greedy needs n colors
+    fn crown(n: usize) -> UndirectedGraph {
+        let mut g = UndirectedGraph::new(2 * n);
+        for i in 0..n {
+            for j in 0..n {
+                if i != j {
+                    g.add_edge(2 * i, 2 * j + 1);
+                }
+            }
+        }
+        g
+    }
+
+    #[test]
+    fn empty() {
+        let g = UndirectedGraph::new(0);
+        assert_eq!(0, g.exact_coloring().unwrap().count());
+        assert!(g.is_k_colorable(0));
+        assert_eq!((0, 0), g.chromatic_bounds());
+
+        let g = UndirectedGraph::new(3);
+        for &o in ORDERS.iter() {
+            assert_eq!(1, g.greedy_coloring(o).count());
+        }
+        assert_eq!(1, g.exact_coloring().unwrap().count());
+        assert!(!g.is_k_colorable(0));
+        assert!(g.is_k_colorable(1));
+    }
+
+    #[test]
+    fn greedy_proper() {
+        for g in [cycle(5), cycle(6), petersen(), crown(4)].iter() {
+            for &o in ORDERS.iter() {
+                let c = g.greedy_coloring(o);
+                assert!(is_proper_coloring(g, c.colors()));
+                assert!(c.count() <= 4);
+            }
+        }
+    }
+
+    #[test]
+    fn crown_orders() {
+        let g = crown(4);
+        assert_eq!(4, g.greedy_coloring(ColoringOrder::Natural).count());
+        assert_eq!(2, g.greedy_coloring(ColoringOrder::DSatur).count());
+        assert_eq!(2, g.greedy_coloring(ColoringOrder::SmallestLast).count());
+        assert_eq!(2, g.exact_coloring().unwrap().count());
+    }
+
+    #[test]
+    fn exact() {
+        assert_eq!(2, cycle(6).exact_coloring().unwrap().count());
+        assert_eq!(3, cycle(7).exact_coloring().unwrap().count());
+
+        let g = petersen();
+        let c = g.exact_coloring().unwrap();
+        assert_eq!(3, c.count());
+        assert!(is_proper_coloring(&g, c.colors()));
+        assert!(!g.is_k_colorable(2));
+        assert!(g.is_k_colorable(3));
+
+        let mut k4 = UndirectedGraph::new(4);
+        for v in 0..4 {
+            for w in (v + 1)..4 {
+                k4.add_edge(v, w);
+            }
+        }
+        assert_eq!(4, k4.exact_coloring().unwrap().count());
+        assert!(!k4.is_k_colorable(3));
+        assert_eq!((4, 4), k4.chromatic_bounds());
+    }
+
+    #[test]
+    fn bounds() {
+        let (lower, upper) = petersen().chromatic_bounds();
+        assert_eq!(2, lower);
+        assert_eq!(3, upper);
+
+        let (lower, upper) = crown(5).chromatic_bounds();
+        assert_eq!(2, lower);
+        assert_eq!(2, upper);
+    }
+
+    #[test]
+    fn self_loop() {
+        let g = graph(2, &[(0, 1), (1, 1)]);
+        assert!(g.exact_coloring().is_none());
+        assert!(!g.is_k_colorable(3));
+
+        let c = g.greedy_coloring(ColoringOrder::Natural);
+        assert_eq!(2, c.count());
+        assert!(!is_proper_coloring(&g, c.colors()));
+    }
+
+    #[test]
+    fn improper() {
+        let g = cycle(3);
+        assert!(is_proper_coloring(&g, &[0, 1, 2]));
+        assert!(!is_proper_coloring(&g, &[0, 1, 1]));
+        assert!(!is_proper_coloring(&g, &[0, 1]));
+    }
+}
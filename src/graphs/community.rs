@@ -0,0 +1,393 @@
+//! Community Detection
+//!
+//! Partitions an undirected graph into densely connected groups of vertices,
+//! rated by modularity: the fraction of edge weights inside communities minus
+//! the expected fraction if edges were placed at random with same degrees.
+use super::base::*;
+use super::{EdgeWeightedUndirectedGraph, UndirectedGraph};
+
+use rand::{Rng, SeedableRng, StdRng};
+use std::collections::BTreeMap;
+
+const LABEL_PROPAGATION_MAX_ITERATIONS: usize = 100;
+// moves gaining less than this are float noise
+const LOUVAIN_MIN_GAIN: f64 = 1e-12;
+
+/// HasCommunities
+///
+/// Undirected graphs can be partitioned into communities, edges of
+/// unweighted graphs weigh 1.0.
+pub trait HasCommunities {
+    fn label_propagation(&self, seed: usize) -> Communities;
+    fn louvain(&self) -> Communities;
+}
+impl HasCommunities for UndirectedGraph {
+    fn label_propagation(&self, seed: usize) -> Communities {
+        let net = Network::new(self, |_| 1.0);
+        Communities::new(&net, label_propagation(&net, seed))
+    }
+    fn louvain(&self) -> Communities {
+        let net = Network::new(self, |_| 1.0);
+        Communities::new(&net, louvain(&net))
+    }
+}
+impl HasCommunities for EdgeWeightedUndirectedGraph {
+    fn label_propagation(&self, seed: usize) -> Communities {
+        let net = Network::new(self, |e| e.weight());
+        Communities::new(&net, label_propagation(&net, seed))
+    }
+    fn louvain(&self) -> Communities {
+        let net = Network::new(self, |e| e.weight());
+        Communities::new(&net, louvain(&net))
+    }
+}
+
+/// Communities
+///
+/// Same as `CC`, `ids[]` contains community id of each vertex, `sizes[]`
+/// contains size of each community, plus the modularity of the partition.
+pub struct Communities {
+    ids: Vec<usize>,
+    sizes: Vec<usize>,
+    modularity: f64,
+}
+impl Communities {
+    // relabel communities by the order of their first vertex
+    fn new(net: &Network, labels: Vec<usize>) -> Self {
+        let mut c = Communities {
+            ids: vec![0; labels.len()],
+            sizes: Vec::new(),
+            modularity: 0.0,
+        };
+        let mut relabel = vec![None; labels.len()];
+        for (v, &l) in labels.iter().enumerate() {
+            let id = *relabel[l].get_or_insert_with(|| {
+                c.sizes.push(0);
+                c.sizes.len() - 1
+            });
+            c.ids[v] = id;
+            c.sizes[id] += 1;
+        }
+        c.modularity = net.modularity(&c.ids, c.count());
+        c
+    }
+
+    pub fn count(&self) -> usize {
+        self.sizes.len()
+    }
+    pub fn size(&self, v: usize) -> usize {
+        self.sizes[self.ids[v]]
+    }
+
+    pub fn id(&self, v: usize) -> usize {
+        self.ids[v]
+    }
+    pub fn connected(&self, v: usize, w: usize) -> bool {
+        self.ids[v] == self.ids[w]
+    }
+    pub fn modularity(&self) -> f64 {
+        self.modularity
+    }
+}
+
+/// Weighted adjacency lists, each undirected edge appears at both ends, and a
+/// self-loop twice at its vertex, so `m2` (sum of all) is twice total weight.
+#[derive(Clone)]
+struct Network {
+    adj: Vec<Vec<(Vertex, f64)>>,
+    m2: f64,
+}
+impl Network {
+    fn new<G, E, F>(g: &G, weight: F) -> Self
+    where
+        E: Undirected,
+        G: Graph<Edge = E>,
+        F: Fn(&E) -> f64,
+    {
+        let adj = (0..g.v_size())
+            .map(|v| g.adj(v).map(|e| (e.other(v), weight(&e))).collect())
+            .collect::<Vec<Vec<_>>>();
+        Self::from_adj(adj)
+    }
+    fn from_adj(adj: Vec<Vec<(Vertex, f64)>>) -> Self {
+        let m2 = adj.iter().flatten().map(|(_, w)| w).sum();
+        Network { adj, m2 }
+    }
+
+    fn degree(&self, v: Vertex) -> f64 {
+        self.adj[v].iter().map(|(_, w)| w).sum()
+    }
+
+    // Q = sum(in_c / 2m - (tot_c / 2m)^2)
+    fn modularity(&self, ids: &[usize], count: usize) -> f64 {
+        if self.m2 == 0.0 {
+            return 0.0;
+        }
+        let mut inside = vec![0.0; count];
+        let mut tot = vec![0.0; count];
+        for (v, adj) in self.adj.iter().enumerate() {
+            for &(w, weight) in adj {
+                tot[ids[v]] += weight;
+                if ids[v] == ids[w] {
+                    inside[ids[v]] += weight;
+                }
+            }
+        }
+        inside
+            .iter()
+            .zip(tot.iter())
+            .map(|(i, t)| i / self.m2 - (t / self.m2) * (t / self.m2))
+            .sum()
+    }
+
+    // total weights from v to each neighboring community, self-loops excluded
+    fn neighbor_weights(&self, v: Vertex, labels: &[usize]) -> BTreeMap<usize, f64> {
+        let mut weights = BTreeMap::new();
+        for &(w, weight) in self.adj[v].iter().filter(|(w, _)| *w != v) {
+            *weights.entry(labels[w]).or_insert(0.0) += weight;
+        }
+        weights
+    }
+}
+
+/// Label propagation
+///
+/// Every vertex starts with its own label, then repeatedly adopts the label
+/// with maximum total weight among its neighbors, in a random order, until no
+/// label changes. Ties prefer the current label, otherwise are broken randomly.
+fn label_propagation(net: &Network, seed: usize) -> Vec<usize> {
+    let n = net.adj.len();
+    let mut labels = (0..n).collect::<Vec<_>>();
+    let mut order = (0..n).collect::<Vec<_>>();
+    let mut rng = StdRng::from_seed(&[seed][..]);
+
+    for _ in 0..LABEL_PROPAGATION_MAX_ITERATIONS {
+        rng.shuffle(&mut order);
+        let mut changed = false;
+        for &v in order.iter() {
+            let weights = net.neighbor_weights(v, &labels);
+            let max = weights.values().cloned().fold(f64::NEG_INFINITY, f64::max);
+            let best = weights
+                .iter()
+                .filter(|(_, &w)| w == max)
+                .map(|(&l, _)| l)
+                .collect::<Vec<_>>();
+            if best.is_empty() || best.contains(&labels[v]) {
+                continue;
+            }
+            labels[v] = best[rng.gen_range(0, best.len())];
+            changed = true;
+        }
+        if !changed {
+            break;
+        }
+    }
+
+    labels
+}
+
+/// Louvain method
+///
+/// Phase 1: move each vertex to the neighboring community with maximum
+/// modularity gain, until no move improves.
+/// Phase 2: collapse communities into vertices, and repeat on the new network
+/// until phase 1 moves nothing.
+fn louvain(net: &Network) -> Vec<usize> {
+    let mut labels = (0..net.adj.len()).collect::<Vec<_>>();
+    let mut net = net.clone();
+    while let Some((community, count)) = one_level(&net) {
+        for l in labels.iter_mut() {
+            *l = community[*l];
+        }
+        net = aggregate(&net, &community, count);
+    }
+
+    labels
+}
+
+// returns communities renumbered as 0..count, None if no vertex moved
+fn one_level(net: &Network) -> Option<(Vec<usize>, usize)> {
+    let n = net.adj.len();
+    if net.m2 == 0.0 {
+        return None;
+    }
+    let k = (0..n).map(|v| net.degree(v)).collect::<Vec<_>>();
+    let mut community = (0..n).collect::<Vec<_>>();
+    let mut tot = k.clone();
+
+    let mut improved = false;
+    loop {
+        let mut moved = false;
+        for v in 0..n {
+            let old = community[v];
+            let weights = net.neighbor_weights(v, &community);
+            tot[old] -= k[v];
+
+            // gain of joining c: k_v,in(c) - tot(c) * k_v / 2m
+            let gain = |c: usize| weights.get(&c).cloned().unwrap_or(0.0) - tot[c] * k[v] / net.m2;
+            let mut best = old;
+            let mut best_gain = gain(old);
+            for &c in weights.keys() {
+                if gain(c) > best_gain + LOUVAIN_MIN_GAIN {
+                    best = c;
+                    best_gain = gain(c);
+                }
+            }
+
+            tot[best] += k[v];
+            community[v] = best;
+            if best != old {
+                moved = true;
+                improved = true;
+            }
+        }
+        if !moved {
+            break;
+        }
+    }
+    if !improved {
+        return None;
+    }
+
+    let mut relabel = vec![None; n];
+    let mut count = 0;
+    for c in community.iter_mut() {
+        *c = *relabel[*c].get_or_insert_with(|| {
+            count += 1;
+            count - 1
+        });
+    }
+    Some((community, count))
+}
+
+// communities become vertices, edges inside a community become a self-loop
+fn aggregate(net: &Network, community: &[usize], count: usize) -> Network {
+    let mut adj = vec![BTreeMap::new(); count];
+    for (v, edges) in net.adj.iter().enumerate() {
+        for &(w, weight) in edges {
+            *adj[community[v]].entry(community[w]).or_insert(0.0) += weight;
+        }
+    }
+    Network::from_adj(adj.into_iter().map(|m| m.into_iter().collect()).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::WeightedUndirectedEdge as WE;
+    use super::*;
+
+    fn triangles(bridge: bool) -> UndirectedGraph {
+        let mut g = UndirectedGraph::new(6);
+        g.add_edge(0, 1);
+        g.add_edge(1, 2);
+        g.add_edge(2, 0);
+        g.add_edge(3, 4);
+        g.add_edge(4, 5);
+        g.add_edge(5, 3);
+        if bridge {
+            g.add_edge(2, 3);
+        }
+        g
+    }
+
+    fn assert_triangles(c: &Communities) {
+        assert_eq!(2, c.count());
+        assert_eq!(0, c.id(0));
+        assert_eq!(1, c.id(5));
+        assert_eq!(3, c.size(0));
+        assert_eq!(3, c.size(3));
+        assert!(c.connected(0, 1));
+        assert!(c.connected(0, 2));
+        assert!(c.connected(3, 4));
+        assert!(c.connected(3, 5));
+        assert!(!c.connected(2, 3));
+    }
+
+    #[test]
+    fn empty() {
+        let g = UndirectedGraph::new(3);
+        for c in [g.label_propagation(1), g.louvain()].iter() {
+            assert_eq!(3, c.count());
+            assert_eq!(0, c.id(0));
+            assert_eq!(1, c.id(1));
+            assert_eq!(2, c.id(2));
+            assert_eq!(1, c.size(2));
+            assert_eq!(0, (c.modularity() * 1000.0).round() as isize);
+        }
+    }
+
+    #[test]
+    fn one_edge() {
+        let mut g = UndirectedGraph::new(2);
+        g.add_edge(0, 1);
+        let c = g.louvain();
+        assert_eq!(1, c.count());
+        assert_eq!(0, (c.modularity() * 1000.0).round() as isize);
+    }
+
+    #[test]
+    fn louvain_triangles() {
+        let c = triangles(true).louvain();
+        assert_triangles(&c);
+        // 2 * (6/14 - (7/14)^2)
+        assert_eq!(357, (c.modularity() * 1000.0).round() as isize);
+
+        let c = triangles(false).louvain();
+        assert_triangles(&c);
+        assert_eq!(500, (c.modularity() * 1000.0).round() as isize);
+    }
+
+    #[test]
+    fn label_propagation_triangles() {
+        let g = triangles(false);
+        for seed in 0..10 {
+            let c = g.label_propagation(seed);
+            assert_triangles(&c);
+            assert_eq!(500, (c.modularity() * 1000.0).round() as isize);
+        }
+
+        let g = triangles(true);
+        let c = g.label_propagation(42);
+        let d = g.label_propagation(42);
+        assert_eq!(c.ids, d.ids);
+    }
+
+    #[test]
+    fn weighted() {
+        let mut g = EdgeWeightedUndirectedGraph::new(4);
+        g.add_edge(&WE::new(0, 1, 10.0));
+        g.add_edge(&WE::new(1, 2, 0.1));
+        g.add_edge(&WE::new(2, 3, 10.0));
+
+        for c in [g.louvain(), g.label_propagation(7)].iter() {
+            assert_eq!(2, c.count());
+            assert!(c.connected(0, 1));
+            assert!(c.connected(2, 3));
+            assert!(!c.connected(1, 2));
+            assert!(c.modularity() > 0.49);
+        }
+    }
+
+    #[test]
+    fn ring_of_cliques() {
+        // 4 cliques of 4 vertices, linked in a ring
+        let mut g = UndirectedGraph::new(16);
+        for k in 0..4 {
+            for i in 0..4 {
+                for j in (i + 1)..4 {
+                    g.add_edge(k * 4 + i, k * 4 + j);
+                }
+            }
+            g.add_edge(k * 4, ((k + 1) % 4) * 4 + 1);
+        }
+
+        let c = g.louvain();
+        assert_eq!(4, c.count());
+        for v in 0..16 {
+            assert_eq!(v / 4, c.id(v));
+            assert_eq!(4, c.size(v));
+        }
+        // 4 * (12/56 - (14/56)^2)
+        assert_eq!(607, (c.modularity() * 1000.0).round() as isize);
+    }
+}
@@ -11,6 +11,9 @@ impl Cycle {
     fn new() -> Self {
         Cycle::default()
     }
+    pub(super) fn from_path(path: Vec<usize>) -> Self {
+        Cycle { path }
+    }
     fn is_empty(&self) -> bool {
         self.path.is_empty()
     }
@@ -7,6 +7,10 @@ pub struct DirectedGraph {
     adj: Vec<Vec<DirectedEdge>>,
 }
 impl DirectedGraph {
+    pub(super) fn from_adj(e: usize, adj: Vec<Vec<DirectedEdge>>) -> Self {
+        DirectedGraph { e, adj }
+    }
+
     pub fn add_edge(&mut self, v: usize, w: usize) {
         self.adj[v].push(DirectedEdge::new(v, w));
         self.e += 1;
@@ -0,0 +1,347 @@
+//! Dominators
+//!
+//! In a directed graph (e.g. a control-flow graph) with a root, vertex a
+//! dominates b if every path from root to b passes through a. The immediate
+//! dominator of b is its closest strict dominator, they form the dominator
+//! tree.
+//!
+//! Implemented by the iterative algorithm of Cooper, Harvey and Kennedy,
+//! "A Simple, Fast Dominance Algorithm".
+use super::base::*;
+use super::dfs_order::DFSOrder;
+use super::DirectedEdge;
+
+/// HasDominators
+///
+/// Directed graph can have a dominator tree from any root.
+pub trait HasDominators {
+    fn dominators(&self, root: Vertex) -> Dominators;
+}
+impl<G, E> HasDominators for G
+where
+    E: Directed,
+    G: Graph<Edge = E>,
+{
+    fn dominators(&self, root: Vertex) -> Dominators {
+        Dominators::new(self, root)
+    }
+}
+
+/// Dominators
+///
+/// `idom[]` contains immediate dominator of each vertex, None for the root and
+/// unreachable vertices. `pre[]`/`post[]` are DFS numbers on the dominator
+/// tree, a dominates b iff interval of a contains interval of b.
+pub struct Dominators {
+    root: Vertex,
+    idom: Vec<Option<Vertex>>,
+    children: Vec<Vec<Vertex>>,
+    frontiers: Vec<Vec<Vertex>>,
+    pre: Vec<usize>,
+    post: Vec<usize>,
+}
+impl Dominators {
+    fn new<G, E>(g: &G, root: Vertex) -> Self
+    where
+        E: Directed,
+        G: Graph<Edge = E>,
+    {
+        let n = g.v_size();
+        let mut d = Dominators {
+            root,
+            idom: vec![None; n],
+            children: vec![Vec::new(); n],
+            frontiers: vec![Vec::new(); n],
+            pre: vec![0; n],
+            post: vec![0; n],
+        };
+        let rpo = reachable_rpo(g, root);
+        let mut preds = vec![Vec::new(); n];
+        for &v in rpo.iter() {
+            for e in g.adj(v) {
+                preds[e.to()].push(v);
+            }
+        }
+        d.init_idom(&rpo, &preds);
+        d.init_frontiers(&rpo, &preds);
+        d.init_intervals();
+        d
+    }
+
+    pub fn root(&self) -> Vertex {
+        self.root
+    }
+    pub fn is_reachable(&self, v: Vertex) -> bool {
+        v == self.root || self.idom[v].is_some()
+    }
+    pub fn idom(&self, v: Vertex) -> Option<Vertex> {
+        self.idom[v]
+    }
+    /// children of v in the dominator tree
+    pub fn children(&self, v: Vertex) -> impl Iterator<Item = &Vertex> {
+        self.children[v].iter()
+    }
+    /// vertices where v's dominance ends: v dominates a predecessor of w, but
+    /// does not strictly dominate w
+    pub fn frontier(&self, v: Vertex) -> impl Iterator<Item = &Vertex> {
+        self.frontiers[v].iter()
+    }
+
+    /// every vertex dominates itself
+    pub fn dominates(&self, a: Vertex, b: Vertex) -> bool {
+        self.is_reachable(a)
+            && self.is_reachable(b)
+            && self.pre[a] <= self.pre[b]
+            && self.post[b] <= self.post[a]
+    }
+    pub fn strictly_dominates(&self, a: Vertex, b: Vertex) -> bool {
+        a != b && self.dominates(a, b)
+    }
+}
+
+// private methods
+impl Dominators {
+    fn init_idom(&mut self, rpo: &[Vertex], preds: &[Vec<Vertex>]) {
+        let n = self.idom.len();
+        let mut order = vec![n; n];
+        for (i, &v) in rpo.iter().enumerate() {
+            order[v] = i;
+        }
+
+        // root is its own dominator during iterations
+        self.idom[self.root] = Some(self.root);
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for &b in rpo.iter().skip(1) {
+                let mut new_idom = None;
+                for &p in preds[b].iter().filter(|&&p| self.idom[p].is_some()) {
+                    new_idom = match new_idom {
+                        None => Some(p),
+                        Some(q) => Some(self.intersect(&order, p, q)),
+                    };
+                }
+                if self.idom[b] != new_idom {
+                    self.idom[b] = new_idom;
+                    changed = true;
+                }
+            }
+        }
+        self.idom[self.root] = None;
+
+        for &v in rpo.iter().skip(1) {
+            self.children[self.idom[v].unwrap()].push(v);
+        }
+        for c in self.children.iter_mut() {
+            c.sort_unstable();
+        }
+    }
+
+    // nearest common ancestor in the (partial) dominator tree, an ancestor
+    // comes earlier in reversed post-order
+    fn intersect(&self, order: &[usize], mut a: Vertex, mut b: Vertex) -> Vertex {
+        while a != b {
+            while order[a] > order[b] {
+                a = self.idom[a].unwrap();
+            }
+            while order[b] > order[a] {
+                b = self.idom[b].unwrap();
+            }
+        }
+        a
+    }
+
+    // walk up from each predecessor until the idom, the root has no idom so
+    // a back edge to the root puts the root into its own frontier
+    fn init_frontiers(&mut self, rpo: &[Vertex], preds: &[Vec<Vertex>]) {
+        for &b in rpo.iter() {
+            for &p in preds[b].iter() {
+                let mut runner = Some(p);
+                while runner != self.idom[b] {
+                    let r = runner.unwrap();
+                    if self.frontiers[r].last() != Some(&b) {
+                        self.frontiers[r].push(b);
+                    }
+                    runner = self.idom[r];
+                }
+            }
+        }
+        for f in self.frontiers.iter_mut() {
+            f.sort_unstable();
+            f.dedup();
+        }
+    }
+
+    fn init_intervals(&mut self) {
+        let mut counter = 0;
+        let mut stack = vec![(self.root, 0)];
+        self.pre[self.root] = counter;
+        while let Some((v, i)) = stack.pop() {
+            if i < self.children[v].len() {
+                stack.push((v, i + 1));
+                let w = self.children[v][i];
+                counter += 1;
+                self.pre[w] = counter;
+                stack.push((w, 0));
+            } else {
+                counter += 1;
+                self.post[v] = counter;
+            }
+        }
+    }
+}
+
+// vertices reachable from root in reversed post-order, root first
+fn reachable_rpo<G, E>(g: &G, root: Vertex) -> Vec<Vertex>
+where
+    E: Directed,
+    G: Graph<Edge = E>,
+{
+    // DFS starts from vertex 0, so swap the root with 0, then vertices
+    // reachable from the root are the tail of the order
+    let view = Rerooted { g, root };
+    let order = view.reversed_post_order().collect::<Vec<_>>();
+    let i = order.iter().position(|&v| v == 0).unwrap();
+    order[i..].iter().map(|&v| view.swap(v)).collect()
+}
+
+/// A view of the graph with vertex 0 and the root swapped
+#[derive(Clone)]
+struct Rerooted<'a, G> {
+    g: &'a G,
+    root: Vertex,
+}
+impl<'a, G> Rerooted<'a, G> {
+    fn swap(&self, v: Vertex) -> Vertex {
+        if v == 0 {
+            self.root
+        } else if v == self.root {
+            0
+        } else {
+            v
+        }
+    }
+}
+impl<'a, G> Graph for Rerooted<'a, G>
+where
+    G: Graph,
+    G::Edge: Directed,
+{
+    type Edge = DirectedEdge;
+
+    fn v_size(&self) -> usize {
+        self.g.v_size()
+    }
+    fn e_size(&self) -> usize {
+        self.g.e_size()
+    }
+    fn adj(&self, v: usize) -> Box<dyn Iterator<Item = Self::Edge> + '_> {
+        Box::new(
+            self.g
+                .adj(self.swap(v))
+                .map(move |e| DirectedEdge::new(self.swap(e.from()), self.swap(e.to()))),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::DirectedGraph;
+    use super::*;
+
+    fn graph(v: usize, edges: &[(usize, usize)]) -> DirectedGraph {
+        let mut g = DirectedGraph::new(v);
+        for &(v, w) in edges {
+            g.add_edge(v, w);
+        }
+        g
+    }
+
+    fn frontier(d: &Dominators, v: Vertex) -> Vec<Vertex> {
+        d.frontier(v).cloned().collect()
+    }
+
+    #[test]
+    fn empty() {
+        let g = DirectedGraph::new(2);
+        let d = g.dominators(1);
+        assert_eq!(1, d.root());
+        assert_eq!(None, d.idom(1));
+        assert!(d.is_reachable(1));
+        assert!(!d.is_reachable(0));
+        assert!(d.dominates(1, 1));
+        assert!(!d.strictly_dominates(1, 1));
+        assert!(!d.dominates(1, 0));
+    }
+
+    #[test]
+    fn loop_cfg() {
+        // 0 -> 1 -> {2, 3} -> 4 -> {1, 5}
+        let g = graph(6, &[(0, 1), (1, 2), (1, 3), (2, 4), (3, 4), (4, 1), (4, 5)]);
+        let d = g.dominators(0);
+
+        let idom = (0..6).map(|v| d.idom(v)).collect::<Vec<_>>();
+        assert_eq!(
+            vec![None, Some(0), Some(1), Some(1), Some(1), Some(4)],
+            idom
+        );
+        assert_eq!(vec![2, 3, 4], d.children(1).cloned().collect::<Vec<_>>());
+
+        assert!(d.dominates(0, 5));
+        assert!(d.dominates(1, 5));
+        assert!(d.strictly_dominates(4, 5));
+        assert!(!d.dominates(2, 4));
+        assert!(!d.dominates(5, 4));
+
+        assert!(frontier(&d, 0).is_empty());
+        assert_eq!(vec![1], frontier(&d, 1));
+        assert_eq!(vec![4], frontier(&d, 2));
+        assert_eq!(vec![4], frontier(&d, 3));
+        assert_eq!(vec![1], frontier(&d, 4));
+        assert!(frontier(&d, 5).is_empty());
+    }
+
+    #[test]
+    fn irreducible() {
+        // figure 4 in the paper, 0 is unreachable
+        let g = graph(
+            7,
+            &[
+                (6, 5),
+                (6, 4),
+                (5, 1),
+                (4, 2),
+                (4, 3),
+                (1, 2),
+                (2, 1),
+                (2, 3),
+                (3, 2),
+                (0, 6),
+            ],
+        );
+        let d = g.dominators(6);
+        for v in 1..6 {
+            assert_eq!(Some(6), d.idom(v));
+        }
+        assert_eq!(None, d.idom(0));
+        assert!(!d.dominates(6, 0));
+        assert!(!d.dominates(4, 2));
+
+        assert_eq!(vec![1], frontier(&d, 5));
+        assert_eq!(vec![2, 3], frontier(&d, 4));
+        assert_eq!(vec![2], frontier(&d, 1));
+        assert_eq!(vec![1, 3], frontier(&d, 2));
+        assert_eq!(vec![2], frontier(&d, 3));
+    }
+
+    #[test]
+    fn back_edge_to_root() {
+        let g = graph(3, &[(2, 0), (0, 1), (1, 2)]);
+        let d = g.dominators(2);
+        assert_eq!(Some(2), d.idom(0));
+        assert_eq!(Some(0), d.idom(1));
+        assert_eq!(vec![2], frontier(&d, 1));
+        assert_eq!(vec![2], frontier(&d, 2));
+        assert!(d.dominates(0, 1));
+    }
+}
@@ -17,6 +17,11 @@ impl Edge for UndirectedEdge {
         (self.v, self.w)
     }
 }
+impl Relabel for UndirectedEdge {
+    fn relabel(&self, v: Vertex, w: Vertex) -> Self {
+        Self::new(v, w)
+    }
+}
 impl Undirected for UndirectedEdge {}
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -34,6 +39,11 @@ impl Edge for DirectedEdge {
         (self.v, self.w)
     }
 }
+impl Relabel for DirectedEdge {
+    fn relabel(&self, v: Vertex, w: Vertex) -> Self {
+        Self::new(v, w)
+    }
+}
 impl Directed for DirectedEdge {
     fn reversed(&self) -> Self {
         Self::new(self.to(), self.from())
@@ -56,6 +66,11 @@ impl Edge for WeightedUndirectedEdge {
         (self.v, self.w)
     }
 }
+impl Relabel for WeightedUndirectedEdge {
+    fn relabel(&self, v: Vertex, w: Vertex) -> Self {
+        Self::new(v, w, self.weight)
+    }
+}
 impl Undirected for WeightedUndirectedEdge {}
 impl Weighted for WeightedUndirectedEdge {
     fn weight(&self) -> f64 {
@@ -84,6 +99,11 @@ impl Edge for WeightedDirectedEdge {
         (self.v, self.w)
     }
 }
+impl Relabel for WeightedDirectedEdge {
+    fn relabel(&self, v: Vertex, w: Vertex) -> Self {
+        Self::new(v, w, self.weight)
+    }
+}
 impl Directed for WeightedDirectedEdge {
     fn reversed(&self) -> Self {
         Self::new(self.w, self.v, self.weight)
@@ -120,6 +140,15 @@ impl Edge for NonNegativeWeightedDirectedEdge {
         (self.v, self.w)
     }
 }
+impl Relabel for NonNegativeWeightedDirectedEdge {
+    fn relabel(&self, v: Vertex, w: Vertex) -> Self {
+        NonNegativeWeightedDirectedEdge {
+            v,
+            w,
+            weight: self.weight,
+        }
+    }
+}
 impl Directed for NonNegativeWeightedDirectedEdge {
     fn reversed(&self) -> Self {
         NonNegativeWeightedDirectedEdge {
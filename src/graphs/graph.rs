@@ -14,6 +14,10 @@ impl EdgeWeightedUndirectedGraph {
         }
     }
 
+    pub(super) fn from_adj(e: usize, adj: Vec<Vec<WeightedUndirectedEdge>>) -> Self {
+        EdgeWeightedUndirectedGraph { e, adj }
+    }
+
     pub fn add_edge(&mut self, edge: &WeightedUndirectedEdge) {
         let (v, w) = edge.vertices();
         self.adj[v].push(*edge);
@@ -42,6 +46,11 @@ pub struct EdgeWeightedDirectedGraph {
     e: usize,
     adj: Vec<Vec<WeightedDirectedEdge>>,
 }
+impl EdgeWeightedDirectedGraph {
+    pub(super) fn from_adj(e: usize, adj: Vec<Vec<WeightedDirectedEdge>>) -> Self {
+        EdgeWeightedDirectedGraph { e, adj }
+    }
+}
 impl Graph for EdgeWeightedDirectedGraph {
     type Edge = WeightedDirectedEdge;
 
@@ -75,6 +84,11 @@ pub struct EdgeNonNegativeWeightedDirectedGraph {
     e: usize,
     adj: Vec<Vec<NonNegativeWeightedDirectedEdge>>,
 }
+impl EdgeNonNegativeWeightedDirectedGraph {
+    pub(super) fn from_adj(e: usize, adj: Vec<Vec<NonNegativeWeightedDirectedEdge>>) -> Self {
+        EdgeNonNegativeWeightedDirectedGraph { e, adj }
+    }
+}
 impl Graph for EdgeNonNegativeWeightedDirectedGraph {
     type Edge = NonNegativeWeightedDirectedEdge;
 
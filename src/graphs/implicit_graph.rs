@@ -0,0 +1,269 @@
+use super::base::*;
+use super::edge::*;
+
+use std::marker::PhantomData;
+
+/// A graph with adjacency computed on demand by a closure, for state spaces
+/// too large (or infinite in principle) to be materialized.
+///
+/// The closure returns any `IntoIterator` of edges, e.g. an iterator adaptor,
+/// so `adj` allocates nothing unless the closure does. The iterator must own
+/// its state (`'static`), e.g. built with `move` closures.
+///
+/// `e_size()` walks all adjacency, O(V+E), undirected edges are counted from
+/// both ends.
+#[derive(Clone)]
+pub struct ImplicitGraph<E, F> {
+    v: usize,
+    adj: F,
+    _edge: PhantomData<fn() -> E>,
+}
+impl<E, F, I> ImplicitGraph<E, F>
+where
+    E: Edge,
+    F: Fn(Vertex) -> I + Clone,
+    I: IntoIterator<Item = E>,
+{
+    pub fn new(v: usize, adj: F) -> Self {
+        ImplicitGraph {
+            v,
+            adj,
+            _edge: PhantomData,
+        }
+    }
+}
+impl<E, F, I> Graph for ImplicitGraph<E, F>
+where
+    E: Edge,
+    F: Fn(Vertex) -> I + Clone,
+    I: IntoIterator<Item = E>,
+    I::IntoIter: 'static,
+{
+    type Edge = E;
+
+    fn v_size(&self) -> usize {
+        self.v
+    }
+    fn e_size(&self) -> usize {
+        (0..self.v).map(|v| (self.adj)(v).into_iter().count()).sum()
+    }
+
+    fn adj(&self, v: usize) -> Box<dyn Iterator<Item = Self::Edge> + '_> {
+        Box::new((self.adj)(v).into_iter())
+    }
+}
+
+/// Moves allowed in a grid
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Connectivity {
+    /// up, down, left, right, each weighs 1.0
+    Four,
+    /// plus diagonals weigh sqrt(2), no corner cutting: both orthogonal
+    /// cells beside a diagonal move must be open
+    Eight,
+}
+
+/// A 2D grid of cells, vertex of cell (r, c) is `r * cols + c`, edges go
+/// between adjacent open cells, both ways.
+#[derive(Clone)]
+pub struct GridGraph {
+    rows: usize,
+    cols: usize,
+    connectivity: Connectivity,
+    blocked: Vec<bool>,
+}
+impl GridGraph {
+    pub fn new(rows: usize, cols: usize, connectivity: Connectivity) -> Self {
+        GridGraph {
+            rows,
+            cols,
+            connectivity,
+            blocked: vec![false; rows * cols],
+        }
+    }
+
+    /// a maze in lines of equal length, `#` is blocked, others are open,
+    /// panics if lines differ in length
+    pub fn from_maze(lines: &[&str], connectivity: Connectivity) -> Self {
+        let cols = lines.first().map_or(0, |l| l.chars().count());
+        let mut g = GridGraph::new(lines.len(), cols, connectivity);
+        for (r, line) in lines.iter().enumerate() {
+            let len = line.chars().count();
+            assert!(
+                len == cols,
+                "maze line {} has {} cells, expected {}",
+                r,
+                len,
+                cols
+            );
+            for (c, ch) in line.chars().enumerate() {
+                if ch == '#' {
+                    g.block(r, c);
+                }
+            }
+        }
+        g
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+    pub fn vertex(&self, r: usize, c: usize) -> Vertex {
+        r * self.cols + c
+    }
+    pub fn cell(&self, v: Vertex) -> (usize, usize) {
+        (v / self.cols, v % self.cols)
+    }
+
+    pub fn block(&mut self, r: usize, c: usize) {
+        let v = self.vertex(r, c);
+        self.blocked[v] = true;
+    }
+    pub fn unblock(&mut self, r: usize, c: usize) {
+        let v = self.vertex(r, c);
+        self.blocked[v] = false;
+    }
+    pub fn is_blocked(&self, r: usize, c: usize) -> bool {
+        self.blocked[self.vertex(r, c)]
+    }
+
+    /// admissible and consistent A* heuristic to t: Manhattan distance for
+    /// 4-connectivity, octile distance for 8-connectivity
+    pub fn heuristic(&self, v: Vertex, t: Vertex) -> f64 {
+        let ((r1, c1), (r2, c2)) = (self.cell(v), self.cell(t));
+        let dr = (r1 as f64 - r2 as f64).abs();
+        let dc = (c1 as f64 - c2 as f64).abs();
+        match self.connectivity {
+            Connectivity::Four => dr + dc,
+            Connectivity::Eight => dr.max(dc) + (std::f64::consts::SQRT_2 - 1.0) * dr.min(dc),
+        }
+    }
+
+    // open cell at (r + dr, c + dc) if inside the grid
+    fn open(&self, r: usize, c: usize, dr: isize, dc: isize) -> Option<Vertex> {
+        let r = r as isize + dr;
+        let c = c as isize + dc;
+        if r < 0 || c < 0 || r >= self.rows as isize || c >= self.cols as isize {
+            return None;
+        }
+        let v = self.vertex(r as usize, c as usize);
+        if self.blocked[v] {
+            None
+        } else {
+            Some(v)
+        }
+    }
+}
+impl Graph for GridGraph {
+    type Edge = NonNegativeWeightedDirectedEdge;
+
+    fn v_size(&self) -> usize {
+        self.blocked.len()
+    }
+    fn e_size(&self) -> usize {
+        (0..self.v_size()).map(|v| self.adj(v).count()).sum()
+    }
+
+    fn adj(&self, v: usize) -> Box<dyn Iterator<Item = Self::Edge> + '_> {
+        if self.blocked[v] {
+            return Box::new(std::iter::empty());
+        }
+        let (r, c) = self.cell(v);
+        let straight = [(-1, 0), (0, 1), (1, 0), (0, -1)]
+            .iter()
+            .filter_map(move |&(dr, dc)| self.open(r, c, dr, dc))
+            .map(move |w| NonNegativeWeightedDirectedEdge::new(v, w, 1.0).unwrap());
+        if self.connectivity == Connectivity::Four {
+            return Box::new(straight);
+        }
+
+        let diagonal = [(-1, 1), (1, 1), (1, -1), (-1, -1)]
+            .iter()
+            .filter(move |&&(dr, dc)| {
+                self.open(r, c, dr, 0).is_some() && self.open(r, c, 0, dc).is_some()
+            })
+            .filter_map(move |&(dr, dc)| self.open(r, c, dr, dc))
+            .map(move |w| {
+                NonNegativeWeightedDirectedEdge::new(v, w, std::f64::consts::SQRT_2).unwrap()
+            });
+        Box::new(straight.chain(diagonal))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::dijkstra_sp::HasDijkstraSP;
+    use super::*;
+
+    #[test]
+    fn implicit() {
+        // n -> n+1 and n -> 2n, up to 100
+        let g = ImplicitGraph::new(101, |v| {
+            std::iter::once(v + 1)
+                .chain(std::iter::once(2 * v))
+                .filter(move |&w| w <= 100 && w != v)
+                .map(move |w| DirectedEdge::new(v, w))
+        });
+        assert_eq!(101, g.v_size());
+        assert_eq!(100 + 50, g.e_size());
+        let adj = |v| g.adj(v).map(|e| e.to()).collect::<Vec<_>>();
+        assert_eq!(vec![1], adj(0));
+        assert_eq!(vec![5, 8], adj(4));
+        assert_eq!(vec![100], adj(99));
+        assert!(adj(100).is_empty());
+
+        let weighted = ImplicitGraph::new(10, |v| {
+            (v + 1..10).map(move |w| {
+                NonNegativeWeightedDirectedEdge::new(v, w, ((w - v) * (w - v)) as f64).unwrap()
+            })
+        });
+        // 1-step hops are the cheapest
+        let sp = weighted.dijkstra_sp(0);
+        assert_eq!(9, sp.dist_to(9).round() as usize);
+        assert_eq!(9, sp.path_to(9).count());
+    }
+
+    #[test]
+    fn grid() {
+        let g = GridGraph::from_maze(&["..#", "...", "#.."], Connectivity::Four);
+        assert_eq!(3, g.rows());
+        assert_eq!(3, g.cols());
+        assert_eq!(9, g.v_size());
+        assert!(g.is_blocked(0, 2));
+        assert_eq!((1, 2), g.cell(5));
+        assert_eq!(5, g.vertex(1, 2));
+        // 7 open cells, 8 adjacent pairs
+        assert_eq!(16, g.e_size());
+
+        let mut adj = g.adj(4).map(|e| e.to()).collect::<Vec<_>>();
+        adj.sort_unstable();
+        assert_eq!(vec![1, 3, 5, 7], adj);
+        assert_eq!(None, g.adj(2).next());
+        assert_eq!(3, g.heuristic(0, 5).round() as usize);
+    }
+
+    #[test]
+    #[should_panic(expected = "maze line 1 has 2 cells, expected 3")]
+    fn ragged_maze() {
+        GridGraph::from_maze(&["..#", "..", "#.."], Connectivity::Four);
+    }
+
+    #[test]
+    fn grid_diagonal() {
+        let mut g = GridGraph::new(2, 2, Connectivity::Eight);
+        let mut adj = g.adj(0).map(|e| e.to()).collect::<Vec<_>>();
+        adj.sort_unstable();
+        assert_eq!(vec![1, 2, 3], adj);
+        let diagonal = g.adj(0).find(|e| e.to() == 3).unwrap();
+        assert_eq!(141, (diagonal.weight() * 100.0).round() as usize);
+
+        // no corner cutting
+        g.block(0, 1);
+        assert_eq!(vec![2], g.adj(0).map(|e| e.to()).collect::<Vec<_>>());
+        g.unblock(0, 1);
+        assert_eq!(3, g.adj(0).count());
+    }
+}
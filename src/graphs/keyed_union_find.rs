@@ -0,0 +1,165 @@
+use super::UnionFind;
+
+use std::borrow::Borrow;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Keyed Union Find
+///
+/// Disjoint sets of any hashable keys, a key gets the next dense id of the
+/// underlying `UnionFind` on first sight.
+pub struct KeyedUnionFind<K> {
+    ids: HashMap<K, usize>,
+    keys: Vec<K>,
+    uf: UnionFind,
+}
+
+impl<K> Default for KeyedUnionFind<K>
+where
+    K: Hash + Eq + Clone,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K> KeyedUnionFind<K>
+where
+    K: Hash + Eq + Clone,
+{
+    pub fn new() -> Self {
+        KeyedUnionFind {
+            ids: HashMap::new(),
+            keys: Vec::new(),
+            uf: UnionFind::new(0),
+        }
+    }
+
+    /// number of keys
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    pub fn count(&self) -> usize {
+        self.uf.count()
+    }
+
+    /// id of the key, add it in its own component if not seen
+    pub fn insert(&mut self, key: K) -> usize {
+        if let Some(&i) = self.ids.get(&key) {
+            return i;
+        }
+        let i = self.uf.make_set();
+        self.ids.insert(key.clone(), i);
+        self.keys.push(key);
+        i
+    }
+
+    pub fn id<Q>(&self, key: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.ids.get(key).cloned()
+    }
+    pub fn key(&self, id: usize) -> Option<&K> {
+        self.keys.get(id)
+    }
+
+    /// the representative key of the component
+    pub fn find<Q>(&self, key: &Q) -> Option<&K>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let r = self.uf.find(self.id(key)?)?;
+        Some(&self.keys[r])
+    }
+
+    /// None if any key is not seen
+    pub fn connected<Q>(&self, p: &Q, q: &Q) -> Option<bool>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.uf.connected(self.id(p)?, self.id(q)?)
+    }
+
+    pub fn size<Q>(&self, key: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.uf.size(self.id(key)?)
+    }
+
+    /// keys of the component, starting from the key
+    pub fn members<Q>(&self, key: &Q) -> impl Iterator<Item = &K> + '_
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let members = self.id(key).map(|i| self.uf.members(i));
+        members.into_iter().flatten().map(move |i| &self.keys[i])
+    }
+
+    /// keys are inserted if not seen, returns whether two components are merged
+    pub fn union(&mut self, p: K, q: K) -> bool {
+        let (p, q) = (self.insert(p), self.insert(q));
+        self.uf.union(p, q) == Some(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty() {
+        let uf = KeyedUnionFind::<String>::new();
+        assert!(uf.is_empty());
+        assert_eq!(0, uf.count());
+        assert_eq!(None, uf.connected("a", "b"));
+        assert_eq!(None, uf.find("a"));
+        assert_eq!(0, uf.members("a").count());
+    }
+
+    #[test]
+    fn strings() {
+        let mut uf = KeyedUnionFind::new();
+        assert!(uf.union("JFK".to_string(), "MCO".to_string()));
+        assert!(uf.union("ORD".to_string(), "DEN".to_string()));
+        assert!(uf.union("MCO".to_string(), "ORD".to_string()));
+        assert!(!uf.union("DEN".to_string(), "JFK".to_string()));
+        assert_eq!(4, uf.insert("LAX".to_string()));
+        assert_eq!(5, uf.len());
+        assert_eq!(2, uf.count());
+
+        assert_eq!(Some(0), uf.id("JFK"));
+        assert_eq!(Some(&"LAX".to_string()), uf.key(4));
+        assert_eq!(Some(true), uf.connected("JFK", "DEN"));
+        assert_eq!(Some(false), uf.connected("JFK", "LAX"));
+        assert_eq!(None, uf.connected("JFK", "SFO"));
+        assert_eq!(uf.find("JFK"), uf.find("ORD"));
+        assert_eq!(Some(4), uf.size("MCO"));
+
+        let mut members = uf.members("DEN").cloned().collect::<Vec<_>>();
+        assert_eq!("DEN", members[0]);
+        members.sort();
+        assert_eq!(vec!["DEN", "JFK", "MCO", "ORD"], members);
+    }
+
+    #[test]
+    fn tuples() {
+        let mut uf = KeyedUnionFind::new();
+        uf.union((0, 0), (0, 1));
+        uf.union((1, 1), (0, 1));
+        uf.insert((5, 5));
+        assert_eq!(2, uf.count());
+        assert_eq!(Some(true), uf.connected(&(0, 0), &(1, 1)));
+        assert_eq!(Some(1), uf.size(&(5, 5)));
+    }
+}
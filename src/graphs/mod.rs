@@ -6,29 +6,52 @@ mod acyclic_graph;
 mod directed_graph;
 mod edge;
 mod graph;
+mod implicit_graph;
 mod undirected_graph;
 pub use self::acyclic_graph::*;
 pub use self::directed_graph::*;
 pub use self::edge::*;
 pub use self::graph::*;
+pub use self::implicit_graph::*;
 pub use self::undirected_graph::*;
 pub mod weighted_path;
 
 // algorithms
 pub mod acyclic_path;
+pub mod astar_sp;
 pub mod bellmanford_sp;
+pub mod binary;
 pub mod cc;
+pub mod centrality;
+pub mod coloring;
+pub mod community;
 pub mod cycle;
 pub mod dfs_order;
 pub mod dijkstra_sp;
+pub mod dominators;
 pub mod mst;
+#[cfg(feature = "parallel")]
+pub mod parallel;
 pub mod reversed;
 pub mod scc;
+pub mod tree;
+pub mod views;
+pub mod yen_ksp;
 
+mod keyed_union_find;
+mod offline_connectivity;
 mod path;
+mod quick_union_find;
+mod rollback_union_find;
 mod symbol_graph;
 mod union_find;
+mod weighted_union_find;
 
+pub use self::keyed_union_find::*;
+pub use self::offline_connectivity::*;
 pub use self::path::*;
+pub use self::quick_union_find::*;
+pub use self::rollback_union_find::*;
 pub use self::symbol_graph::*;
 pub use self::union_find::*;
+pub use self::weighted_union_find::*;
@@ -2,6 +2,8 @@ use super::super::sorting::{IndexMinPQ, MinPQ};
 use super::base::*;
 use super::union_find::UnionFind;
 
+use std::cmp::Ordering;
+
 /// Minimum spanning tree
 ///
 /// A spanning tree connected all vertices in a weighted undirected graph with
@@ -11,6 +13,8 @@ pub trait MST {
 
     fn kruskal_mst(&self) -> KruskalMST<Self::Edge>;
     fn prim_mst(&self) -> PrimMST<Self::Edge>;
+    fn dynamic_mst(&self) -> DynamicMST<Self::Edge>;
+    fn boruvka_mst(&self) -> BoruvkaMST<Self::Edge>;
 }
 // MST implementation for all undirect weighted graphs
 impl<G, E> MST for G
@@ -26,6 +30,12 @@ where
     fn prim_mst(&self) -> PrimMST<E> {
         PrimMST::new(self)
     }
+    fn dynamic_mst(&self) -> DynamicMST<E> {
+        DynamicMST::from_mst(self.v_size(), &KruskalMST::new(self))
+    }
+    fn boruvka_mst(&self) -> BoruvkaMST<E> {
+        BoruvkaMST::new(self, |uf| cheapest_edges(self, uf, 0..self.v_size()))
+    }
 }
 
 /// Kruskal algorithm for MST
@@ -75,10 +85,9 @@ where
         let mut uf = UnionFind::new(g.v_size());
         while let Some(e) = pq.pop() {
             let (v, w) = e.vertices();
-            if uf.connected(v, w).unwrap() {
+            if uf.union(v, w) != Some(true) {
                 continue;
             }
-            uf.union(v, w);
             self.edges_.push(e);
             if self.edges_.len() == g.v_size() - 1 {
                 break;
@@ -162,6 +171,237 @@ where
     }
 }
 
+/// Boruvka algorithm for MST
+///
+/// A component-based algorithm:
+/// in each round, find the cheapest edge leaving each component, add them all
+/// and merge the components, until no edge leaves any component. Edges are
+/// ordered by (weight, vertices), so ties never make a cycle, and rounds are
+/// independent of the order of scanning, which makes it easy to parallelize.
+pub struct BoruvkaMST<E: Undirected + Weighted> {
+    edges_: Vec<E>,
+    weight: f64,
+}
+
+impl<E> BoruvkaMST<E>
+where
+    E: Undirected + Weighted,
+{
+    // `cheapest(uf)[r]` is the cheapest edge leaving component rooted at r
+    pub(super) fn new<G, F>(g: &G, mut cheapest: F) -> Self
+    where
+        G: Graph<Edge = E>,
+        F: FnMut(&UnionFind) -> Vec<Option<E>>,
+    {
+        let mut t = BoruvkaMST {
+            edges_: Vec::new(),
+            weight: 0.0,
+        };
+        let mut uf = UnionFind::new(g.v_size());
+        loop {
+            let mut merged = false;
+            for e in cheapest(&uf).into_iter().flatten() {
+                let (v, w) = e.vertices();
+                if uf.union(v, w) == Some(true) {
+                    t.edges_.push(e);
+                    merged = true;
+                }
+            }
+            if !merged {
+                break;
+            }
+        }
+        t.weight = t.edges().map(|e| e.weight()).sum();
+
+        t
+    }
+
+    pub fn weight(&self) -> f64 {
+        self.weight
+    }
+    pub fn edges(&self) -> impl Iterator<Item = &E> {
+        self.edges_.iter()
+    }
+}
+
+// cheapest edges leaving components, scanning edges of vertices in range
+pub(super) fn cheapest_edges<G, E>(
+    g: &G,
+    uf: &UnionFind,
+    vertices: std::ops::Range<usize>,
+) -> Vec<Option<E>>
+where
+    E: Undirected + Weighted,
+    G: Graph<Edge = E>,
+{
+    let mut cheapest = vec![None; g.v_size()];
+    for v in vertices {
+        let r = uf.find(v).unwrap();
+        for e in g.adj(v) {
+            if uf.find(e.other(v)).unwrap() != r {
+                cheapest[r] = lighter(cheapest[r], Some(e));
+            }
+        }
+    }
+    cheapest
+}
+
+// the first one wins a tie, so merging results in scanning order is stable
+pub(super) fn lighter<E: Undirected + Weighted>(a: Option<E>, b: Option<E>) -> Option<E> {
+    match (a, b) {
+        (Some(a), Some(b)) => {
+            let key = |e: &E| {
+                let (v, w) = e.vertices();
+                (v.min(w), v.max(w))
+            };
+            match b.weight().partial_cmp(&a.weight()) {
+                Some(Ordering::Less) => Some(b),
+                Some(Ordering::Equal) if key(&b) < key(&a) => Some(b),
+                _ => Some(a),
+            }
+        }
+        (a, b) => a.or(b),
+    }
+}
+
+/// Incrementally maintained MST
+///
+/// Keeps a minimum spanning forest while edges are inserted one by one:
+/// a new edge (v,w) either connects two trees, or closes a cycle with the
+/// tree path v->w, in which case the heaviest edge on that cycle is dropped.
+/// Since MST(G+e) is MST(MST(G)+e), only tree edges are kept, each insertion
+/// costs O(V).
+pub struct DynamicMST<E: Undirected + Weighted> {
+    edges_: Vec<E>,
+    adj: Vec<Vec<usize>>, // indices into `edges_` for each vertex
+    weight: f64,
+}
+
+impl<E> DynamicMST<E>
+where
+    E: Undirected + Weighted,
+{
+    pub fn new(v: usize) -> Self {
+        DynamicMST {
+            edges_: Vec::new(),
+            adj: vec![Vec::new(); v],
+            weight: 0.0,
+        }
+    }
+
+    fn from_mst(v: usize, t: &KruskalMST<E>) -> Self {
+        let mut d = Self::new(v);
+        for e in t.edges() {
+            d.link(*e);
+        }
+        d.weight = t.weight();
+
+        d
+    }
+
+    pub fn weight(&self) -> f64 {
+        self.weight
+    }
+    pub fn edges(&self) -> impl Iterator<Item = &E> {
+        self.edges_.iter()
+    }
+
+    /// Inserts an edge, grows vertices if needed.
+    ///
+    /// Returns the edge left out of the tree: the heaviest edge on the
+    /// induced cycle (which may be `e` itself), or `None` if `e` joined two
+    /// trees.
+    pub fn add_edge(&mut self, e: E) -> Option<E> {
+        let (v, w) = e.vertices();
+        let n = v.max(w) + 1;
+        if n > self.adj.len() {
+            self.adj.resize(n, Vec::new());
+        }
+
+        let dropped = match self.path(v, w) {
+            None => None,
+            Some(path) => {
+                let max = path.into_iter().max_by(|&i, &j| {
+                    let (a, b) = (&self.edges_[i], &self.edges_[j]);
+                    a.partial_cmp(b).unwrap_or(Ordering::Equal)
+                });
+                match max {
+                    // a self-loop closes a cycle without tree edges
+                    None => return Some(e),
+                    Some(i) if self.edges_[i].weight() <= e.weight() => return Some(e),
+                    Some(i) => Some(self.cut(i)),
+                }
+            }
+        };
+        self.link(e);
+        self.weight = self.edges().map(|e| e.weight()).sum();
+
+        dropped
+    }
+
+    // tree edges on the path v->w, None if not connected
+    fn path(&self, v: Vertex, w: Vertex) -> Option<Vec<usize>> {
+        let mut edge_to = vec![None; self.adj.len()];
+        let mut marked = vec![false; self.adj.len()];
+        let mut stack = vec![v];
+        marked[v] = true;
+        while let Some(x) = stack.pop() {
+            if x == w {
+                break;
+            }
+            for &i in &self.adj[x] {
+                let y = self.edges_[i].other(x);
+                if !marked[y] {
+                    marked[y] = true;
+                    edge_to[y] = Some(i);
+                    stack.push(y);
+                }
+            }
+        }
+        if !marked[w] {
+            return None;
+        }
+
+        let mut path = Vec::new();
+        let mut x = w;
+        while let Some(i) = edge_to[x] {
+            path.push(i);
+            x = self.edges_[i].other(x);
+        }
+        Some(path)
+    }
+
+    fn link(&mut self, e: E) {
+        let (v, w) = e.vertices();
+        let i = self.edges_.len();
+        self.edges_.push(e);
+        self.adj[v].push(i);
+        self.adj[w].push(i);
+    }
+
+    fn cut(&mut self, i: usize) -> E {
+        let (v, w) = self.edges_[i].vertices();
+        self.adj[v].retain(|&j| j != i);
+        self.adj[w].retain(|&j| j != i);
+
+        // the last edge is moved to slot i
+        let last = self.edges_.len() - 1;
+        let e = self.edges_.swap_remove(i);
+        if i != last {
+            let (v, w) = self.edges_[i].vertices();
+            for x in [v, w].iter() {
+                for j in self.adj[*x].iter_mut() {
+                    if *j == last {
+                        *j = i;
+                    }
+                }
+            }
+        }
+
+        e
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::super::EdgeWeightedUndirectedGraph as EWG;
@@ -242,5 +482,117 @@ mod tests {
             paths
         );
         assert_eq!(181, (t.weight() * 100.0).round() as isize);
+
+        let t = g.boruvka_mst();
+        let mut paths = t.edges().map(|e| e.vertices()).collect::<Vec<_>>();
+        paths.sort();
+        assert_eq!(
+            vec![(0, 2), (0, 7), (1, 7), (2, 3), (4, 5), (5, 7), (6, 2)],
+            paths
+        );
+        assert_eq!(181, (t.weight() * 100.0).round() as isize);
+    }
+
+    #[test]
+    fn boruvka_ties() {
+        // all weights equal, a forest of a 4-cycle and an isolated vertex
+        let mut g = EWG::new(5);
+        g.add_edge(&WE::new(0, 1, 1.0));
+        g.add_edge(&WE::new(1, 2, 1.0));
+        g.add_edge(&WE::new(2, 3, 1.0));
+        g.add_edge(&WE::new(3, 0, 1.0));
+        g.add_edge(&WE::new(3, 0, 1.0));
+
+        let t = g.boruvka_mst();
+        assert_eq!(3, t.edges().count());
+        assert_eq!(3, t.weight().round() as isize);
+        assert_eq!(None, EWG::new(1).boruvka_mst().edges().next());
+    }
+
+    #[test]
+    fn dynamic_empty() {
+        let t = DynamicMST::<WE>::new(0);
+        assert_eq!(0, (t.weight() * 1000.0) as isize);
+        assert_eq!(None, t.edges().next());
+
+        let t = EWG::new(3).dynamic_mst();
+        assert_eq!(None, t.edges().next());
+    }
+
+    #[test]
+    fn dynamic_replace_heaviest() {
+        let mut t = DynamicMST::new(3);
+        assert_eq!(None, t.add_edge(WE::new(0, 1, 0.5)));
+        assert_eq!(None, t.add_edge(WE::new(1, 2, 0.7)));
+        assert_eq!(12, (t.weight() * 10.0).round() as isize);
+
+        // heavier than the cycle, rejected
+        let e = t.add_edge(WE::new(0, 2, 0.9)).unwrap();
+        assert_eq!((0, 2), e.vertices());
+        // lighter than (1,2), which is replaced
+        let e = t.add_edge(WE::new(0, 2, 0.1)).unwrap();
+        assert_eq!((1, 2), e.vertices());
+        assert_eq!(6, (t.weight() * 10.0).round() as isize);
+
+        // self-loop never joins the tree
+        let e = t.add_edge(WE::new(1, 1, 0.0)).unwrap();
+        assert_eq!((1, 1), e.vertices());
+
+        // grows to new vertices
+        assert_eq!(None, t.add_edge(WE::new(2, 4, 0.2)));
+        let mut paths = t.edges().map(|e| e.vertices()).collect::<Vec<_>>();
+        paths.sort();
+        assert_eq!(vec![(0, 1), (0, 2), (2, 4)], paths);
+    }
+
+    #[test]
+    fn dynamic_multiple_edges() {
+        // as the example in the book, inserted one by one
+        let edges = vec![
+            WE::new(0, 2, 0.26),
+            WE::new(0, 4, 0.38),
+            WE::new(0, 7, 0.16),
+            WE::new(1, 2, 0.36),
+            WE::new(1, 3, 0.29),
+            WE::new(1, 5, 0.32),
+            WE::new(1, 7, 0.19),
+            WE::new(2, 3, 0.17),
+            WE::new(2, 7, 0.34),
+            WE::new(3, 6, 0.52),
+            WE::new(4, 5, 0.35),
+            WE::new(4, 7, 0.37),
+            WE::new(5, 7, 0.28),
+            WE::new(6, 0, 0.58),
+            WE::new(6, 2, 0.40),
+            WE::new(6, 4, 0.93),
+        ];
+        let mut t = DynamicMST::new(8);
+        for e in edges.iter() {
+            t.add_edge(*e);
+        }
+        let mut paths = t.edges().map(|e| e.vertices()).collect::<Vec<_>>();
+        paths.sort();
+        assert_eq!(
+            vec![(0, 2), (0, 7), (1, 7), (2, 3), (4, 5), (5, 7), (6, 2)],
+            paths
+        );
+        assert_eq!(181, (t.weight() * 100.0).round() as isize);
+
+        // seeded from an existing graph, then updated
+        let mut g = EWG::new(8);
+        for e in edges.iter().take(10) {
+            g.add_edge(e);
+        }
+        let mut t = g.dynamic_mst();
+        for e in edges.iter().skip(10) {
+            t.add_edge(*e);
+        }
+        let mut paths = t.edges().map(|e| e.vertices()).collect::<Vec<_>>();
+        paths.sort();
+        assert_eq!(
+            vec![(0, 2), (0, 7), (1, 7), (2, 3), (4, 5), (5, 7), (6, 2)],
+            paths
+        );
+        assert_eq!(181, (t.weight() * 100.0).round() as isize);
     }
 }
@@ -0,0 +1,234 @@
+use super::RollbackUnionFind;
+
+use std::collections::HashMap;
+
+/// An operation of the log, timestamp is its index in the log
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operation {
+    /// add an undirected edge, parallel edges are allowed
+    Add(usize, usize),
+    /// remove the latest added edge of p-q, ignored if there is none
+    Remove(usize, usize),
+    Connected(usize, usize),
+    Count,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Answer {
+    /// None if out of range
+    Connected(Option<bool>),
+    Count(usize),
+}
+
+/// Offline Dynamic Connectivity
+///
+/// Each edge lives in an interval of time, which is split into O(logT) nodes
+/// of a segment tree over time. DFS of the tree unions edges of a node when
+/// entering it, answers queries at leaves, and rolls the unions back when
+/// leaving, so O(logT) unions of O(logV) each per edge, O(T*logT*logV) total.
+pub struct OfflineConnectivity {
+    answers: Vec<(usize, Answer)>,
+}
+
+impl OfflineConnectivity {
+    pub fn new(n: usize, log: &[Operation]) -> Self {
+        let t = log.len();
+        let mut segments = vec![Vec::new(); 4 * t.max(1)];
+
+        // intervals [start, end) of edges, a query at the time of adding or
+        // removing an edge sees it before the operation
+        let mut open: HashMap<(usize, usize), Vec<usize>> = HashMap::new();
+        for (time, op) in log.iter().enumerate() {
+            match *op {
+                Operation::Add(p, q) => {
+                    let key = (p.min(q), p.max(q));
+                    open.entry(key).or_default().push(time + 1);
+                }
+                Operation::Remove(p, q) => {
+                    let key = (p.min(q), p.max(q));
+                    if let Some(starts) = open.get_mut(&key) {
+                        if let Some(start) = starts.pop() {
+                            insert(&mut segments, 1, 0, t, start, time, key);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        for (key, starts) in open {
+            for start in starts {
+                insert(&mut segments, 1, 0, t, start, t, key);
+            }
+        }
+
+        let mut oc = OfflineConnectivity {
+            answers: Vec::new(),
+        };
+        if t > 0 {
+            let mut uf = RollbackUnionFind::new(n);
+            oc.dfs(&segments, log, &mut uf, 1, 0, t);
+        }
+        oc
+    }
+
+    /// (timestamp, answer) of queries in the order of the log
+    pub fn answers(&self) -> impl Iterator<Item = &(usize, Answer)> {
+        self.answers.iter()
+    }
+
+    fn dfs(
+        &mut self,
+        segments: &[Vec<(usize, usize)>],
+        log: &[Operation],
+        uf: &mut RollbackUnionFind,
+        node: usize,
+        lo: usize,
+        hi: usize,
+    ) {
+        let snapshot = uf.snapshot();
+        for &(p, q) in segments[node].iter() {
+            uf.union(p, q);
+        }
+
+        if hi - lo == 1 {
+            match log[lo] {
+                Operation::Connected(p, q) => self
+                    .answers
+                    .push((lo, Answer::Connected(uf.connected(p, q)))),
+                Operation::Count => self.answers.push((lo, Answer::Count(uf.count()))),
+                _ => {}
+            }
+        } else {
+            let mid = lo + (hi - lo) / 2;
+            self.dfs(segments, log, uf, 2 * node, lo, mid);
+            self.dfs(segments, log, uf, 2 * node + 1, mid, hi);
+        }
+
+        uf.rollback(snapshot);
+    }
+}
+
+// add the edge to nodes covering [start, end) within the node range [lo, hi)
+fn insert(
+    segments: &mut [Vec<(usize, usize)>],
+    node: usize,
+    lo: usize,
+    hi: usize,
+    start: usize,
+    end: usize,
+    edge: (usize, usize),
+) {
+    if end <= lo || hi <= start {
+        return;
+    }
+    if start <= lo && hi <= end {
+        segments[node].push(edge);
+        return;
+    }
+    let mid = lo + (hi - lo) / 2;
+    insert(segments, 2 * node, lo, mid, start, end, edge);
+    insert(segments, 2 * node + 1, mid, hi, start, end, edge);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::UnionFind;
+    use super::*;
+    use rand::{Rng, SeedableRng, StdRng};
+
+    use Operation::*;
+
+    #[test]
+    fn empty() {
+        let oc = OfflineConnectivity::new(3, &[]);
+        assert_eq!(0, oc.answers().count());
+
+        let oc = OfflineConnectivity::new(3, &[Count, Connected(0, 3)]);
+        assert_eq!(
+            vec![(0, Answer::Count(3)), (1, Answer::Connected(None))],
+            oc.answers().cloned().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn add_and_remove() {
+        let log = [
+            Add(0, 1),
+            Add(1, 2),
+            Connected(0, 2),
+            Count,
+            Remove(1, 0),
+            Connected(0, 2),
+            Add(0, 1),
+            Add(0, 1),
+            Remove(0, 1),
+            Connected(0, 2),
+            Remove(0, 1),
+            Connected(0, 2),
+            Remove(3, 4),
+            Count,
+        ];
+        let oc = OfflineConnectivity::new(5, &log);
+        assert_eq!(
+            vec![
+                (2, Answer::Connected(Some(true))),
+                (3, Answer::Count(3)),
+                (5, Answer::Connected(Some(false))),
+                (9, Answer::Connected(Some(true))),
+                (11, Answer::Connected(Some(false))),
+                (13, Answer::Count(4)),
+            ],
+            oc.answers().cloned().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn random_vs_rebuild() {
+        let n = 12;
+        let mut rng = StdRng::from_seed(&[40][..]);
+        let mut edges: Vec<(usize, usize)> = Vec::new();
+        let mut log = Vec::new();
+        for _ in 0..300 {
+            let op = match rng.gen_range(0, 4) {
+                0 | 1 => {
+                    let e = (rng.gen_range(0, n), rng.gen_range(0, n));
+                    edges.push(e);
+                    Add(e.0, e.1)
+                }
+                2 if !edges.is_empty() => {
+                    let (p, q) = edges.remove(rng.gen_range(0, edges.len()));
+                    Remove(q, p)
+                }
+                _ if rng.gen() => Count,
+                _ => Connected(rng.gen_range(0, n), rng.gen_range(0, n)),
+            };
+            log.push(op);
+        }
+
+        // rebuild a UnionFind for every query
+        let mut edges: Vec<(usize, usize)> = Vec::new();
+        let mut expected = Vec::new();
+        for (time, op) in log.iter().enumerate() {
+            let uf = || {
+                let mut uf = UnionFind::new(n);
+                for &(p, q) in edges.iter() {
+                    uf.union(p, q);
+                }
+                uf
+            };
+            match *op {
+                Add(p, q) => edges.push((p.min(q), p.max(q))),
+                Remove(p, q) => {
+                    let i = edges.iter().rposition(|&e| e == (p.min(q), p.max(q)));
+                    edges.remove(i.unwrap());
+                }
+                Connected(p, q) => expected.push((time, Answer::Connected(uf().connected(p, q)))),
+                Count => expected.push((time, Answer::Count(uf().count()))),
+            }
+        }
+
+        let oc = OfflineConnectivity::new(n, &log);
+        assert!(expected.len() > 50);
+        assert_eq!(expected, oc.answers().cloned().collect::<Vec<_>>());
+    }
+}
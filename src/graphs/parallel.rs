@@ -0,0 +1,315 @@
+//! Parallel Algorithms
+//!
+//! Multi-threaded versions of BFS, connected components, MST and all-sources
+//! shortest paths by `std::thread::scope`, enabled by the `parallel` feature.
+//! Work is split into `threads` chunks in vertex order, and partial results
+//! are merged in the same order, so results are identical to the sequential
+//! versions (`BFSPath`, `HasCC::cc`, `MST::boruvka_mst`, `HasDijkstraSP`).
+use super::base::*;
+use super::cc::CC;
+use super::dijkstra_sp::HasDijkstraSP;
+use super::mst::{cheapest_edges, lighter, BoruvkaMST};
+use super::union_find::UnionFind;
+use super::weighted_path::WeightedPath;
+use super::BFSPath;
+
+use std::ops::Range;
+use std::thread;
+
+/// HasParallelBFS
+///
+/// Level-synchronous BFS for all graphs: vertices of a level are scanned in
+/// parallel, then discovered vertices are claimed in the order of the level.
+pub trait HasParallelBFS {
+    fn par_bfs(&self, s: Vertex, threads: usize) -> BFSPath;
+}
+impl<G> HasParallelBFS for G
+where
+    G: Graph + Sync,
+{
+    fn par_bfs(&self, s: Vertex, threads: usize) -> BFSPath {
+        let n = self.v_size();
+        let mut dist_to = vec![None; n];
+        let mut edge_to = vec![n; n];
+        dist_to[s] = Some(0);
+
+        let mut frontier = vec![s];
+        let mut d = 0;
+        while !frontier.is_empty() {
+            let visited = &dist_to;
+            let claims = par_chunks(&frontier, threads, |part: &[Vertex]| {
+                let mut claims = Vec::new();
+                for &v in part {
+                    for w in self.adj(v).map(|e| e.other(v)) {
+                        if visited[w].is_none() {
+                            claims.push((w, v));
+                        }
+                    }
+                }
+                claims
+            });
+
+            d += 1;
+            let mut next = Vec::new();
+            for (w, v) in claims.into_iter().flatten() {
+                if dist_to[w].is_none() {
+                    dist_to[w] = Some(d);
+                    edge_to[w] = v;
+                    next.push(w);
+                }
+            }
+            frontier = next;
+        }
+
+        BFSPath::from_parts(s, dist_to, edge_to)
+    }
+}
+
+/// HasParallelCC
+///
+/// Each thread unions edges of a range of vertices into its own `UnionFind`,
+/// then they are merged into one, components are numbered by first vertex.
+pub trait HasParallelCC {
+    fn par_cc(&self, threads: usize) -> CC;
+}
+impl<G> HasParallelCC for G
+where
+    G: Graph + Sync,
+    G::Edge: Undirected,
+{
+    fn par_cc(&self, threads: usize) -> CC {
+        let n = self.v_size();
+        let parts = par_ranges(n, threads, |vertices| {
+            let mut uf = UnionFind::new(n);
+            for v in vertices {
+                for w in self.adj(v).map(|e| e.other(v)) {
+                    uf.union(v, w);
+                }
+            }
+            uf
+        });
+
+        let mut uf = UnionFind::new(n);
+        for part in parts {
+            for v in 0..n {
+                let r = part.find(v).unwrap();
+                if r != v {
+                    uf.union(v, r);
+                }
+            }
+        }
+
+        let mut id_of_root = vec![None; n];
+        let mut count = 0;
+        let ids = (0..n)
+            .map(|v| {
+                *id_of_root[uf.find(v).unwrap()].get_or_insert_with(|| {
+                    count += 1;
+                    count - 1
+                })
+            })
+            .collect();
+        CC::from_ids(ids, count)
+    }
+}
+
+/// ParallelMST
+///
+/// Boruvka MST with the cheapest edges of components found in parallel.
+pub trait ParallelMST {
+    type Edge: Undirected + Weighted;
+
+    fn par_boruvka_mst(&self, threads: usize) -> BoruvkaMST<Self::Edge>;
+}
+impl<G, E> ParallelMST for G
+where
+    E: Undirected + Weighted + Send + Sync,
+    G: Graph<Edge = E> + Sync,
+{
+    type Edge = E;
+
+    fn par_boruvka_mst(&self, threads: usize) -> BoruvkaMST<E> {
+        BoruvkaMST::new(self, |uf| {
+            let parts = par_ranges(self.v_size(), threads, |vertices| {
+                cheapest_edges(self, uf, vertices)
+            });
+            let mut cheapest = vec![None; self.v_size()];
+            for part in parts {
+                for (c, e) in cheapest.iter_mut().zip(part) {
+                    *c = lighter(*c, e);
+                }
+            }
+            cheapest
+        })
+    }
+}
+
+/// HasParallelSP
+///
+/// Dijkstra shortest paths from every source, sources fanned out to threads.
+pub trait HasParallelSP<E>
+where
+    E: Directed + NonNegative,
+{
+    fn par_all_sources_sp(&self, threads: usize) -> Vec<WeightedPath<E>>;
+}
+impl<G, E> HasParallelSP<E> for G
+where
+    E: Directed + NonNegative + Send + Sync,
+    G: Graph<Edge = E> + Sync,
+{
+    fn par_all_sources_sp(&self, threads: usize) -> Vec<WeightedPath<E>> {
+        par_ranges(self.v_size(), threads, |sources| {
+            sources.map(|s| self.dijkstra_sp(s)).collect::<Vec<_>>()
+        })
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+}
+
+// at most `threads` chunks of `items`, results in order of chunks
+fn par_chunks<T, R, F>(items: &[T], threads: usize, f: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&[T]) -> R + Sync,
+{
+    if items.is_empty() {
+        return Vec::new();
+    }
+    let size = items.len().div_ceil(threads.max(1));
+    let f = &f;
+    thread::scope(|scope| {
+        let handles = items
+            .chunks(size)
+            .map(|part| scope.spawn(move || f(part)))
+            .collect::<Vec<_>>();
+        handles.into_iter().map(|h| h.join().unwrap()).collect()
+    })
+}
+
+// at most `threads` ranges of `0..n`, results in order of ranges
+fn par_ranges<R, F>(n: usize, threads: usize, f: F) -> Vec<R>
+where
+    R: Send,
+    F: Fn(Range<usize>) -> R + Sync,
+{
+    let size = n.div_ceil(threads.max(1)).max(1);
+    let f = &f;
+    thread::scope(|scope| {
+        let handles = (0..n)
+            .step_by(size)
+            .map(|i| scope.spawn(move || f(i..(i + size).min(n))))
+            .collect::<Vec<_>>();
+        handles.into_iter().map(|h| h.join().unwrap()).collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::cc::HasCC;
+    use super::super::mst::MST;
+    use super::super::EdgeNonNegativeWeightedDirectedGraph as ENNWDG;
+    use super::super::EdgeWeightedUndirectedGraph as EWUG;
+    use super::super::NonNegativeWeightedDirectedEdge as NNWDE;
+    use super::super::WeightedUndirectedEdge as WE;
+    use super::super::{DirectedGraph, UndirectedGraph};
+    use super::*;
+    use rand::{Rng, SeedableRng, StdRng};
+
+    const THREADS: [usize; 4] = [1, 2, 3, 8];
+
+    fn random_edges(n: usize, m: usize, seed: usize) -> Vec<(usize, usize, f64)> {
+        let mut rng = StdRng::from_seed(&[seed][..]);
+        (0..m)
+            .map(|_| {
+                let w = (rng.gen_range(0, 100) as f64) / 10.0;
+                (rng.gen_range(0, n), rng.gen_range(0, n), w)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn empty() {
+        let g = UndirectedGraph::new(0);
+        assert_eq!(0, g.par_cc(4).count());
+        let g = EWUG::new(0);
+        assert_eq!(None, g.par_boruvka_mst(4).edges().next());
+        let g = ENNWDG::new(0);
+        assert!(g.par_all_sources_sp(4).is_empty());
+
+        let g = UndirectedGraph::new(1);
+        assert_eq!(Some(0), g.par_bfs(0, 4).dist_to(0));
+    }
+
+    #[test]
+    fn bfs() {
+        let mut g = DirectedGraph::new(200);
+        for (v, w, _) in random_edges(200, 600, 1) {
+            g.add_edge(v, w);
+        }
+        let seq = BFSPath::new(&g, 0);
+        for &t in THREADS.iter() {
+            let par = g.par_bfs(0, t);
+            for v in 0..200 {
+                assert_eq!(seq.dist_to(v), par.dist_to(v));
+                assert!(seq.path_to(v).eq(par.path_to(v)));
+            }
+        }
+    }
+
+    #[test]
+    fn cc() {
+        let mut g = UndirectedGraph::new(300);
+        for (v, w, _) in random_edges(300, 200, 2) {
+            g.add_edge(v, w);
+        }
+        let seq = g.cc();
+        for &t in THREADS.iter() {
+            let par = g.par_cc(t);
+            assert_eq!(seq.count(), par.count());
+            for v in 0..300 {
+                assert_eq!(seq.id(v), par.id(v));
+                assert_eq!(seq.size(v), par.size(v));
+            }
+        }
+    }
+
+    #[test]
+    fn mst() {
+        let mut g = EWUG::new(100);
+        for (v, w, weight) in random_edges(100, 400, 3) {
+            g.add_edge(&WE::new(v, w, weight));
+        }
+        let seq = g.boruvka_mst();
+        let kruskal = g.kruskal_mst();
+        assert_eq!(
+            (kruskal.weight() * 10.0).round(),
+            (seq.weight() * 10.0).round()
+        );
+        for &t in THREADS.iter() {
+            let par = g.par_boruvka_mst(t);
+            assert!(seq.edges().eq(par.edges()));
+        }
+    }
+
+    #[test]
+    fn all_sources_sp() {
+        let mut g = ENNWDG::new(50);
+        for (v, w, weight) in random_edges(50, 200, 4) {
+            g.add_edge(NNWDE::new(v, w, weight).unwrap());
+        }
+        for &t in THREADS.iter() {
+            let all = g.par_all_sources_sp(t);
+            assert_eq!(50, all.len());
+            for (s, p) in all.iter().enumerate() {
+                let seq = g.dijkstra_sp(s);
+                for v in 0..50 {
+                    assert_eq!(seq.dist_to(v), p.dist_to(v));
+                    assert!(seq.path_to(v).eq(p.path_to(v)));
+                }
+            }
+        }
+    }
+}
@@ -61,12 +61,115 @@ impl Path {
     }
 }
 
+/// Paths with fewest edges from s, found by BFS
+pub struct BFSPath {
+    s: usize,
+    dist_to: Vec<Option<usize>>,
+    edge_to: Vec<usize>,
+}
+
+impl BFSPath {
+    pub fn new<G, E>(g: &G, s: usize) -> Self
+    where
+        E: Edge,
+        G: Graph<Edge = E>,
+    {
+        let mut f = BFSPath {
+            s,
+            dist_to: vec![None; g.v_size()],
+            edge_to: vec![g.v_size(); g.v_size()],
+        };
+        f.bfs(g);
+        f
+    }
+
+    #[cfg(feature = "parallel")]
+    pub(super) fn from_parts(s: usize, dist_to: Vec<Option<usize>>, edge_to: Vec<usize>) -> Self {
+        BFSPath {
+            s,
+            dist_to,
+            edge_to,
+        }
+    }
+
+    pub fn has_path_to(&self, v: usize) -> bool {
+        self.dist_to[v].is_some()
+    }
+
+    /// number of edges on the shortest path
+    pub fn dist_to(&self, v: usize) -> Option<usize> {
+        self.dist_to[v]
+    }
+
+    pub fn path_to(&self, v: usize) -> impl Iterator<Item = usize> {
+        let mut path = Vec::new();
+        if self.has_path_to(v) {
+            let mut x = v;
+            while x != self.s {
+                path.push(x);
+                x = self.edge_to[x];
+            }
+            path.push(self.s);
+            path.reverse();
+        }
+
+        path.into_iter()
+    }
+}
+
+impl BFSPath {
+    fn bfs<G, E>(&mut self, g: &G)
+    where
+        E: Edge,
+        G: Graph<Edge = E>,
+    {
+        let mut queue = std::collections::VecDeque::new();
+        self.dist_to[self.s] = Some(0);
+        queue.push_back(self.s);
+        while let Some(v) = queue.pop_front() {
+            for e in g.adj(v) {
+                let w = e.other(v);
+                if self.dist_to[w].is_none() {
+                    self.dist_to[w] = self.dist_to[v].map(|d| d + 1);
+                    self.edge_to[w] = v;
+                    queue.push_back(w);
+                }
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::super::DirectedGraph;
     use super::super::UndirectedGraph;
     use super::*;
 
+    #[test]
+    fn bfs_paths() {
+        let mut g = UndirectedGraph::new(6);
+        g.add_edge(0, 1);
+        g.add_edge(1, 2);
+        g.add_edge(2, 3);
+        g.add_edge(0, 4);
+        g.add_edge(4, 3);
+
+        let f = BFSPath::new(&g, 0);
+        assert_eq!(Some(0), f.dist_to(0));
+        assert_eq!(Some(2), f.dist_to(3));
+        assert_eq!(vec![0, 4, 3], f.path_to(3).collect::<Vec<_>>());
+        assert_eq!(vec![0], f.path_to(0).collect::<Vec<_>>());
+        assert!(!f.has_path_to(5));
+        assert_eq!(None, f.path_to(5).next());
+
+        let mut g = DirectedGraph::new(3);
+        g.add_edge(0, 1);
+        g.add_edge(2, 0);
+        let f = BFSPath::new(&g, 0);
+        assert_eq!(Some(1), f.dist_to(1));
+        assert_eq!(None, f.dist_to(2));
+    }
+
     #[test]
     fn empty() {
         let g = UndirectedGraph::new(3);
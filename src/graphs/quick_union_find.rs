@@ -0,0 +1,309 @@
+use super::union_find::UnionFind;
+
+/// UnionFindAlgo
+///
+/// Common interface of union-find algorithms to compare them, `find` may
+/// compress paths so takes `&mut self`. The quick-find/quick-union variants
+/// count their array accesses, `UnionFind` doesn't.
+pub trait UnionFindAlgo {
+    fn new(n: usize) -> Self
+    where
+        Self: Sized;
+
+    /// number of elements
+    fn len(&self) -> usize;
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    fn count(&self) -> usize;
+
+    fn find(&mut self, p: usize) -> Option<usize>;
+    /// returns None if out of range, else whether two components are merged
+    fn union(&mut self, p: usize, q: usize) -> Option<bool>;
+
+    fn connected(&mut self, p: usize, q: usize) -> Option<bool> {
+        match (self.find(p), self.find(q)) {
+            (Some(p_root), Some(q_root)) => Some(p_root == q_root),
+            _ => None,
+        }
+    }
+
+    /// number of array accesses so far, None if not counted
+    fn accesses(&self) -> Option<usize>;
+}
+
+/// Quick Find
+///
+/// ids[]: component id of each element, `find` is O(1), but `union` walks
+/// through the whole array, O(N).
+pub struct QuickFindUF {
+    ids: Vec<usize>,
+    count: usize,
+    accesses: usize,
+}
+impl UnionFindAlgo for QuickFindUF {
+    fn new(n: usize) -> Self {
+        QuickFindUF {
+            ids: (0..n).collect(),
+            count: n,
+            accesses: 0,
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.ids.len()
+    }
+    fn count(&self) -> usize {
+        self.count
+    }
+
+    fn find(&mut self, p: usize) -> Option<usize> {
+        let id = self.ids.get(p).cloned();
+        self.accesses += 1;
+        id
+    }
+
+    fn union(&mut self, p: usize, q: usize) -> Option<bool> {
+        let (p_id, q_id) = (self.find(p)?, self.find(q)?);
+        if p_id == q_id {
+            return Some(false);
+        }
+
+        for id in self.ids.iter_mut() {
+            if *id == p_id {
+                *id = q_id;
+                self.accesses += 1;
+            }
+        }
+        self.accesses += self.ids.len();
+        self.count -= 1;
+        Some(true)
+    }
+
+    fn accesses(&self) -> Option<usize> {
+        Some(self.accesses)
+    }
+}
+
+/// Quick Union
+///
+/// ids[]: pointers to parent, `union` links root to root, trees may be as
+/// tall as N, so both `find` and `union` are O(N) in the worst case.
+pub struct QuickUnionUF {
+    ids: Vec<usize>,
+    count: usize,
+    accesses: usize,
+}
+impl QuickUnionUF {
+    fn root(&mut self, mut p: usize) -> usize {
+        self.accesses += 1;
+        while p != self.ids[p] {
+            p = self.ids[p];
+            self.accesses += 2;
+        }
+        p
+    }
+}
+impl UnionFindAlgo for QuickUnionUF {
+    fn new(n: usize) -> Self {
+        QuickUnionUF {
+            ids: (0..n).collect(),
+            count: n,
+            accesses: 0,
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.ids.len()
+    }
+    fn count(&self) -> usize {
+        self.count
+    }
+
+    fn find(&mut self, p: usize) -> Option<usize> {
+        if p >= self.ids.len() {
+            return None;
+        }
+        Some(self.root(p))
+    }
+
+    fn union(&mut self, p: usize, q: usize) -> Option<bool> {
+        let (p_root, q_root) = (self.find(p)?, self.find(q)?);
+        if p_root == q_root {
+            return Some(false);
+        }
+
+        self.ids[p_root] = q_root;
+        self.accesses += 1;
+        self.count -= 1;
+        Some(true)
+    }
+
+    fn accesses(&self) -> Option<usize> {
+        Some(self.accesses)
+    }
+}
+
+/// Weighted Quick Union
+///
+/// Quick union linking the smaller tree to the larger one, trees are at most
+/// logN tall.
+pub struct WeightedQuickUnionUF {
+    ids: Vec<usize>,
+    sizes: Vec<usize>,
+    count: usize,
+    accesses: usize,
+}
+impl WeightedQuickUnionUF {
+    fn root(&mut self, mut p: usize) -> usize {
+        self.accesses += 1;
+        while p != self.ids[p] {
+            p = self.ids[p];
+            self.accesses += 2;
+        }
+        p
+    }
+}
+impl UnionFindAlgo for WeightedQuickUnionUF {
+    fn new(n: usize) -> Self {
+        WeightedQuickUnionUF {
+            ids: (0..n).collect(),
+            sizes: vec![1; n],
+            count: n,
+            accesses: 0,
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.ids.len()
+    }
+    fn count(&self) -> usize {
+        self.count
+    }
+
+    fn find(&mut self, p: usize) -> Option<usize> {
+        if p >= self.ids.len() {
+            return None;
+        }
+        Some(self.root(p))
+    }
+
+    fn union(&mut self, p: usize, q: usize) -> Option<bool> {
+        let (mut p_root, mut q_root) = (self.find(p)?, self.find(q)?);
+        if p_root == q_root {
+            return Some(false);
+        }
+
+        if self.sizes[p_root] > self.sizes[q_root] {
+            std::mem::swap(&mut p_root, &mut q_root);
+        }
+        self.ids[p_root] = q_root;
+        self.sizes[q_root] += self.sizes[p_root];
+        self.accesses += 5;
+        self.count -= 1;
+        Some(true)
+    }
+
+    fn accesses(&self) -> Option<usize> {
+        Some(self.accesses)
+    }
+}
+
+impl UnionFindAlgo for UnionFind {
+    fn new(n: usize) -> Self {
+        UnionFind::new(n)
+    }
+
+    fn len(&self) -> usize {
+        UnionFind::len(self)
+    }
+    fn count(&self) -> usize {
+        UnionFind::count(self)
+    }
+
+    fn find(&mut self, p: usize) -> Option<usize> {
+        UnionFind::find(self, p)
+    }
+    fn union(&mut self, p: usize, q: usize) -> Option<bool> {
+        UnionFind::union(self, p, q)
+    }
+
+    fn accesses(&self) -> Option<usize> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAIRS: [(usize, usize); 11] = [
+        (4, 3),
+        (3, 8),
+        (6, 5),
+        (9, 4),
+        (2, 1),
+        (8, 9),
+        (5, 0),
+        (7, 2),
+        (6, 1),
+        (1, 0),
+        (6, 7),
+    ];
+
+    // tinyUF.txt
+    fn tiny<U: UnionFindAlgo>() -> U {
+        let mut uf = U::new(10);
+        let merged = PAIRS
+            .iter()
+            .filter(|&&(p, q)| uf.union(p, q).unwrap())
+            .count();
+        assert_eq!(8, merged);
+        uf
+    }
+
+    fn check<U: UnionFindAlgo>() {
+        let mut uf = tiny::<U>();
+        assert_eq!(10, uf.len());
+        assert_eq!(2, uf.count());
+        assert_eq!(Some(true), uf.connected(0, 7));
+        assert_eq!(Some(true), uf.connected(3, 9));
+        assert_eq!(Some(false), uf.connected(0, 9));
+        assert_eq!(None, uf.connected(0, 10));
+        assert_eq!(None, uf.union(10, 0));
+        assert_eq!(uf.find(1), uf.find(6));
+    }
+
+    #[test]
+    fn all() {
+        check::<QuickFindUF>();
+        check::<QuickUnionUF>();
+        check::<WeightedQuickUnionUF>();
+        check::<UnionFind>();
+        assert_eq!(None, tiny::<UnionFind>().accesses());
+    }
+
+    #[test]
+    fn accesses() {
+        let mut uf = QuickFindUF::new(10);
+        uf.find(3);
+        assert_eq!(Some(1), uf.accesses());
+        // 2 finds, scan 10 and change 1
+        uf.union(3, 4);
+        assert_eq!(Some(1 + 2 + 10 + 1), uf.accesses());
+
+        // a line 0 <- 1 <- ... <- 99 is the worst case of quick union
+        let n = 100;
+        let mut qu = QuickUnionUF::new(n);
+        let mut wqu = WeightedQuickUnionUF::new(n);
+        for i in 1..n {
+            qu.union(i - 1, i);
+            wqu.union(i - 1, i);
+        }
+        let (a, b) = (qu.accesses().unwrap(), wqu.accesses().unwrap());
+        qu.find(0);
+        wqu.find(0);
+        assert_eq!(1 + 2 * (n - 1), qu.accesses().unwrap() - a);
+        assert!(wqu.accesses().unwrap() - b <= 1 + 2);
+    }
+}
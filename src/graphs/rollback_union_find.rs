@@ -0,0 +1,146 @@
+/// Rollback Union Find
+///
+/// Union by size without path compression, so each `union` changes only one
+/// parent pointer and can be undone, `find` is O(logN).
+pub struct RollbackUnionFind {
+    parent: Vec<usize>,
+    size: Vec<usize>,
+    count: usize,
+    // merged (child, root) of each union, None if already connected
+    history: Vec<Option<(usize, usize)>>,
+}
+
+impl RollbackUnionFind {
+    pub fn new(cap: usize) -> Self {
+        RollbackUnionFind {
+            parent: (0..cap).collect(),
+            size: vec![1; cap],
+            count: cap,
+            history: Vec::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.parent.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.parent.is_empty()
+    }
+
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    pub fn find(&self, i: usize) -> Option<usize> {
+        if i >= self.parent.len() {
+            return None;
+        }
+        let mut i = i;
+        while i != self.parent[i] {
+            i = self.parent[i];
+        }
+        Some(i)
+    }
+
+    pub fn connected(&self, p: usize, q: usize) -> Option<bool> {
+        match (self.find(p), self.find(q)) {
+            (Some(p_root), Some(q_root)) => Some(p_root == q_root),
+            _ => None,
+        }
+    }
+
+    pub fn size(&self, i: usize) -> Option<usize> {
+        self.find(i).map(|r| self.size[r])
+    }
+
+    /// returns None if out of range, else whether two components are merged;
+    /// every call in range is recorded and undone by `undo`/`rollback`
+    pub fn union(&mut self, p: usize, q: usize) -> Option<bool> {
+        let (mut p_root, mut q_root) = (self.find(p)?, self.find(q)?);
+        if p_root == q_root {
+            self.history.push(None);
+            return Some(false);
+        }
+
+        if self.size[p_root] > self.size[q_root] {
+            std::mem::swap(&mut p_root, &mut q_root);
+        }
+        self.parent[p_root] = q_root;
+        self.size[q_root] += self.size[p_root];
+        self.count -= 1;
+        self.history.push(Some((p_root, q_root)));
+
+        Some(true)
+    }
+
+    /// undo the last `union`, returns false if nothing to undo
+    pub fn undo(&mut self) -> bool {
+        match self.history.pop() {
+            Some(Some((child, root))) => {
+                self.parent[child] = child;
+                self.size[root] -= self.size[child];
+                self.count += 1;
+                true
+            }
+            Some(None) => true,
+            None => false,
+        }
+    }
+
+    /// a point to `rollback` to
+    pub fn snapshot(&self) -> usize {
+        self.history.len()
+    }
+
+    /// undo all unions after the snapshot
+    pub fn rollback(&mut self, snapshot: usize) {
+        while self.history.len() > snapshot {
+            self.undo();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn none() {
+        let mut uf = RollbackUnionFind::new(2);
+        assert_eq!(None, uf.find(2));
+        assert_eq!(None, uf.union(0, 2));
+        assert_eq!(0, uf.snapshot());
+        assert!(!uf.undo());
+    }
+
+    #[test]
+    fn rollback() {
+        let mut uf = RollbackUnionFind::new(6);
+        uf.union(0, 1);
+        uf.union(2, 3);
+        let snapshot = uf.snapshot();
+        assert_eq!(4, uf.count());
+
+        assert_eq!(Some(true), uf.union(1, 2));
+        assert_eq!(Some(false), uf.union(0, 3));
+        assert_eq!(Some(true), uf.union(4, 5));
+        assert_eq!(Some(true), uf.connected(0, 3));
+        assert_eq!(Some(4), uf.size(3));
+        assert_eq!(2, uf.count());
+
+        assert!(uf.undo());
+        assert_eq!(Some(false), uf.connected(4, 5));
+        assert_eq!(Some(true), uf.connected(0, 3));
+
+        uf.rollback(snapshot);
+        assert_eq!(4, uf.count());
+        assert_eq!(Some(false), uf.connected(0, 3));
+        assert_eq!(Some(true), uf.connected(2, 3));
+        assert_eq!(Some(2), uf.size(0));
+        assert_eq!(Some(2), uf.size(3));
+
+        uf.rollback(0);
+        assert_eq!(6, uf.count());
+        assert!((0..6).all(|i| uf.find(i) == Some(i)));
+    }
+}
@@ -6,7 +6,7 @@
 //! connected.
 use super::base::*;
 use super::dfs_order::*;
-use super::DirectedGraph;
+use super::views::ReversedView;
 
 /// HasSCC
 ///
@@ -68,15 +68,8 @@ impl SCC {
     where
         G: Graph<Edge = E>,
     {
-        let mut dg = DirectedGraph::new(g.v_size());
-        for v in 0..g.v_size() {
-            for e in g.adj(v) {
-                dg.add_edge(e.to(), e.from());
-            }
-        }
-
         let mut marked = vec![false; g.v_size()];
-        for v in dg.reversed_post_order() {
+        for v in ReversedView::new(g).reversed_post_order() {
             if !marked[v] {
                 self.sizes.push(0);
                 self.dfs(g, v, &mut marked);
@@ -0,0 +1,581 @@
+//! Rooted Tree
+//!
+//! A tree view of an acyclic undirected graph (or a MST) hanging from a root,
+//! for lowest common ancestor and path queries, diameter and centroid.
+//!
+//! Only vertices reachable from the root are in the tree, queries involving
+//! other vertices return `None`.
+use super::base::*;
+use super::cycle::Cycle;
+
+use std::cell::OnceCell;
+use std::collections::VecDeque;
+
+/// RootedTree
+///
+/// `up[k][v]` is the 2^k-th ancestor of v (root is the ancestor of itself),
+/// for binary lifting. `euler[]` is the DFS tour visiting a vertex again after
+/// each child, with `sparse[k][i]` the shallowest vertex in
+/// `euler[i..i + 2^k]`, LCA of u,v is the shallowest vertex between their first
+/// visits.
+pub struct RootedTree<E: Undirected> {
+    root: Vertex,
+    parent_edge: Vec<Option<E>>,
+    depth: Vec<Option<usize>>,
+    children: Vec<Vec<Vertex>>,
+    order: Vec<Vertex>,
+    up: Vec<Vec<Vertex>>,
+    first: Vec<usize>,
+    euler: Vec<Vertex>,
+    sparse: Vec<Vec<Vertex>>,
+    weighted: OnceCell<WeightTables<E>>,
+}
+
+// weighted depth, and `up_max[k][v]` heaviest edge of the 2^k edges above v
+struct WeightTables<E> {
+    depth: Vec<f64>,
+    up_max: Vec<Vec<Option<E>>>,
+}
+
+impl<E: Undirected> RootedTree<E> {
+    /// Tree of vertices reachable from `root`, error if a cycle is reachable
+    pub fn new<G>(g: &G, root: Vertex) -> Result<Self, Cycle>
+    where
+        G: Graph<Edge = E>,
+    {
+        let adj = (0..g.v_size()).map(|v| g.adj(v).collect()).collect();
+        Self::build(adj, root)
+    }
+
+    /// Tree from edges, e.g. `mst.edges().cloned()` of a MST
+    pub fn from_edges<I>(v: usize, edges: I, root: Vertex) -> Result<Self, Cycle>
+    where
+        I: IntoIterator<Item = E>,
+    {
+        let mut adj = vec![Vec::new(); v];
+        for e in edges {
+            let (v, w) = e.vertices();
+            adj[v].push(e);
+            if v != w {
+                adj[w].push(e);
+            }
+        }
+        Self::build(adj, root)
+    }
+
+    pub fn root(&self) -> Vertex {
+        self.root
+    }
+    /// number of vertices in the tree
+    pub fn size(&self) -> usize {
+        self.order.len()
+    }
+    pub fn contains(&self, v: Vertex) -> bool {
+        self.depth[v].is_some()
+    }
+    pub fn depth(&self, v: Vertex) -> Option<usize> {
+        self.depth[v]
+    }
+    pub fn parent(&self, v: Vertex) -> Option<Vertex> {
+        self.parent_edge[v].map(|e| e.other(v))
+    }
+    pub fn parent_edge(&self, v: Vertex) -> Option<E> {
+        self.parent_edge[v]
+    }
+    pub fn children(&self, v: Vertex) -> impl Iterator<Item = &Vertex> {
+        self.children[v].iter()
+    }
+
+    /// LCA by binary lifting, O(logV)
+    pub fn lca(&self, u: Vertex, v: Vertex) -> Option<Vertex> {
+        let (du, dv) = (self.depth[u]?, self.depth[v]?);
+        let (mut u, mut v) = if du >= dv { (u, v) } else { (v, u) };
+        u = self.lift(u, du.max(dv) - du.min(dv));
+        if u == v {
+            return Some(u);
+        }
+        for k in (0..self.up.len()).rev() {
+            if self.up[k][u] != self.up[k][v] {
+                u = self.up[k][u];
+                v = self.up[k][v];
+            }
+        }
+        Some(self.up[0][u])
+    }
+
+    /// LCA by range minimum query on the Euler tour, O(1)
+    pub fn lca_euler(&self, u: Vertex, v: Vertex) -> Option<Vertex> {
+        self.depth[u]?;
+        self.depth[v]?;
+        let (i, j) = if self.first[u] <= self.first[v] {
+            (self.first[u], self.first[v])
+        } else {
+            (self.first[v], self.first[u])
+        };
+        let k = log2(j - i + 1);
+        Some(self.shallower(self.sparse[k][i], self.sparse[k][j + 1 - (1 << k)]))
+    }
+
+    /// number of edges on the path u->v
+    pub fn distance(&self, u: Vertex, v: Vertex) -> Option<usize> {
+        let a = self.lca(u, v)?;
+        Some(self.depth[u]? + self.depth[v]? - 2 * self.depth[a]?)
+    }
+
+    /// 0th ancestor is v itself, None if k > depth(v)
+    pub fn kth_ancestor(&self, v: Vertex, k: usize) -> Option<Vertex> {
+        if k > self.depth[v]? {
+            return None;
+        }
+        Some(self.lift(v, k))
+    }
+
+    /// vertices on the path u->v
+    pub fn path(&self, u: Vertex, v: Vertex) -> Option<Vec<Vertex>> {
+        let a = self.lca(u, v)?;
+        let mut path = self.climb(u, a);
+        path.push(a);
+        let mut down = self.climb(v, a);
+        down.reverse();
+        path.extend(down);
+        Some(path)
+    }
+
+    /// longest path in edges, as (length, one end, other end)
+    pub fn diameter(&self) -> (usize, Vertex, Vertex) {
+        // the deepest vertex is an end of some longest path
+        let a = self.farthest(|v| self.depth[v].unwrap());
+        let b = self.farthest(|v| self.distance(a, v).unwrap());
+        (self.distance(a, b).unwrap(), a, b)
+    }
+
+    /// vertices whose removal leaves components of at most size/2 vertices,
+    /// one or two of them
+    pub fn centroids(&self) -> Vec<Vertex> {
+        let n = self.size();
+        let mut sizes = vec![1; self.depth.len()];
+        for &v in self.order.iter().rev() {
+            if let Some(p) = self.parent(v) {
+                sizes[p] += sizes[v];
+            }
+        }
+        let mut centroids = self
+            .order
+            .iter()
+            .cloned()
+            .filter(|&v| {
+                let below = self.children[v].iter().map(|&c| sizes[c]).max();
+                below.unwrap_or(0).max(n - sizes[v]) * 2 <= n
+            })
+            .collect::<Vec<_>>();
+        centroids.sort_unstable();
+        centroids
+    }
+}
+
+// private methods
+impl<E: Undirected> RootedTree<E> {
+    fn build(adj: Vec<Vec<E>>, root: Vertex) -> Result<Self, Cycle> {
+        let n = adj.len();
+        let mut t = RootedTree {
+            root,
+            parent_edge: vec![None; n],
+            depth: vec![None; n],
+            children: vec![Vec::new(); n],
+            order: Vec::new(),
+            up: Vec::new(),
+            first: vec![0; n],
+            euler: Vec::new(),
+            sparse: Vec::new(),
+            weighted: OnceCell::new(),
+        };
+        t.bfs(&adj)?;
+        t.init_lifting();
+        t.init_euler();
+        Ok(t)
+    }
+
+    fn bfs(&mut self, adj: &[Vec<E>]) -> Result<(), Cycle> {
+        let mut queue = VecDeque::new();
+        self.depth[self.root] = Some(0);
+        queue.push_back(self.root);
+        while let Some(v) = queue.pop_front() {
+            self.order.push(v);
+            // the edge to parent is seen once, a parallel edge is a cycle
+            let mut parent_seen = false;
+            for &e in adj[v].iter() {
+                let w = e.other(v);
+                if self.depth[w].is_none() {
+                    self.depth[w] = Some(self.depth[v].unwrap() + 1);
+                    self.parent_edge[w] = Some(e);
+                    self.children[v].push(w);
+                    queue.push_back(w);
+                } else if Some(w) == self.parent(v) && !parent_seen {
+                    parent_seen = true;
+                } else {
+                    return Err(self.cycle(v, w));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // v and w are in the tree, path v->w closed by edge w-v
+    fn cycle(&self, v: Vertex, w: Vertex) -> Cycle {
+        // `up` is not ready, climb step by step
+        let (mut a, mut b) = (v, w);
+        while a != b {
+            if self.depth[a] >= self.depth[b] {
+                a = self.parent(a).unwrap();
+            } else {
+                b = self.parent(b).unwrap();
+            }
+        }
+        let mut path = self.climb(v, a);
+        path.push(a);
+        let mut down = self.climb(w, a);
+        down.reverse();
+        path.extend(down);
+        path.push(v);
+        Cycle::from_path(path)
+    }
+
+    // vertices from v up to ancestor a, a excluded
+    fn climb(&self, mut v: Vertex, a: Vertex) -> Vec<Vertex> {
+        let mut path = Vec::new();
+        while v != a {
+            path.push(v);
+            v = self.parent(v).unwrap();
+        }
+        path
+    }
+
+    fn init_lifting(&mut self) {
+        let n = self.depth.len();
+        let parents = (0..n).map(|v| self.parent(v).unwrap_or(v)).collect();
+        self.up.push(parents);
+        for k in 1..log2(n.max(1)) + 1 {
+            let prev = &self.up[k - 1];
+            let next = (0..n).map(|v| prev[prev[v]]).collect();
+            self.up.push(next);
+        }
+    }
+
+    fn init_euler(&mut self) {
+        let mut stack = vec![(self.root, 0)];
+        while let Some((v, i)) = stack.pop() {
+            if i == 0 {
+                self.first[v] = self.euler.len();
+            }
+            self.euler.push(v);
+            if i < self.children[v].len() {
+                stack.push((v, i + 1));
+                stack.push((self.children[v][i], 0));
+            }
+        }
+
+        self.sparse.push(self.euler.clone());
+        let m = self.euler.len();
+        for k in 1..log2(m) + 1 {
+            let prev = &self.sparse[k - 1];
+            let next = (0..m + 1 - (1 << k))
+                .map(|i| self.shallower(prev[i], prev[i + (1 << (k - 1))]))
+                .collect();
+            self.sparse.push(next);
+        }
+    }
+
+    fn shallower(&self, u: Vertex, v: Vertex) -> Vertex {
+        if self.depth[u] <= self.depth[v] {
+            u
+        } else {
+            v
+        }
+    }
+
+    fn lift(&self, mut v: Vertex, d: usize) -> Vertex {
+        for (k, up) in self.up.iter().enumerate() {
+            if d & (1 << k) != 0 {
+                v = up[v];
+            }
+        }
+        v
+    }
+
+    fn farthest<F, T>(&self, dist: F) -> Vertex
+    where
+        F: Fn(Vertex) -> T,
+        T: PartialOrd,
+    {
+        let mut best = self.root;
+        for &v in self.order.iter() {
+            if dist(v) > dist(best) {
+                best = v;
+            }
+        }
+        best
+    }
+}
+
+impl<E: Undirected + Weighted> RootedTree<E> {
+    /// sum of weights on the path from root to v
+    pub fn weighted_depth(&self, v: Vertex) -> Option<f64> {
+        self.depth[v]?;
+        Some(self.weight_tables().depth[v])
+    }
+
+    /// sum of weights on the path u->v
+    pub fn weighted_distance(&self, u: Vertex, v: Vertex) -> Option<f64> {
+        let a = self.lca(u, v)?;
+        let d = &self.weight_tables().depth;
+        Some(d[u] + d[v] - 2.0 * d[a])
+    }
+
+    /// heaviest edge on the path u->v, None if u == v, O(logV)
+    pub fn max_edge(&self, u: Vertex, v: Vertex) -> Option<E> {
+        let a = self.lca(u, v)?;
+        let up = self.max_edge_up(u, self.depth[u]? - self.depth[a]?);
+        let down = self.max_edge_up(v, self.depth[v]? - self.depth[a]?);
+        heavier(up, down)
+    }
+
+    /// longest path by weights as (weight, one end, other end), assuming
+    /// non-negative weights
+    pub fn weighted_diameter(&self) -> (f64, Vertex, Vertex) {
+        let d = &self.weight_tables().depth;
+        let a = self.farthest(|v| d[v]);
+        let b = self.farthest(|v| self.weighted_distance(a, v).unwrap());
+        (self.weighted_distance(a, b).unwrap(), a, b)
+    }
+
+    fn weight_tables(&self) -> &WeightTables<E> {
+        self.weighted.get_or_init(|| {
+            let n = self.depth.len();
+            let mut depth = vec![0.0; n];
+            for &v in self.order.iter() {
+                if let Some(e) = self.parent_edge[v] {
+                    depth[v] = depth[e.other(v)] + e.weight();
+                }
+            }
+
+            let mut up_max = vec![self.parent_edge.clone()];
+            for k in 1..self.up.len() {
+                let prev: &Vec<Option<E>> = &up_max[k - 1];
+                let next = (0..n)
+                    .map(|v| heavier(prev[v], prev[self.up[k - 1][v]]))
+                    .collect();
+                up_max.push(next);
+            }
+            WeightTables { depth, up_max }
+        })
+    }
+
+    // heaviest edge of the d edges above v
+    fn max_edge_up(&self, mut v: Vertex, d: usize) -> Option<E> {
+        let up_max = &self.weight_tables().up_max;
+        let mut max = None;
+        for (k, up) in self.up.iter().enumerate() {
+            if d & (1 << k) != 0 {
+                max = heavier(max, up_max[k][v]);
+                v = up[v];
+            }
+        }
+        max
+    }
+}
+
+fn heavier<E: Weighted>(a: Option<E>, b: Option<E>) -> Option<E> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(if b.weight() > a.weight() { b } else { a }),
+        (a, b) => a.or(b),
+    }
+}
+
+// floor(log2(n)), n > 0
+fn log2(n: usize) -> usize {
+    (usize::BITS - 1 - n.leading_zeros()) as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::mst::MST;
+    use super::super::EdgeWeightedUndirectedGraph as EWUG;
+    use super::super::WeightedUndirectedEdge as WE;
+    use super::super::{UndirectedEdge, UndirectedGraph};
+    use super::*;
+
+    //        0
+    //      / | \
+    //     1  2  3
+    //    / \     \
+    //   4   5     6
+    //   |
+    //   7
+    fn tree() -> RootedTree<UndirectedEdge> {
+        let mut g = UndirectedGraph::new(9);
+        for &(v, w) in [(0, 1), (0, 2), (0, 3), (1, 4), (1, 5), (3, 6), (4, 7)].iter() {
+            g.add_edge(v, w);
+        }
+        RootedTree::new(&g, 0).unwrap()
+    }
+
+    #[test]
+    fn basic() {
+        let t = tree();
+        assert_eq!(0, t.root());
+        assert_eq!(8, t.size());
+        assert!(!t.contains(8));
+        assert_eq!(None, t.parent(0));
+        assert_eq!(Some(4), t.parent(7));
+        assert_eq!(Some(3), t.depth(7));
+        assert_eq!(vec![4, 5], t.children(1).cloned().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn lca() {
+        let t = tree();
+        let cases = [
+            (7, 5, 1),
+            (7, 6, 0),
+            (4, 7, 4),
+            (2, 2, 2),
+            (5, 1, 1),
+            (0, 6, 0),
+        ];
+        for &(u, v, a) in cases.iter() {
+            assert_eq!(Some(a), t.lca(u, v));
+            assert_eq!(Some(a), t.lca(v, u));
+            assert_eq!(Some(a), t.lca_euler(u, v));
+            assert_eq!(Some(a), t.lca_euler(v, u));
+        }
+        assert_eq!(None, t.lca(8, 0));
+        assert_eq!(None, t.lca_euler(0, 8));
+    }
+
+    #[test]
+    fn paths() {
+        let t = tree();
+        assert_eq!(Some(5), t.distance(7, 6));
+        assert_eq!(Some(0), t.distance(3, 3));
+        assert_eq!(None, t.distance(8, 3));
+        assert_eq!(Some(vec![7, 4, 1, 0, 3, 6]), t.path(7, 6));
+        assert_eq!(Some(vec![2]), t.path(2, 2));
+
+        assert_eq!(Some(7), t.kth_ancestor(7, 0));
+        assert_eq!(Some(1), t.kth_ancestor(7, 2));
+        assert_eq!(Some(0), t.kth_ancestor(7, 3));
+        assert_eq!(None, t.kth_ancestor(7, 4));
+    }
+
+    #[test]
+    fn diameter_centroids() {
+        let t = tree();
+        let (len, a, b) = t.diameter();
+        assert_eq!(5, len);
+        assert_eq!(Some(5), t.distance(a, b));
+        // removing 1 leaves {4,7}, {5}, {0,2,3,6}
+        assert_eq!(vec![0, 1], t.centroids());
+
+        // path 0-1-2-3 has two centroids
+        let mut g = UndirectedGraph::new(4);
+        g.add_edge(0, 1);
+        g.add_edge(1, 2);
+        g.add_edge(2, 3);
+        let t = RootedTree::new(&g, 3).unwrap();
+        assert_eq!(vec![1, 2], t.centroids());
+        assert_eq!((3, 0, 3), t.diameter());
+
+        let t = RootedTree::new(&UndirectedGraph::new(1), 0).unwrap();
+        assert_eq!(vec![0], t.centroids());
+        assert_eq!((0, 0, 0), t.diameter());
+    }
+
+    #[test]
+    fn cycles() {
+        let mut g = UndirectedGraph::new(4);
+        g.add_edge(0, 1);
+        g.add_edge(1, 2);
+        g.add_edge(2, 3);
+        g.add_edge(3, 1);
+        let c = RootedTree::new(&g, 0).err().unwrap();
+        let c = c.iter().cloned().collect::<Vec<_>>();
+        assert_eq!(c.first(), c.last());
+        assert_eq!(vec![2, 1, 3, 2], c);
+
+        let mut g = UndirectedGraph::new(2);
+        g.add_edge(0, 1);
+        g.add_edge(0, 1);
+        assert!(RootedTree::new(&g, 0).is_err());
+
+        let mut g = UndirectedGraph::new(2);
+        g.add_edge(1, 1);
+        assert!(RootedTree::new(&g, 0).is_ok());
+        assert!(RootedTree::new(&g, 1).is_err());
+    }
+
+    #[test]
+    fn weighted() {
+        let mut g = EWUG::new(6);
+        for &(v, w, weight) in [
+            (0, 1, 4.0),
+            (0, 2, 1.0),
+            (1, 2, 2.0),
+            (1, 3, 5.0),
+            (2, 4, 8.0),
+            (3, 4, 3.0),
+            (3, 5, 6.0),
+            (4, 5, 7.0),
+        ]
+        .iter()
+        {
+            g.add_edge(&WE::new(v, w, weight));
+        }
+        // MST: 0-2(1), 2-1(2), 1-3(5), 3-4(3), 3-5(6)
+        let mst = g.kruskal_mst();
+        let t = RootedTree::from_edges(6, mst.edges().cloned(), 0).unwrap();
+        let p = RootedTree::from_edges(6, g.prim_mst().edges(), 0).unwrap();
+        for t in [&t, &p].iter() {
+            assert_eq!(Some(3), t.lca(4, 5));
+            assert_eq!(Some(8.0), t.weighted_depth(3));
+            assert_eq!(Some(9.0), t.weighted_distance(4, 5));
+            assert_eq!(Some(14.0), t.weighted_distance(0, 5));
+
+            assert_eq!(6.0, t.max_edge(4, 5).unwrap().weight());
+            assert_eq!(5.0, t.max_edge(0, 4).unwrap().weight());
+            assert_eq!(2.0, t.max_edge(2, 1).unwrap().weight());
+            assert!(t.max_edge(1, 1).is_none());
+
+            let (w, a, b) = t.weighted_diameter();
+            assert_eq!(14.0, w);
+            assert_eq!((0, 5), (a.min(b), a.max(b)));
+        }
+    }
+
+    #[test]
+    fn random_lca() {
+        use rand::{Rng, SeedableRng, StdRng};
+        let mut rng = StdRng::from_seed(&[31][..]);
+        let n = 300;
+        let mut g = UndirectedGraph::new(n);
+        for v in 1..n {
+            g.add_edge(rng.gen_range(0, v), v);
+        }
+        let t = RootedTree::new(&g, 0).unwrap();
+        for _ in 0..1000 {
+            let (u, v) = (rng.gen_range(0, n), rng.gen_range(0, n));
+            let a = t.lca(u, v);
+            assert_eq!(a, t.lca_euler(u, v));
+
+            // naive: climb the deeper one
+            let (mut x, mut y) = (u, v);
+            while x != y {
+                if t.depth(x) >= t.depth(y) {
+                    x = t.parent(x).unwrap();
+                } else {
+                    y = t.parent(y).unwrap();
+                }
+            }
+            assert_eq!(Some(x), a);
+        }
+    }
+}
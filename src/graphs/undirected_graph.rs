@@ -14,6 +14,10 @@ impl UndirectedGraph {
 		}
 	}
 
+	pub(super) fn from_adj(e: usize, adj: Vec<Vec<UndirectedEdge>>) -> Self {
+		UndirectedGraph { e, adj }
+	}
+
 	pub fn add_edge(&mut self, v: usize, w: usize) {
 		self.adj[v].push(UndirectedEdge::new(v, w));
 		self.adj[w].push(UndirectedEdge::new(v, w));
@@ -3,6 +3,8 @@ use std::cmp::Ordering::{Greater, Less};
 struct Node {
     parent: usize,
     rank: usize,
+    size: usize,
+    next: usize, // circular list of members of the same component
 }
 /// Union Find
 ///
@@ -14,16 +16,41 @@ pub struct UnionFind {
 
 impl UnionFind {
     pub fn new(cap: usize) -> Self {
-        UnionFind {
-            ids: (0..cap).map(|i| Node { parent: i, rank: 0 }).collect(),
-            count: cap,
+        let mut uf = UnionFind {
+            ids: Vec::with_capacity(cap),
+            count: 0,
+        };
+        for _ in 0..cap {
+            uf.make_set();
         }
+        uf
+    }
+
+    /// number of elements
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
     }
 
     pub fn count(&self) -> usize {
         self.count
     }
 
+    /// add a new element in its own component, returns its id
+    pub fn make_set(&mut self) -> usize {
+        let i = self.ids.len();
+        self.ids.push(Node {
+            parent: i,
+            rank: 0,
+            size: 1,
+            next: i,
+        });
+        self.count += 1;
+        i
+    }
+
     pub fn find(&self, i: usize) -> Option<usize> {
         if i >= self.ids.len() {
             None
@@ -58,26 +85,46 @@ impl UnionFind {
         }
     }
 
-    pub fn union(&mut self, p: usize, q: usize) {
-        if p > self.ids.capacity() || q > self.ids.capacity() {
-            return;
+    /// size of the component containing i
+    pub fn size(&self, i: usize) -> Option<usize> {
+        self.find(i).map(|r| self.ids[r].size)
+    }
+
+    /// members of the component containing i, starting from i
+    pub fn members(&self, i: usize) -> impl Iterator<Item = usize> + '_ {
+        let n = self.size(i).unwrap_or(0);
+        std::iter::successors(Some(i), move |&j| Some(self.ids[j].next)).take(n)
+    }
+
+    /// returns None if out of range, else whether two components are merged
+    pub fn union(&mut self, p: usize, q: usize) -> Option<bool> {
+        if p >= self.ids.len() || q >= self.ids.len() {
+            return None;
         }
 
         let p_root = self._find_mut(p);
         let q_root = self._find_mut(q);
         if p_root == q_root {
-            return;
+            return Some(false);
         }
 
-        match self.ids[p_root].rank.cmp(&self.ids[q_root].rank) {
-            Less => self.ids[p_root].parent = q_root,
-            Greater => self.ids[q_root].parent = p_root,
+        let (child, root) = match self.ids[p_root].rank.cmp(&self.ids[q_root].rank) {
+            Less => (p_root, q_root),
+            Greater => (q_root, p_root),
             _ => {
-                self.ids[p_root].parent = q_root;
                 self.ids[q_root].rank += 1;
+                (p_root, q_root)
             }
-        }
+        };
+        self.ids[child].parent = root;
+        self.ids[root].size += self.ids[child].size;
+        // splice two circular lists
+        let next = self.ids[child].next;
+        self.ids[child].next = self.ids[root].next;
+        self.ids[root].next = next;
         self.count -= 1;
+
+        Some(true)
     }
 }
 
@@ -96,6 +143,11 @@ mod tests {
         let uf = UnionFind::new(1);
         assert_eq!(None, uf.find(1));
         assert_eq!(None, uf.connected(1, 0));
+        assert_eq!(None, uf.size(1));
+
+        let mut uf = UnionFind::new(2);
+        assert_eq!(None, uf.union(0, 2));
+        assert_eq!(2, uf.count());
     }
 
     #[test]
@@ -145,4 +197,36 @@ mod tests {
         assert_eq!(root, uf.find(4));
         assert_eq!(root, uf.find(5));
     }
+
+    #[test]
+    fn size_and_members() {
+        let mut uf = UnionFind::new(5);
+        assert_eq!(Some(true), uf.union(0, 1));
+        assert_eq!(Some(true), uf.union(3, 4));
+        assert_eq!(Some(true), uf.union(1, 4));
+        assert_eq!(Some(false), uf.union(0, 3));
+        assert_eq!(Some(4), uf.size(3));
+        assert_eq!(Some(1), uf.size(2));
+
+        let mut members = uf.members(4).collect::<Vec<_>>();
+        assert_eq!(4, members[0]);
+        members.sort_unstable();
+        assert_eq!(vec![0, 1, 3, 4], members);
+        assert_eq!(vec![2], uf.members(2).collect::<Vec<_>>());
+        assert_eq!(0, uf.members(5).count());
+    }
+
+    #[test]
+    fn make_set() {
+        let mut uf = UnionFind::new(0);
+        assert!(uf.is_empty());
+        assert_eq!(0, uf.make_set());
+        assert_eq!(1, uf.make_set());
+        assert_eq!(2, uf.count());
+        uf.union(0, 1);
+        assert_eq!(2, uf.make_set());
+        assert_eq!(3, uf.len());
+        assert_eq!(2, uf.count());
+        assert_eq!(Some(false), uf.connected(1, 2));
+    }
 }
@@ -0,0 +1,315 @@
+//! Graph Views
+//!
+//! Adapters implementing `Graph` over borrowed graphs: reversed, induced
+//! subgraph, edge-filtered, and union of two graphs. Only the reversed view
+//! keeps a copy of edges, the others map edges of `g.adj()` on the fly.
+//!
+//! `e_size()` of views counts an edge once in the adjacency of its first
+//! vertex, i.e. `e.vertices().0`, so undirected edges are not counted twice.
+use super::base::*;
+
+/// Reversed view of a directed graph
+///
+/// Keeps the reversed in-coming edges of each vertex, compressed in
+/// `offsets[]`/`edges[]` by a counting sort, so `adj(v)` is O(indegree).
+#[derive(Clone)]
+pub struct ReversedView<'a, G: Graph> {
+    g: &'a G,
+    offsets: Vec<usize>,
+    edges: Vec<G::Edge>,
+}
+impl<'a, G> ReversedView<'a, G>
+where
+    G: Graph,
+    G::Edge: Directed,
+{
+    pub fn new(g: &'a G) -> Self {
+        let n = g.v_size();
+        let mut offsets = vec![0; n + 1];
+        for e in g.edges() {
+            offsets[e.to() + 1] += 1;
+        }
+        for v in 0..n {
+            offsets[v + 1] += offsets[v];
+        }
+
+        // counting sort of the reversed edges by source, any edge fills the
+        // slots before they are placed
+        let mut edges = match g.edges().next() {
+            Some(e) => vec![e; offsets[n]],
+            None => Vec::new(),
+        };
+        let mut next = offsets.clone();
+        for e in g.edges() {
+            let v = e.to();
+            edges[next[v]] = e.reversed();
+            next[v] += 1;
+        }
+
+        ReversedView { g, offsets, edges }
+    }
+}
+impl<'a, G> Graph for ReversedView<'a, G>
+where
+    G: Graph,
+    G::Edge: Directed,
+{
+    type Edge = G::Edge;
+
+    fn v_size(&self) -> usize {
+        self.g.v_size()
+    }
+    fn e_size(&self) -> usize {
+        self.g.e_size()
+    }
+
+    fn adj(&self, v: usize) -> Box<dyn Iterator<Item = Self::Edge> + '_> {
+        let edges = &self.edges[self.offsets[v]..self.offsets[v + 1]];
+        Box::new(edges.iter().copied())
+    }
+}
+
+/// Induced subgraph of a vertex subset
+///
+/// Vertices are renumbered `0..len` in the order given, edges between them
+/// are relabeled on the fly.
+#[derive(Clone)]
+pub struct SubGraph<'a, G> {
+    g: &'a G,
+    vertices: Vec<Vertex>,
+    index: Vec<Option<Vertex>>,
+    e: usize,
+}
+impl<'a, G> SubGraph<'a, G>
+where
+    G: Graph,
+    G::Edge: Relabel,
+{
+    /// duplicated vertices are ignored
+    pub fn new<I>(g: &'a G, vertices: I) -> Self
+    where
+        I: IntoIterator<Item = Vertex>,
+    {
+        let mut s = SubGraph {
+            g,
+            vertices: Vec::new(),
+            index: vec![None; g.v_size()],
+            e: 0,
+        };
+        for v in vertices {
+            if s.index[v].is_none() {
+                s.index[v] = Some(s.vertices.len());
+                s.vertices.push(v);
+            }
+        }
+        s.e = count_edges(&s);
+        s
+    }
+
+    /// vertex id in the original graph
+    pub fn original(&self, v: Vertex) -> Vertex {
+        self.vertices[v]
+    }
+    /// vertex id in the subgraph
+    pub fn index_of(&self, v: Vertex) -> Option<Vertex> {
+        self.index[v]
+    }
+}
+impl<'a, G> Graph for SubGraph<'a, G>
+where
+    G: Graph,
+    G::Edge: Relabel,
+{
+    type Edge = G::Edge;
+
+    fn v_size(&self) -> usize {
+        self.vertices.len()
+    }
+    fn e_size(&self) -> usize {
+        self.e
+    }
+
+    fn adj(&self, v: usize) -> Box<dyn Iterator<Item = Self::Edge> + '_> {
+        Box::new(self.g.adj(self.vertices[v]).filter_map(move |e| {
+            let (a, b) = e.vertices();
+            Some(e.relabel(self.index[a]?, self.index[b]?))
+        }))
+    }
+}
+
+/// Edge-filtered view, only edges satisfying the predicate remain
+#[derive(Clone)]
+pub struct FilteredGraph<'a, G, P> {
+    g: &'a G,
+    predicate: P,
+}
+impl<'a, G, P> FilteredGraph<'a, G, P>
+where
+    G: Graph,
+    P: Fn(&G::Edge) -> bool + Clone,
+{
+    pub fn new(g: &'a G, predicate: P) -> Self {
+        FilteredGraph { g, predicate }
+    }
+}
+impl<'a, G, P> Graph for FilteredGraph<'a, G, P>
+where
+    G: Graph,
+    P: Fn(&G::Edge) -> bool + Clone,
+{
+    type Edge = G::Edge;
+
+    fn v_size(&self) -> usize {
+        self.g.v_size()
+    }
+    /// O(V+E) by walking all adjacency
+    fn e_size(&self) -> usize {
+        count_edges(self)
+    }
+
+    fn adj(&self, v: usize) -> Box<dyn Iterator<Item = Self::Edge> + '_> {
+        Box::new(self.g.adj(v).filter(move |e| (self.predicate)(e)))
+    }
+}
+
+/// Union of two graphs with the same edge type on shared vertex ids, vertex
+/// v of the union has edges of v in both graphs
+#[derive(Clone)]
+pub struct UnionGraph<'a, G, H> {
+    g: &'a G,
+    h: &'a H,
+}
+impl<'a, G, H> UnionGraph<'a, G, H>
+where
+    G: Graph,
+    H: Graph<Edge = G::Edge>,
+{
+    pub fn new(g: &'a G, h: &'a H) -> Self {
+        UnionGraph { g, h }
+    }
+}
+impl<'a, G, H> Graph for UnionGraph<'a, G, H>
+where
+    G: Graph,
+    H: Graph<Edge = G::Edge>,
+{
+    type Edge = G::Edge;
+
+    fn v_size(&self) -> usize {
+        self.g.v_size().max(self.h.v_size())
+    }
+    fn e_size(&self) -> usize {
+        self.g.e_size() + self.h.e_size()
+    }
+
+    fn adj(&self, v: usize) -> Box<dyn Iterator<Item = Self::Edge> + '_> {
+        let g = (v < self.g.v_size()).then(|| self.g.adj(v));
+        let h = (v < self.h.v_size()).then(|| self.h.adj(v));
+        Box::new(g.into_iter().flatten().chain(h.into_iter().flatten()))
+    }
+}
+
+fn count_edges<G: Graph>(g: &G) -> usize {
+    (0..g.v_size())
+        .map(|v| g.adj(v).filter(|e| e.vertices().0 == v).count())
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::reversed::Reversed;
+    use super::super::scc::HasSCC;
+    use super::super::EdgeWeightedUndirectedGraph as EWUG;
+    use super::super::WeightedUndirectedEdge as WE;
+    use super::super::{BFSPath, DirectedEdge, DirectedGraph, UndirectedGraph};
+    use super::*;
+
+    fn adj<G: Graph>(g: &G, v: Vertex) -> Vec<(Vertex, Vertex)> {
+        let mut adj = g.adj(v).map(|e| e.vertices()).collect::<Vec<_>>();
+        adj.sort_unstable();
+        adj
+    }
+
+    fn digraph() -> DirectedGraph {
+        let mut g = DirectedGraph::new(5);
+        for &(v, w) in [(0, 1), (0, 2), (1, 2), (2, 0), (2, 3), (3, 3), (0, 1)].iter() {
+            g.add_edge(v, w);
+        }
+        g
+    }
+
+    #[test]
+    fn reversed_view() {
+        let g = digraph();
+        let r = ReversedView::new(&g);
+        let copy = g.reversed();
+        assert_eq!(5, r.v_size());
+        assert_eq!(7, r.e_size());
+        for v in 0..5 {
+            assert_eq!(adj(&copy, v), adj(&r, v));
+        }
+        assert_eq!(vec![(1, 0), (1, 0)], adj(&r, 1));
+        assert_eq!(vec![(3, 2), (3, 3)], adj(&r, 3));
+        assert!(adj(&r, 4).is_empty());
+
+        // vertices reaching 3
+        let p = BFSPath::new(&r, 3);
+        let reach = (0..5).filter(|&v| p.has_path_to(v)).collect::<Vec<_>>();
+        assert_eq!(vec![0, 1, 2, 3], reach);
+    }
+
+    #[test]
+    fn subgraph() {
+        let g = digraph();
+        let s = SubGraph::new(&g, vec![2, 0, 3, 2]);
+        assert_eq!(3, s.v_size());
+        // 2->0, 2->3, 0->2, 3->3
+        assert_eq!(4, s.e_size());
+        assert_eq!(2, s.original(0));
+        assert_eq!(Some(1), s.index_of(0));
+        assert_eq!(None, s.index_of(1));
+        assert_eq!(vec![(0, 1), (0, 2)], adj(&s, 0));
+        assert_eq!(vec![(1, 0)], adj(&s, 1));
+        assert_eq!(2, s.scc().count());
+
+        let mut g = EWUG::new(4);
+        g.add_edge(&WE::new(0, 1, 1.0));
+        g.add_edge(&WE::new(1, 2, 2.0));
+        g.add_edge(&WE::new(2, 3, 3.0));
+        let s = SubGraph::new(&g, 1..4);
+        assert_eq!(2, s.e_size());
+        let e = s.adj(2).next().unwrap();
+        assert_eq!((1, 2), e.vertices());
+        assert_eq!(3.0, e.weight());
+    }
+
+    #[test]
+    fn filtered() {
+        let g = digraph();
+        let f = FilteredGraph::new(&g, |e: &DirectedEdge| e.from() != e.to());
+        assert_eq!(6, f.e_size());
+        assert_eq!(vec![(3, 3)], adj(&g, 3));
+        assert!(adj(&f, 3).is_empty());
+
+        let mut g = UndirectedGraph::new(3);
+        g.add_edge(0, 1);
+        g.add_edge(1, 2);
+        let f = FilteredGraph::new(&g, |e: &_| e.vertices() != (1, 2));
+        assert_eq!(1, f.e_size());
+        assert_eq!(vec![(0, 1)], adj(&f, 1));
+    }
+
+    #[test]
+    fn union() {
+        let g = digraph();
+        let mut h = DirectedGraph::new(6);
+        h.add_edge(3, 4);
+        h.add_edge(4, 5);
+        let u = UnionGraph::new(&g, &h);
+        assert_eq!(6, u.v_size());
+        assert_eq!(9, u.e_size());
+        assert_eq!(vec![(3, 3), (3, 4)], adj(&u, 3));
+        assert_eq!(vec![(4, 5)], adj(&u, 4));
+        assert_eq!(Some(4), BFSPath::new(&u, 1).dist_to(5));
+    }
+}
@@ -0,0 +1,169 @@
+use std::ops::{Add, Sub};
+
+/// Weighted (potential) Union Find
+///
+/// Each element has an unknown potential, `union(p, q, w)` records
+/// `potential(q) - potential(p) == w`, and `diff(p, q)` tells the difference
+/// of any two connected elements.
+///
+/// diff[]: potential of a node minus potential of its parent, union by size
+/// and path compression in `union`.
+pub struct WeightedUnionFind<T> {
+    parent: Vec<usize>,
+    size: Vec<usize>,
+    diff: Vec<T>,
+    count: usize,
+}
+
+impl<T> WeightedUnionFind<T>
+where
+    T: Copy + Default + Add<Output = T> + Sub<Output = T>,
+{
+    pub fn new(cap: usize) -> Self {
+        WeightedUnionFind {
+            parent: (0..cap).collect(),
+            size: vec![1; cap],
+            diff: vec![T::default(); cap],
+            count: cap,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.parent.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.parent.is_empty()
+    }
+
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    pub fn find(&self, i: usize) -> Option<usize> {
+        self.potential(i).map(|(r, _)| r)
+    }
+
+    pub fn connected(&self, p: usize, q: usize) -> Option<bool> {
+        match (self.find(p), self.find(q)) {
+            (Some(p_root), Some(q_root)) => Some(p_root == q_root),
+            _ => None,
+        }
+    }
+
+    pub fn size(&self, i: usize) -> Option<usize> {
+        self.find(i).map(|r| self.size[r])
+    }
+
+    /// potential(q) - potential(p), None if not connected
+    pub fn diff(&self, p: usize, q: usize) -> Option<T> {
+        let (p_root, p_pot) = self.potential(p)?;
+        let (q_root, q_pot) = self.potential(q)?;
+        if p_root == q_root {
+            Some(q_pot - p_pot)
+        } else {
+            None
+        }
+    }
+
+    /// returns None if out of range, else whether two components are merged;
+    /// `w` is ignored if already connected, check it by `diff` first
+    pub fn union(&mut self, p: usize, q: usize, w: T) -> Option<bool> {
+        let (p_root, p_pot) = self.potential_mut(p)?;
+        let (q_root, q_pot) = self.potential_mut(q)?;
+        if p_root == q_root {
+            return Some(false);
+        }
+
+        // potential(q_root) - potential(p_root)
+        let d = w + p_pot - q_pot;
+        if self.size[p_root] < self.size[q_root] {
+            self.parent[p_root] = q_root;
+            self.diff[p_root] = T::default() - d;
+            self.size[q_root] += self.size[p_root];
+        } else {
+            self.parent[q_root] = p_root;
+            self.diff[q_root] = d;
+            self.size[p_root] += self.size[q_root];
+        }
+        self.count -= 1;
+
+        Some(true)
+    }
+
+    // (root, potential(i) - potential(root))
+    fn potential(&self, i: usize) -> Option<(usize, T)> {
+        if i >= self.parent.len() {
+            return None;
+        }
+        let (mut i, mut pot) = (i, T::default());
+        while i != self.parent[i] {
+            pot = pot + self.diff[i];
+            i = self.parent[i];
+        }
+        Some((i, pot))
+    }
+
+    fn potential_mut(&mut self, i: usize) -> Option<(usize, T)> {
+        let (root, pot) = self.potential(i)?;
+        // path compression, potentials of the path are known from the top
+        let (mut i, mut rest) = (i, pot);
+        while i != root {
+            let (parent, d) = (self.parent[i], self.diff[i]);
+            self.parent[i] = root;
+            self.diff[i] = rest;
+            rest = rest - d;
+            i = parent;
+        }
+        Some((root, pot))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn none() {
+        let mut uf = WeightedUnionFind::<i64>::new(2);
+        assert_eq!(None, uf.union(0, 2, 1));
+        assert_eq!(None, uf.diff(0, 1));
+        assert_eq!(Some(0), uf.diff(1, 1));
+    }
+
+    #[test]
+    fn potentials() {
+        let mut uf = WeightedUnionFind::new(6);
+        assert_eq!(Some(true), uf.union(0, 1, 3));
+        assert_eq!(Some(true), uf.union(2, 3, -2));
+        assert_eq!(Some(true), uf.union(1, 3, 10));
+        assert_eq!(Some(true), uf.union(5, 4, 1));
+        assert_eq!(2, uf.count());
+        assert_eq!(Some(4), uf.size(2));
+
+        assert_eq!(Some(3), uf.diff(0, 1));
+        assert_eq!(Some(13), uf.diff(0, 3));
+        assert_eq!(Some(15), uf.diff(0, 2));
+        assert_eq!(Some(-12), uf.diff(2, 1));
+        assert_eq!(Some(-1), uf.diff(4, 5));
+        assert_eq!(None, uf.diff(0, 4));
+
+        // consistent or not, ignored
+        assert_eq!(Some(false), uf.union(2, 0, -15));
+        assert_eq!(Some(false), uf.union(2, 0, 1));
+        assert_eq!(Some(15), uf.diff(0, 2));
+
+        // paths compressed by `union`
+        uf.union(3, 4, 0);
+        assert_eq!(Some(-13), uf.diff(3, 0));
+        assert_eq!(Some(0), uf.diff(3, 4));
+        assert_eq!(Some(12), uf.diff(0, 5));
+    }
+
+    #[test]
+    fn float() {
+        let mut uf = WeightedUnionFind::new(3);
+        uf.union(0, 1, 0.5);
+        uf.union(2, 1, 0.25);
+        assert_eq!(Some(0.25), uf.diff(0, 2));
+    }
+}
@@ -0,0 +1,283 @@
+//! K Shortest Paths
+//!
+//! Yen's algorithm finds the k shortest loopless paths between two vertices,
+//! every path is a shortest path deviated from a previous one at a spur vertex.
+use super::base::*;
+use super::dijkstra_sp::*;
+
+/// HasYenKSP
+///
+/// Directed non-negative graph can calculate k shortest simple paths by Yen's
+/// algorithm on top of Dijkstra's algorithm.
+pub trait HasYenKSP<E>
+where
+    E: Directed + NonNegative,
+{
+    fn yen_ksp(&self, s: usize, t: usize, k: usize) -> Vec<SimplePath<E>>;
+}
+impl<G, E> HasYenKSP<E> for G
+where
+    E: Directed + NonNegative,
+    G: Graph<Edge = E>,
+{
+    fn yen_ksp(&self, s: usize, t: usize, k: usize) -> Vec<SimplePath<E>> {
+        yen_ksp(self, s, t, k)
+    }
+}
+
+/// A loopless path as a sequence of edges, with its total weight
+#[derive(Debug, Clone)]
+pub struct SimplePath<E: Directed + Weighted> {
+    edges: Vec<E>,
+    weight: f64,
+}
+impl<E> SimplePath<E>
+where
+    E: Directed + Weighted,
+{
+    fn new(edges: Vec<E>) -> Self {
+        let weight = edges.iter().map(|e| e.weight()).sum();
+        SimplePath { edges, weight }
+    }
+
+    pub fn weight(&self) -> f64 {
+        self.weight
+    }
+    pub fn edges(&self) -> impl Iterator<Item = &E> {
+        self.edges.iter()
+    }
+    pub fn vertices(&self) -> impl Iterator<Item = Vertex> + '_ {
+        let s = self.edges.first().map(|e| e.from());
+        s.into_iter().chain(self.edges.iter().map(|e| e.to()))
+    }
+
+    fn same_edges(&self, other: &[E]) -> bool {
+        self.edges.len() == other.len()
+            && self.edges.iter().zip(other).all(|(a, b)| same_edge(a, b))
+    }
+}
+
+// parallel edges are told apart by weight only
+fn same_edge<E: Directed + Weighted>(a: &E, b: &E) -> bool {
+    a.vertices() == b.vertices() && a.weight() == b.weight()
+}
+
+/// A view of the graph with some vertices and edges removed, for spur paths
+#[derive(Clone)]
+struct SpurGraph<'a, G: Graph> {
+    g: &'a G,
+    removed_vertices: Vec<bool>,
+    removed_edges: Vec<G::Edge>,
+}
+impl<'a, G> Graph for SpurGraph<'a, G>
+where
+    G: Graph,
+    G::Edge: Directed + Weighted,
+{
+    type Edge = G::Edge;
+
+    fn v_size(&self) -> usize {
+        self.g.v_size()
+    }
+    fn e_size(&self) -> usize {
+        self.g.e_size()
+    }
+
+    fn adj(&self, v: usize) -> Box<dyn Iterator<Item = Self::Edge> + '_> {
+        if self.removed_vertices[v] {
+            return Box::new(std::iter::empty());
+        }
+        Box::new(self.g.adj(v).filter(move |e| {
+            !self.removed_vertices[e.to()] && !self.removed_edges.iter().any(|r| same_edge(r, e))
+        }))
+    }
+}
+
+fn yen_ksp<G, E>(g: &G, s: usize, t: usize, k: usize) -> Vec<SimplePath<E>>
+where
+    E: Directed + NonNegative,
+    G: Graph<Edge = E>,
+{
+    let mut a: Vec<SimplePath<E>> = Vec::new();
+    if k == 0 {
+        return a;
+    }
+    let sp = g.dijkstra_sp(s);
+    if s == t {
+        // the empty path is the only loopless one
+        a.push(SimplePath::new(Vec::new()));
+        return a;
+    }
+    if !sp.has_path_to(t) {
+        return a;
+    }
+    a.push(SimplePath::new(sp.path_to(t).collect()));
+
+    // candidates for the next shortest path
+    let mut b: Vec<SimplePath<E>> = Vec::new();
+    while a.len() < k {
+        let prev = a.last().unwrap().edges.clone();
+        for i in 0..prev.len() {
+            let root = &prev[..i];
+            let spur = prev[i].from();
+
+            let mut sg = SpurGraph {
+                g,
+                removed_vertices: vec![false; g.v_size()],
+                removed_edges: Vec::new(),
+            };
+            // block edges leaving the same root path as found paths
+            for p in a.iter().filter(|p| p.edges.len() > i) {
+                if p.edges[..i].iter().zip(root).all(|(a, b)| same_edge(a, b)) {
+                    sg.removed_edges.push(p.edges[i]);
+                }
+            }
+            // keep it loopless: root path vertices except the spur
+            for e in root {
+                sg.removed_vertices[e.from()] = true;
+            }
+
+            let sp = sg.dijkstra_sp(spur);
+            if !sp.has_path_to(t) {
+                continue;
+            }
+            let path = root
+                .iter()
+                .cloned()
+                .chain(sp.path_to(t))
+                .collect::<Vec<_>>();
+            if !b.iter().chain(a.iter()).any(|p| p.same_edges(&path)) {
+                b.push(SimplePath::new(path));
+            }
+        }
+
+        // pop the lightest candidate, the first one found wins a tie
+        let mut min = None;
+        for (i, p) in b.iter().enumerate() {
+            if min.is_none_or(|j: usize| p.weight() < b[j].weight()) {
+                min = Some(i);
+            }
+        }
+        match min {
+            Some(i) => a.push(b.remove(i)),
+            None => break,
+        }
+    }
+
+    a
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::EdgeNonNegativeWeightedDirectedGraph as ENNWDG;
+    use super::super::NonNegativeWeightedDirectedEdge as NNWDE;
+    use super::*;
+
+    fn graph(v: usize, edges: &[(usize, usize, f64)]) -> ENNWDG {
+        let mut g = ENNWDG::new(v);
+        for e in edges {
+            g.add_edge(NNWDE::new(e.0, e.1, e.2).unwrap());
+        }
+        g
+    }
+
+    #[test]
+    fn empty() {
+        let g = ENNWDG::new(2);
+        assert!(g.yen_ksp(0, 1, 3).is_empty());
+        assert!(g.yen_ksp(0, 0, 0).is_empty());
+
+        let paths = g.yen_ksp(0, 0, 3);
+        assert_eq!(1, paths.len());
+        assert_eq!(None, paths[0].edges().next());
+        assert_eq!(0, paths[0].weight().round() as usize);
+    }
+
+    #[test]
+    fn one_path() {
+        let g = graph(3, &[(0, 1, 1.0), (1, 2, 2.0)]);
+        let paths = g.yen_ksp(0, 2, 5);
+        assert_eq!(1, paths.len());
+        assert_eq!(vec![0, 1, 2], paths[0].vertices().collect::<Vec<_>>());
+        assert_eq!(3, paths[0].weight().round() as usize);
+    }
+
+    #[test]
+    fn parallel_edges() {
+        let g = graph(2, &[(0, 1, 2.0), (0, 1, 1.0)]);
+        let paths = g.yen_ksp(0, 1, 3);
+        assert_eq!(2, paths.len());
+        assert_eq!(1, paths[0].weight().round() as usize);
+        assert_eq!(2, paths[1].weight().round() as usize);
+    }
+
+    #[test]
+    fn wikipedia_example() {
+        // C=0, D=1, E=2, F=3, G=4, H=5
+        let g = graph(
+            6,
+            &[
+                (0, 1, 3.0),
+                (0, 2, 2.0),
+                (1, 3, 4.0),
+                (2, 1, 1.0),
+                (2, 3, 2.0),
+                (2, 4, 3.0),
+                (3, 4, 2.0),
+                (3, 5, 1.0),
+                (4, 5, 2.0),
+            ],
+        );
+        let paths = g.yen_ksp(0, 5, 3);
+        assert_eq!(3, paths.len());
+
+        assert_eq!(vec![0, 2, 3, 5], paths[0].vertices().collect::<Vec<_>>());
+        assert_eq!(5, paths[0].weight().round() as usize);
+        assert_eq!(vec![0, 2, 4, 5], paths[1].vertices().collect::<Vec<_>>());
+        assert_eq!(7, paths[1].weight().round() as usize);
+        // C-D-F-H and C-E-D-F-H tie
+        assert_eq!(8, paths[2].weight().round() as usize);
+    }
+
+    #[test]
+    fn nondecreasing_loopless() {
+        let ewd = vec![
+            (4, 5, 0.35),
+            (5, 4, 0.35),
+            (4, 7, 0.37),
+            (5, 7, 0.28),
+            (7, 5, 0.28),
+            (5, 1, 0.32),
+            (0, 4, 0.38),
+            (0, 2, 0.26),
+            (7, 3, 0.39),
+            (1, 3, 0.29),
+            (2, 7, 0.34),
+            (6, 2, 0.40),
+            (3, 6, 0.52),
+            (6, 0, 0.58),
+            (6, 4, 0.93),
+        ];
+        let g = graph(8, &ewd);
+        let paths = g.yen_ksp(0, 6, 10);
+        assert_eq!(151, (paths[0].weight() * 100.0).round() as usize);
+        assert_eq!(
+            (g.dijkstra_sp(0).dist_to(6) * 100.0).round(),
+            (paths[0].weight() * 100.0).round()
+        );
+
+        for w in paths.windows(2) {
+            assert!(w[0].weight() <= w[1].weight());
+            assert!(!w[0].same_edges(&w[1].edges));
+        }
+        for p in paths.iter() {
+            let mut vs = p.vertices().collect::<Vec<_>>();
+            assert_eq!(Some(&0), vs.first());
+            assert_eq!(Some(&6), vs.last());
+            let n = vs.len();
+            vs.sort_unstable();
+            vs.dedup();
+            assert_eq!(n, vs.len());
+        }
+    }
+}
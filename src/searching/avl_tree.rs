@@ -0,0 +1,445 @@
+use super::{OrderedSymbolTable, SymbolTable};
+
+use std::cmp::Ordering::{Equal, Greater, Less};
+
+type Tree<K, V> = Option<Box<Node<K, V>>>;
+struct Node<K, V> {
+    key: K,
+    value: V,
+    left: Tree<K, V>,
+    right: Tree<K, V>,
+    height: usize,
+    size: usize,
+}
+impl<K, V> Node<K, V> {
+    fn new(key: K, value: V) -> Self {
+        Node {
+            key,
+            value,
+            left: None,
+            right: None,
+            height: 1,
+            size: 1,
+        }
+    }
+    fn update(&mut self) {
+        self.height = 1 + height(&self.left).max(height(&self.right));
+        self.size = 1 + size(&self.left) + size(&self.right);
+    }
+    // height of left minus height of right
+    fn balance_factor(&self) -> isize {
+        height(&self.left) as isize - height(&self.right) as isize
+    }
+}
+
+/// AVL Tree
+///
+/// Heights of two sub-trees of every node differ by at most one, restored
+/// by single or double rotations on the way up after put/delete, so the
+/// height is at most 1.44lgN.
+#[derive(Default)]
+pub struct AVLTree<K, V> {
+    root: Tree<K, V>,
+}
+
+impl<K: Ord, V> AVLTree<K, V> {
+    pub fn new() -> Self {
+        AVLTree { root: None }
+    }
+
+    pub fn len(&self) -> usize {
+        size(&self.root)
+    }
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    pub fn height(&self) -> usize {
+        height(&self.root)
+    }
+
+    pub fn contains(&self, key: &K) -> bool {
+        self.get(key).is_some()
+    }
+    pub fn get(&self, key: &K) -> Option<&V> {
+        let mut x = self.root.as_ref();
+        while let Some(n) = x {
+            match key.cmp(&n.key) {
+                Equal => return Some(&n.value),
+                Less => x = n.left.as_ref(),
+                Greater => x = n.right.as_ref(),
+            }
+        }
+        None
+    }
+    pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        let mut x = self.root.as_mut();
+        while let Some(n) = x {
+            match key.cmp(&n.key) {
+                Equal => return Some(&mut n.value),
+                Less => x = n.left.as_mut(),
+                Greater => x = n.right.as_mut(),
+            }
+        }
+        None
+    }
+
+    pub fn min(&self) -> Option<&K> {
+        let mut x = self.root.as_ref()?;
+        while let Some(n) = x.left.as_ref() {
+            x = n;
+        }
+        Some(&x.key)
+    }
+    pub fn max(&self) -> Option<&K> {
+        let mut x = self.root.as_ref()?;
+        while let Some(n) = x.right.as_ref() {
+            x = n;
+        }
+        Some(&x.key)
+    }
+    pub fn floor(&self, key: &K) -> Option<&K> {
+        let (mut x, mut floor) = (self.root.as_ref(), None);
+        while let Some(n) = x {
+            match key.cmp(&n.key) {
+                Equal => return Some(&n.key),
+                Less => x = n.left.as_ref(),
+                Greater => {
+                    floor = Some(&n.key);
+                    x = n.right.as_ref();
+                }
+            }
+        }
+        floor
+    }
+    pub fn ceiling(&self, key: &K) -> Option<&K> {
+        let (mut x, mut ceiling) = (self.root.as_ref(), None);
+        while let Some(n) = x {
+            match key.cmp(&n.key) {
+                Equal => return Some(&n.key),
+                Less => {
+                    ceiling = Some(&n.key);
+                    x = n.left.as_ref();
+                }
+                Greater => x = n.right.as_ref(),
+            }
+        }
+        ceiling
+    }
+    /// number of keys less than key
+    pub fn rank(&self, key: &K) -> usize {
+        let (mut x, mut rank) = (self.root.as_ref(), 0);
+        while let Some(n) = x {
+            match key.cmp(&n.key) {
+                Equal => return rank + size(&n.left),
+                Less => x = n.left.as_ref(),
+                Greater => {
+                    rank += size(&n.left) + 1;
+                    x = n.right.as_ref();
+                }
+            }
+        }
+        rank
+    }
+    pub fn select(&self, mut i: usize) -> Option<&K> {
+        let mut x = self.root.as_ref();
+        while let Some(n) = x {
+            let ls = size(&n.left);
+            match i.cmp(&ls) {
+                Equal => return Some(&n.key),
+                Less => x = n.left.as_ref(),
+                Greater => {
+                    i -= ls + 1;
+                    x = n.right.as_ref();
+                }
+            }
+        }
+        None
+    }
+
+    pub fn put(&mut self, key: K, value: V) {
+        self.root = Some(put(self.root.take(), key, value));
+    }
+    pub fn delete(&mut self, key: &K) {
+        self.remove(key);
+    }
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        let (root, value) = remove(self.root.take(), key);
+        self.root = root;
+        value
+    }
+    pub fn pop_min(&mut self) -> Option<(K, V)> {
+        let (root, min) = pop_min(self.root.take()?);
+        self.root = root;
+        Some((min.key, min.value))
+    }
+    pub fn pop_max(&mut self) -> Option<(K, V)> {
+        let (root, max) = pop_max(self.root.take()?);
+        self.root = root;
+        Some((max.key, max.value))
+    }
+    pub fn delete_min(&mut self) {
+        self.pop_min();
+    }
+    pub fn delete_max(&mut self) {
+        self.pop_max();
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        let mut stack = Vec::new();
+        let mut x = self.root.as_deref();
+        std::iter::from_fn(move || {
+            while let Some(n) = x {
+                stack.push(n);
+                x = n.left.as_deref();
+            }
+            let n = stack.pop()?;
+            x = n.right.as_deref();
+            Some((&n.key, &n.value))
+        })
+    }
+
+    pub fn check(&self) -> bool {
+        self.check_error().is_none()
+    }
+    pub fn check_error(&self) -> Option<String> {
+        if !is_bst(&self.root, None, None) {
+            return Some("Not a BST".into());
+        }
+        if !is_consistent(&self.root) {
+            return Some("Heights or sizes are not consistent".into());
+        }
+        if !is_avl(&self.root) {
+            return Some("Not balanced".into());
+        }
+        None
+    }
+}
+
+fn size<K, V>(t: &Tree<K, V>) -> usize {
+    t.as_ref().map_or(0, |n| n.size)
+}
+fn height<K, V>(t: &Tree<K, V>) -> usize {
+    t.as_ref().map_or(0, |n| n.height)
+}
+
+fn is_bst<K: Ord, V>(t: &Tree<K, V>, min: Option<&K>, max: Option<&K>) -> bool {
+    t.as_ref().is_none_or(|n| {
+        min.is_none_or(|k| n.key > *k)
+            && max.is_none_or(|k| n.key < *k)
+            && is_bst(&n.left, min, Some(&n.key))
+            && is_bst(&n.right, Some(&n.key), max)
+    })
+}
+fn is_consistent<K, V>(t: &Tree<K, V>) -> bool {
+    t.as_ref().is_none_or(|n| {
+        n.size == 1 + size(&n.left) + size(&n.right)
+            && n.height == 1 + height(&n.left).max(height(&n.right))
+            && is_consistent(&n.left)
+            && is_consistent(&n.right)
+    })
+}
+fn is_avl<K, V>(t: &Tree<K, V>) -> bool {
+    t.as_ref()
+        .is_none_or(|n| n.balance_factor().abs() <= 1 && is_avl(&n.left) && is_avl(&n.right))
+}
+
+fn rotate_left<K, V>(mut n: Box<Node<K, V>>) -> Box<Node<K, V>> {
+    let mut x = n.right.take().unwrap();
+    n.right = x.left.take();
+    n.update();
+    x.left = Some(n);
+    x.update();
+    x
+}
+fn rotate_right<K, V>(mut n: Box<Node<K, V>>) -> Box<Node<K, V>> {
+    let mut x = n.left.take().unwrap();
+    n.left = x.right.take();
+    n.update();
+    x.right = Some(n);
+    x.update();
+    x
+}
+// restore the AVL property of n, whose sub-trees are AVL trees with heights
+// differing by at most two
+fn balance<K, V>(mut n: Box<Node<K, V>>) -> Box<Node<K, V>> {
+    n.update();
+    match n.balance_factor() {
+        2 => {
+            // left-right case: turn to left-left first
+            if n.left.as_ref().is_some_and(|l| l.balance_factor() < 0) {
+                n.left = n.left.take().map(rotate_left);
+            }
+            rotate_right(n)
+        }
+        -2 => {
+            if n.right.as_ref().is_some_and(|r| r.balance_factor() > 0) {
+                n.right = n.right.take().map(rotate_right);
+            }
+            rotate_left(n)
+        }
+        _ => n,
+    }
+}
+
+fn put<K: Ord, V>(t: Tree<K, V>, key: K, value: V) -> Box<Node<K, V>> {
+    let mut n = match t {
+        None => return Box::new(Node::new(key, value)),
+        Some(n) => n,
+    };
+    match key.cmp(&n.key) {
+        Equal => {
+            n.value = value;
+            return n;
+        }
+        Less => n.left = Some(put(n.left.take(), key, value)),
+        Greater => n.right = Some(put(n.right.take(), key, value)),
+    }
+    balance(n)
+}
+fn pop_min<K, V>(mut n: Box<Node<K, V>>) -> (Tree<K, V>, Box<Node<K, V>>) {
+    match n.left.take() {
+        None => (n.right.take(), n),
+        Some(left) => {
+            let (left, min) = pop_min(left);
+            n.left = left;
+            (Some(balance(n)), min)
+        }
+    }
+}
+fn pop_max<K, V>(mut n: Box<Node<K, V>>) -> (Tree<K, V>, Box<Node<K, V>>) {
+    match n.right.take() {
+        None => (n.left.take(), n),
+        Some(right) => {
+            let (right, max) = pop_max(right);
+            n.right = right;
+            (Some(balance(n)), max)
+        }
+    }
+}
+fn remove<K: Ord, V>(t: Tree<K, V>, key: &K) -> (Tree<K, V>, Option<V>) {
+    let mut n = match t {
+        None => return (None, None),
+        Some(n) => n,
+    };
+    let value = match key.cmp(&n.key) {
+        Less => {
+            let (left, value) = remove(n.left.take(), key);
+            n.left = left;
+            value
+        }
+        Greater => {
+            let (right, value) = remove(n.right.take(), key);
+            n.right = right;
+            value
+        }
+        Equal => {
+            let (left, right) = (n.left.take(), n.right.take());
+            let right = match right {
+                None => return (left, Some(n.value)),
+                Some(right) => right,
+            };
+            // replaced by the successor
+            let (right, mut s) = pop_min(right);
+            s.left = left;
+            s.right = right;
+            return (Some(balance(s)), Some(n.value));
+        }
+    };
+    (Some(balance(n)), value)
+}
+
+impl<K: Ord, V> SymbolTable<K, V> for AVLTree<K, V> {
+    fn len(&self) -> usize {
+        AVLTree::len(self)
+    }
+    fn get(&self, key: &K) -> Option<&V> {
+        AVLTree::get(self, key)
+    }
+    fn put(&mut self, key: K, value: V) {
+        AVLTree::put(self, key, value)
+    }
+    fn delete(&mut self, key: &K) {
+        AVLTree::delete(self, key)
+    }
+    fn keys(&self) -> Box<dyn Iterator<Item = &K> + '_> {
+        Box::new(self.iter().map(|(k, _)| k))
+    }
+}
+impl<K: Ord, V> OrderedSymbolTable<K, V> for AVLTree<K, V> {
+    fn min(&self) -> Option<&K> {
+        AVLTree::min(self)
+    }
+    fn max(&self) -> Option<&K> {
+        AVLTree::max(self)
+    }
+    fn floor(&self, key: &K) -> Option<&K> {
+        AVLTree::floor(self, key)
+    }
+    fn ceiling(&self, key: &K) -> Option<&K> {
+        AVLTree::ceiling(self, key)
+    }
+    fn rank(&self, key: &K) -> usize {
+        AVLTree::rank(self, key)
+    }
+    fn select(&self, i: usize) -> Option<&K> {
+        AVLTree::select(self, i)
+    }
+    fn delete_min(&mut self) {
+        AVLTree::delete_min(self)
+    }
+    fn delete_max(&mut self) {
+        AVLTree::delete_max(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{Rng, SeedableRng, StdRng};
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn sorted_inputs() {
+        let n = 1023;
+        let mut st = AVLTree::new();
+        for i in 0..n {
+            st.put(i, i);
+        }
+        assert_eq!(None, st.check_error());
+        // a perfect tree for 2^k-1 sorted keys
+        assert_eq!(10, st.height());
+
+        for i in (0..n).rev().step_by(2) {
+            st.delete(&i);
+            assert!(st.height() <= 14);
+        }
+        assert_eq!(None, st.check_error());
+        assert_eq!(n / 2, st.len());
+    }
+
+    #[test]
+    fn random() {
+        let mut rng = StdRng::from_seed(&[46][..]);
+        let mut st = AVLTree::new();
+        let mut map = BTreeMap::new();
+        for i in 0..2000 {
+            let k: u32 = rng.gen_range(0, 500);
+            match i % 6 {
+                0 | 1 => assert_eq!(map.remove(&k), st.remove(&k)),
+                2 if i % 4 == 0 => assert_eq!(map.pop_first(), st.pop_min()),
+                2 => assert_eq!(map.pop_last(), st.pop_max()),
+                _ => {
+                    st.put(k, i);
+                    map.insert(k, i);
+                }
+            }
+            if i % 50 == 0 {
+                assert_eq!(None, st.check_error());
+            }
+        }
+        assert_eq!(None, st.check_error());
+        assert!(map.iter().eq(st.iter()));
+        // 1.44lgN
+        assert!(st.height() as f64 <= 1.44 * (st.len() as f64 + 2.0).log2());
+    }
+}
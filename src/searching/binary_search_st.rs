@@ -1,3 +1,5 @@
+use super::{Entry, OrderedSymbolTable, SymbolTable, SymbolTableMut};
+
 #[derive(Default)]
 pub struct BinarySearchST<K, V> {
     keys: Vec<K>,
@@ -30,17 +32,24 @@ impl<K: Eq + Ord, V> BinarySearchST<K, V> {
     }
 
     pub fn get(&self, key: &K) -> Option<&V> {
-        if self.keys.is_empty() {
-            return None;
-        }
-
         let lo = self.lower_bound(key);
-        if &self.keys[lo] == key {
+        if lo < self.len() && &self.keys[lo] == key {
             Some(&self.values[lo])
         } else {
             None
         }
     }
+    pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        let lo = self.lower_bound(key);
+        if lo < self.len() && &self.keys[lo] == key {
+            Some(&mut self.values[lo])
+        } else {
+            None
+        }
+    }
+    pub fn entry(&mut self, key: K) -> Entry<'_, K, V, Self> {
+        SymbolTableMut::entry(self, key)
+    }
     pub fn put(&mut self, key: K, value: V) {
         let up = self.upper_bound(&key);
         if up > 0 && self.keys[up - 1] == key {
@@ -65,26 +74,27 @@ impl<K: Eq + Ord, V> BinarySearchST<K, V> {
     }
 
     pub fn floor(&self, key: &K) -> Option<&K> {
-        //self.keys.get(self.lower_bound(key))
-        let lo = self.lower_bound(key);
-        self.keys.get(lo).and_then(|k| {
-            if k == key {
-                Some(k)
-            } else if lo == 0 {
-                None
-            } else {
-                self.keys.get(lo - 1)
-            }
-        })
+        // the last one <= key
+        match self.upper_bound(key) {
+            0 => None,
+            up => self.keys.get(up - 1),
+        }
     }
     pub fn ceiling(&self, key: &K) -> Option<&K> {
-        self.keys.get(self.upper_bound(key) - 1)
+        // the first one >= key
+        self.keys.get(self.lower_bound(key))
     }
 
     pub fn delete(&mut self, key: &K) {
+        self.remove(key);
+    }
+    pub fn remove(&mut self, key: &K) -> Option<V> {
         let lo = self.lower_bound(key);
         if lo < self.len() && &self.keys[lo] == key {
             self.keys.remove(lo);
+            Some(self.values.remove(lo))
+        } else {
+            None
         }
     }
     pub fn delete_min(&mut self) {
@@ -150,6 +160,67 @@ impl<K: Eq + Ord, V> BinarySearchST<K, V> {
     }
 }
 
+impl<K: Eq + Ord, V> SymbolTable<K, V> for BinarySearchST<K, V> {
+    fn len(&self) -> usize {
+        self.keys.len()
+    }
+    fn get(&self, key: &K) -> Option<&V> {
+        BinarySearchST::get(self, key)
+    }
+    fn put(&mut self, key: K, value: V) {
+        BinarySearchST::put(self, key, value)
+    }
+    fn delete(&mut self, key: &K) {
+        BinarySearchST::delete(self, key)
+    }
+    fn keys(&self) -> Box<dyn Iterator<Item = &K> + '_> {
+        Box::new(self.keys.iter())
+    }
+}
+impl<K: Eq + Ord, V> OrderedSymbolTable<K, V> for BinarySearchST<K, V> {
+    fn min(&self) -> Option<&K> {
+        self.keys.first()
+    }
+    fn max(&self) -> Option<&K> {
+        self.keys.last()
+    }
+    fn floor(&self, key: &K) -> Option<&K> {
+        BinarySearchST::floor(self, key)
+    }
+    fn ceiling(&self, key: &K) -> Option<&K> {
+        BinarySearchST::ceiling(self, key)
+    }
+    fn rank(&self, key: &K) -> usize {
+        self.lower_bound(key)
+    }
+    fn select(&self, i: usize) -> Option<&K> {
+        self.keys.get(i)
+    }
+    fn delete_min(&mut self) {
+        BinarySearchST::delete_min(self)
+    }
+    fn delete_max(&mut self) {
+        BinarySearchST::delete_max(self)
+    }
+    fn keys_range<'a>(&'a self, lo: &'a K, hi: &'a K) -> Box<dyn Iterator<Item = &'a K> + 'a> {
+        Box::new(BinarySearchST::keys_range(self, lo, hi))
+    }
+}
+impl<K: Eq + Ord, V> SymbolTableMut<K, V> for BinarySearchST<K, V> {
+    fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        BinarySearchST::get_mut(self, key)
+    }
+    fn select_mut(&mut self, i: usize) -> Option<(&K, &mut V)> {
+        self.keys.get(i).zip(self.values.get_mut(i))
+    }
+    fn remove(&mut self, key: &K) -> Option<V> {
+        BinarySearchST::remove(self, key)
+    }
+    fn search(&self, key: &K) -> Result<usize, usize> {
+        self.keys.binary_search(key)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -214,6 +285,40 @@ mod tests {
         assert_eq!(Some(&"blackberry".into()), st.max());
     }
 
+    #[test]
+    fn order_methods() {
+        let mut st = BinarySearchST::<u32, u32>::new();
+        for i in [3, 7, 5].iter() {
+            st.put(*i, i * 2);
+        }
+        assert_eq!(None, st.get(&8));
+        assert_eq!(None, st.floor(&2));
+        assert_eq!(Some(&5), st.floor(&6));
+        assert_eq!(Some(&7), st.floor(&9));
+        assert_eq!(Some(&3), st.ceiling(&0));
+        assert_eq!(Some(&5), st.ceiling(&5));
+        assert_eq!(None, st.ceiling(&8));
+
+        st.delete(&5);
+        assert_eq!(Some(&14), st.get(&7));
+        assert!(st.check());
+    }
+
+    #[test]
+    fn entry() {
+        let mut st = BinarySearchST::<String, usize>::new();
+        for w in "to be or not to be".split(' ') {
+            *st.entry(w.into()).or_insert(0) += 1;
+        }
+        assert_eq!(Some(&2), st.get(&"be".into()));
+        if let Some(n) = st.get_mut(&"or".into()) {
+            *n += 10;
+        }
+        assert_eq!(Some(11), st.remove(&"or".into()));
+        assert_eq!(None, st.remove(&"or".into()));
+        assert_eq!(3, st.len());
+    }
+
     #[test]
     fn keys_range() {
         let mut st = BinarySearchST::<u32, u32>::new();
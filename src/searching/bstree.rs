@@ -1,3 +1,5 @@
+use super::{Entry, OrderedSymbolTable, SymbolTable, SymbolTableMut};
+
 use std::cmp::Ordering;
 
 type Tree<K, V> = Option<Box<Node<K, V>>>;
@@ -62,6 +64,12 @@ impl<K: Ord, V> BSTree<K, V> {
     pub fn get(&self, key: &K) -> Option<&V> {
         self.root.as_ref().and_then(|p| node_get(p, key))
     }
+    pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        self.root.as_mut().and_then(|p| node_get_mut(p, key))
+    }
+    pub fn entry(&mut self, key: K) -> Entry<'_, K, V, Self> {
+        SymbolTableMut::entry(self, key)
+    }
     pub fn put(&mut self, key: K, value: V) {
         let a = self.root.take();
         self.root = Some(node_put(a, key, value));
@@ -80,8 +88,13 @@ impl<K: Ord, V> BSTree<K, V> {
         self.root = tree_delete_max(r);
     }
     pub fn delete(&mut self, key: &K) {
+        self.remove(key);
+    }
+    pub fn remove(&mut self, key: &K) -> Option<V> {
         let r = self.root.take();
-        self.root = tree_delete(r, key);
+        let (r, value) = tree_remove(r, key);
+        self.root = r;
+        value
     }
     pub fn pop_min(&mut self) -> Option<(K, V)> {
         self.root.take().map(|b| {
@@ -91,15 +104,13 @@ impl<K: Ord, V> BSTree<K, V> {
         })
     }
 
-    pub fn keys(&self) -> Vec<&K> {
-        let mut q: Vec<&K> = Vec::new();
-        tree_keys(&self.root, &mut q);
-        q
+    pub fn keys(&self) -> impl Iterator<Item = &K> {
+        self.iter().map(|(k, _)| k)
     }
-    pub fn keys_range(&self, lo: &K, hi: &K) -> Vec<&K> {
+    pub fn keys_range(&self, lo: &K, hi: &K) -> impl Iterator<Item = &K> {
         let mut q: Vec<&K> = Vec::new();
         tree_keys_range(&self.root, &mut q, lo, hi);
-        q
+        q.into_iter()
     }
     pub fn iter(&self) -> Iter<'_, K, V> {
         Iter {
@@ -109,7 +120,10 @@ impl<K: Ord, V> BSTree<K, V> {
     }
 
     pub fn check(&self) -> bool {
-        self.keys().windows(2).all(|w| w[0] <= w[1])
+        self.keys()
+            .collect::<Vec<_>>()
+            .windows(2)
+            .all(|w| w[0] <= w[1])
     }
 }
 
@@ -146,7 +160,20 @@ fn tree_rank<K: Ord, V>(t: &Tree<K, V>, key: &K) -> usize {
     t.as_ref().map_or(0, |b| match key.cmp(&b.key) {
         Ordering::Equal => b.left.as_ref().map_or(0, |b| b.size),
         Ordering::Less => tree_rank(&b.left, key),
-        Ordering::Greater => tree_rank(&b.left, key) + 1 + tree_rank(&b.right, key),
+        Ordering::Greater => tree_size(&b.left) + 1 + tree_rank(&b.right, key),
+    })
+}
+// rank of the key, and whether it's found
+fn tree_search<K: Ord, V>(t: &Tree<K, V>, key: &K) -> Result<usize, usize> {
+    t.as_ref().map_or(Err(0), |b| match key.cmp(&b.key) {
+        Ordering::Equal => Ok(tree_size(&b.left)),
+        Ordering::Less => tree_search(&b.left, key),
+        Ordering::Greater => {
+            let ls = tree_size(&b.left) + 1;
+            tree_search(&b.right, key)
+                .map(|r| ls + r)
+                .map_err(|r| ls + r)
+        }
     })
 }
 
@@ -170,39 +197,56 @@ fn tree_delete_max<K: Ord, V>(t: Tree<K, V>) -> Tree<K, V> {
         }
     })
 }
-fn tree_delete<K: Ord, V>(t: Tree<K, V>, key: &K) -> Tree<K, V> {
-    t.and_then(|mut b| {
-        match key.cmp(&b.key) {
-            Ordering::Less => b.left = tree_delete(b.left, key),
-            Ordering::Greater => b.right = tree_delete(b.right, key),
-            _ => {
-                if b.right.is_none() {
-                    return b.left;
-                }
-                if b.left.is_none() {
-                    return b.right;
-                }
-
-                // use min of right sub-tree as the new node
-                let t = b.left.take();
-                let (child, x) = node_pop_min(b.right.unwrap());
-                b = x;
-                b.right = child;
-                b.left = t;
-            }
+fn tree_select_mut<K: Ord, V>(t: &mut Tree<K, V>, i: usize) -> Option<(&K, &mut V)> {
+    t.as_mut().and_then(|b| {
+        let ls = tree_size(&b.left);
+        match i.cmp(&ls) {
+            Ordering::Equal => Some((&b.key, &mut b.value)),
+            Ordering::Less => tree_select_mut(&mut b.left, i),
+            Ordering::Greater => tree_select_mut(&mut b.right, i - ls - 1),
         }
-        b.size = 1 + tree_size(&b.left) + tree_size(&b.right);
-        Some(b)
     })
 }
 
-fn tree_keys<'a, K: Ord, V>(t: &'a Tree<K, V>, q: &mut Vec<&'a K>) {
-    if let Some(ref b) = t {
-        tree_keys(&b.left, q);
-        q.push(&b.key);
-        tree_keys(&b.right, q);
+// returns the new tree and the removed value
+fn tree_remove<K: Ord, V>(t: Tree<K, V>, key: &K) -> (Tree<K, V>, Option<V>) {
+    match t {
+        None => (None, None),
+        Some(mut b) => {
+            let removed;
+            match key.cmp(&b.key) {
+                Ordering::Less => {
+                    let (child, v) = tree_remove(b.left, key);
+                    b.left = child;
+                    removed = v;
+                }
+                Ordering::Greater => {
+                    let (child, v) = tree_remove(b.right, key);
+                    b.right = child;
+                    removed = v;
+                }
+                _ => {
+                    if b.right.is_none() {
+                        return (b.left, Some(b.value));
+                    }
+                    if b.left.is_none() {
+                        return (b.right, Some(b.value));
+                    }
+
+                    // use min of right sub-tree as the new node
+                    let t = b.left.take();
+                    let (child, x) = node_pop_min(b.right.take().unwrap());
+                    removed = Some(std::mem::replace(&mut b, x).value);
+                    b.right = child;
+                    b.left = t;
+                }
+            }
+            b.size = 1 + tree_size(&b.left) + tree_size(&b.right);
+            (Some(b), removed)
+        }
     }
 }
+
 fn tree_keys_range<'a, K: Ord, V>(t: &'a Tree<K, V>, q: &mut Vec<&'a K>, lo: &K, hi: &K) {
     if let Some(ref b) = t {
         if *lo < b.key {
@@ -232,6 +276,13 @@ fn node_get<'a, K: Ord, V>(node: &'a Node<K, V>, key: &K) -> Option<&'a V> {
         Ordering::Greater => node.right.as_ref().and_then(|n| node_get(n, key)),
     }
 }
+fn node_get_mut<'a, K: Ord, V>(node: &'a mut Node<K, V>, key: &K) -> Option<&'a mut V> {
+    match key.cmp(&node.key) {
+        Ordering::Equal => Some(&mut node.value),
+        Ordering::Less => node.left.as_mut().and_then(|n| node_get_mut(n, key)),
+        Ordering::Greater => node.right.as_mut().and_then(|n| node_get_mut(n, key)),
+    }
+}
 fn node_put<K: Ord, V>(t: Tree<K, V>, key: K, value: V) -> Box<Node<K, V>> {
     match t {
         None => Box::new(Node::new(key, value)),
@@ -258,6 +309,68 @@ fn node_pop_min<K: Ord, V>(mut b: Box<Node<K, V>>) -> (Tree<K, V>, Box<Node<K, V
     }
 }
 
+impl<K: Ord, V> SymbolTable<K, V> for BSTree<K, V> {
+    fn len(&self) -> usize {
+        BSTree::len(self)
+    }
+    fn get(&self, key: &K) -> Option<&V> {
+        BSTree::get(self, key)
+    }
+    fn put(&mut self, key: K, value: V) {
+        BSTree::put(self, key, value)
+    }
+    fn delete(&mut self, key: &K) {
+        BSTree::delete(self, key)
+    }
+    fn keys(&self) -> Box<dyn Iterator<Item = &K> + '_> {
+        Box::new(BSTree::keys(self))
+    }
+}
+impl<K: Ord, V> OrderedSymbolTable<K, V> for BSTree<K, V> {
+    fn min(&self) -> Option<&K> {
+        BSTree::min(self)
+    }
+    fn max(&self) -> Option<&K> {
+        BSTree::max(self)
+    }
+    fn floor(&self, key: &K) -> Option<&K> {
+        BSTree::floor(self, key)
+    }
+    fn ceiling(&self, key: &K) -> Option<&K> {
+        BSTree::ceiling(self, key)
+    }
+    fn rank(&self, key: &K) -> usize {
+        BSTree::rank(self, key)
+    }
+    fn select(&self, i: usize) -> Option<&K> {
+        BSTree::select(self, i)
+    }
+    fn delete_min(&mut self) {
+        BSTree::delete_min(self)
+    }
+    fn delete_max(&mut self) {
+        BSTree::delete_max(self)
+    }
+    fn keys_range<'a>(&'a self, lo: &'a K, hi: &'a K) -> Box<dyn Iterator<Item = &'a K> + 'a> {
+        Box::new(BSTree::keys_range(self, lo, hi))
+    }
+}
+
+impl<K: Ord, V> SymbolTableMut<K, V> for BSTree<K, V> {
+    fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        BSTree::get_mut(self, key)
+    }
+    fn select_mut(&mut self, i: usize) -> Option<(&K, &mut V)> {
+        tree_select_mut(&mut self.root, i)
+    }
+    fn remove(&mut self, key: &K) -> Option<V> {
+        BSTree::remove(self, key)
+    }
+    fn search(&self, key: &K) -> Result<usize, usize> {
+        tree_search(&self.root, key)
+    }
+}
+
 impl<K: Ord, V> IntoIterator for BSTree<K, V> {
     type Item = (K, V);
     type IntoIter = IntoIter<K, V>;
@@ -431,7 +544,7 @@ mod tests {
             st.put(c.to_string(), 1);
         }
 
-        let keys = st.keys().iter().fold(String::new(), |acc, v| acc + v);
+        let keys = st.keys().fold(String::new(), |acc, v| acc + v);
         assert_eq!("ACEHMRSX", keys);
 
         assert!(st.check());
@@ -445,13 +558,13 @@ mod tests {
         }
         assert!(st.check());
 
-        let v = st.keys_range(&1, &3);
+        let v = st.keys_range(&1, &3).collect::<Vec<_>>();
         assert_eq!([&1, &2, &3], v.as_slice());
-        let v = st.keys_range(&0, &3);
+        let v = st.keys_range(&0, &3).collect::<Vec<_>>();
         assert_eq!([&1, &2, &3], &v[..]);
-        let v = st.keys_range(&8, &9);
+        let v = st.keys_range(&8, &9).collect::<Vec<_>>();
         assert_eq!([&8, &9], &v[..]);
-        let v = st.keys_range(&8, &20);
+        let v = st.keys_range(&8, &20).collect::<Vec<_>>();
         assert_eq!([&8, &9], &v[..]);
     }
 
@@ -0,0 +1,600 @@
+use super::{OrderedSymbolTable, SymbolTable};
+
+use std::ops::{Bound, RangeBounds};
+
+// keys[i] is between keys of children[i] and children[i + 1], `size` is the
+// number of keys of the sub-tree
+struct Node<K, V> {
+    keys: Vec<K>,
+    values: Vec<V>,
+    children: Vec<Node<K, V>>,
+    size: usize,
+}
+impl<K, V> Node<K, V> {
+    fn new() -> Self {
+        Node {
+            keys: Vec::new(),
+            values: Vec::new(),
+            children: Vec::new(),
+            size: 0,
+        }
+    }
+    fn is_leaf(&self) -> bool {
+        self.children.is_empty()
+    }
+    fn update(&mut self) {
+        self.size = self.keys.len() + self.children.iter().map(|c| c.size).sum::<usize>();
+    }
+    fn child_size(&self, i: usize) -> usize {
+        self.children.get(i).map_or(0, |c| c.size)
+    }
+}
+
+/// B-Tree
+///
+/// A balanced search tree of order M: every node has at most M-1 keys and M
+/// children, every node other than the root has at least (M-1)/2 keys, and
+/// all leaves are at the same depth. A node splits into two when it's full
+/// on insertion, and borrows from or merges with a sibling when it's too
+/// few on deletion, so the height is about log_{M/2}(N).
+pub struct BTree<K, V> {
+    root: Node<K, V>,
+    order: usize,
+}
+
+impl<K: Ord, V> Default for BTree<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Ord, V> BTree<K, V> {
+    pub const DEFAULT_ORDER: usize = 12;
+
+    pub fn new() -> Self {
+        Self::with_order(Self::DEFAULT_ORDER)
+    }
+    /// max number of children of a node, at least 3
+    pub fn with_order(order: usize) -> Self {
+        assert!(order >= 3, "order of B-tree must be at least 3");
+        BTree {
+            root: Node::new(),
+            order,
+        }
+    }
+
+    pub fn order(&self) -> usize {
+        self.order
+    }
+    pub fn len(&self) -> usize {
+        self.root.size
+    }
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    pub fn height(&self) -> usize {
+        let (mut x, mut h) = (&self.root, 0);
+        while let Some(c) = x.children.first() {
+            x = c;
+            h += 1;
+        }
+        h
+    }
+    fn min_keys(&self) -> usize {
+        (self.order - 1) / 2
+    }
+
+    pub fn contains(&self, key: &K) -> bool {
+        self.get(key).is_some()
+    }
+    pub fn get(&self, key: &K) -> Option<&V> {
+        let mut x = &self.root;
+        loop {
+            match x.keys.binary_search(key) {
+                Ok(i) => return Some(&x.values[i]),
+                Err(i) => x = x.children.get(i)?,
+            }
+        }
+    }
+    pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        let mut x = &mut self.root;
+        loop {
+            match x.keys.binary_search(key) {
+                Ok(i) => return Some(&mut x.values[i]),
+                Err(i) => x = x.children.get_mut(i)?,
+            }
+        }
+    }
+
+    pub fn min(&self) -> Option<&K> {
+        let mut x = &self.root;
+        while let Some(c) = x.children.first() {
+            x = c;
+        }
+        x.keys.first()
+    }
+    pub fn max(&self) -> Option<&K> {
+        let mut x = &self.root;
+        while let Some(c) = x.children.last() {
+            x = c;
+        }
+        x.keys.last()
+    }
+    pub fn floor(&self, key: &K) -> Option<&K> {
+        let (mut x, mut floor) = (&self.root, None);
+        loop {
+            match x.keys.binary_search(key) {
+                Ok(i) => return Some(&x.keys[i]),
+                Err(i) => {
+                    if i > 0 {
+                        floor = Some(&x.keys[i - 1]);
+                    }
+                    match x.children.get(i) {
+                        Some(c) => x = c,
+                        None => return floor,
+                    }
+                }
+            }
+        }
+    }
+    pub fn ceiling(&self, key: &K) -> Option<&K> {
+        let (mut x, mut ceiling) = (&self.root, None);
+        loop {
+            match x.keys.binary_search(key) {
+                Ok(i) => return Some(&x.keys[i]),
+                Err(i) => {
+                    if i < x.keys.len() {
+                        ceiling = Some(&x.keys[i]);
+                    }
+                    match x.children.get(i) {
+                        Some(c) => x = c,
+                        None => return ceiling,
+                    }
+                }
+            }
+        }
+    }
+    /// number of keys less than key
+    pub fn rank(&self, key: &K) -> usize {
+        let (mut x, mut rank) = (&self.root, 0);
+        loop {
+            let (i, found) = match x.keys.binary_search(key) {
+                Ok(i) => (i, true),
+                Err(i) => (i, false),
+            };
+            rank += i + (0..i).map(|j| x.child_size(j)).sum::<usize>();
+            if found {
+                return rank + x.child_size(i);
+            }
+            match x.children.get(i) {
+                Some(c) => x = c,
+                None => return rank,
+            }
+        }
+    }
+    pub fn select(&self, mut i: usize) -> Option<&K> {
+        if i >= self.len() {
+            return None;
+        }
+        let mut x = &self.root;
+        'down: loop {
+            for j in 0..x.keys.len() {
+                let cs = x.child_size(j);
+                if i < cs {
+                    x = &x.children[j];
+                    continue 'down;
+                }
+                if i == cs {
+                    return Some(&x.keys[j]);
+                }
+                i -= cs + 1;
+            }
+            x = x.children.last()?;
+        }
+    }
+
+    pub fn put(&mut self, key: K, value: V) {
+        if let Some((k, v, right)) = insert(&mut self.root, key, value, self.order) {
+            // the root splits, the tree grows one level
+            let mut root = Node::new();
+            root.keys.push(k);
+            root.values.push(v);
+            root.children
+                .push(std::mem::replace(&mut self.root, Node::new()));
+            root.children.push(right);
+            root.update();
+            self.root = root;
+        }
+    }
+    pub fn delete(&mut self, key: &K) {
+        self.remove(key);
+    }
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        let min = self.min_keys();
+        let value = remove(&mut self.root, key, min);
+        self.shrink();
+        value
+    }
+    pub fn pop_min(&mut self) -> Option<(K, V)> {
+        if self.is_empty() {
+            return None;
+        }
+        let min = self.min_keys();
+        let kv = pop_min(&mut self.root, min);
+        self.shrink();
+        Some(kv)
+    }
+    pub fn pop_max(&mut self) -> Option<(K, V)> {
+        if self.is_empty() {
+            return None;
+        }
+        let min = self.min_keys();
+        let kv = pop_max(&mut self.root, min);
+        self.shrink();
+        Some(kv)
+    }
+    pub fn delete_min(&mut self) {
+        self.pop_min();
+    }
+    pub fn delete_max(&mut self) {
+        self.pop_max();
+    }
+    // the root runs out of keys after a merge, the tree shrinks one level
+    fn shrink(&mut self) {
+        if self.root.keys.is_empty() {
+            if let Some(child) = self.root.children.pop() {
+                self.root = child;
+            }
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        self.range(..)
+    }
+    /// lazy iterator of keys within the range in order
+    pub fn range<R: RangeBounds<K>>(&self, range: R) -> impl Iterator<Item = (&K, &V)> {
+        let (lo, hi) = (range.start_bound(), range.end_bound());
+        let skip = match lo {
+            Bound::Included(k) => self.rank(k),
+            Bound::Excluded(k) => self.rank(k) + self.contains(k) as usize,
+            Bound::Unbounded => 0,
+        };
+        let end = match hi {
+            Bound::Included(k) => self.rank(k) + self.contains(k) as usize,
+            Bound::Excluded(k) => self.rank(k),
+            Bound::Unbounded => self.len(),
+        };
+        let mut remaining = end.saturating_sub(skip);
+
+        // (node, index of the next key) on the path to the first key
+        let mut stack = Vec::new();
+        let mut x = Some(&self.root);
+        while let Some(n) = x {
+            let i = n.keys.partition_point(|k| match lo {
+                Bound::Included(lo) => k < lo,
+                Bound::Excluded(lo) => k <= lo,
+                Bound::Unbounded => false,
+            });
+            stack.push((n, i));
+            x = n.children.get(i);
+        }
+
+        std::iter::from_fn(move || {
+            if remaining == 0 {
+                return None;
+            }
+            loop {
+                let (n, i) = stack.pop()?;
+                if i < n.keys.len() {
+                    stack.push((n, i + 1));
+                    let mut x = n.children.get(i + 1);
+                    while let Some(c) = x {
+                        stack.push((c, 0));
+                        x = c.children.first();
+                    }
+                    remaining -= 1;
+                    return Some((&n.keys[i], &n.values[i]));
+                }
+            }
+        })
+    }
+
+    pub fn check(&self) -> bool {
+        self.check_error().is_none()
+    }
+    pub fn check_error(&self) -> Option<String> {
+        let height = self.height();
+        // (node, depth, min, max)
+        let mut stack = vec![(&self.root, 0, None::<&K>, None::<&K>)];
+        while let Some((n, depth, min, max)) = stack.pop() {
+            if n.keys.windows(2).any(|w| w[0] >= w[1])
+                || n.keys.first().is_some_and(|k| min.is_some_and(|m| k <= m))
+                || n.keys.last().is_some_and(|k| max.is_some_and(|m| k >= m))
+            {
+                return Some("Keys are not in order".into());
+            }
+            if n.keys.len() >= self.order
+                || (depth > 0 && n.keys.len() < self.min_keys())
+                || (depth == 0 && n.keys.is_empty() && !n.is_leaf())
+            {
+                return Some(format!("Node has {} keys", n.keys.len()));
+            }
+            if n.values.len() != n.keys.len()
+                || (!n.is_leaf() && n.children.len() != n.keys.len() + 1)
+            {
+                return Some("Numbers of keys, values and children don't match".into());
+            }
+            if n.is_leaf() && depth != height {
+                return Some("Leaves are not at the same depth".into());
+            }
+            let size = n.keys.len() + n.children.iter().map(|c| c.size).sum::<usize>();
+            if n.size != size {
+                return Some("Sizes are not consistent".into());
+            }
+            for (i, c) in n.children.iter().enumerate() {
+                let lo = if i > 0 { n.keys.get(i - 1) } else { min };
+                let hi = n.keys.get(i).or(max);
+                stack.push((c, depth + 1, lo, hi));
+            }
+        }
+        None
+    }
+}
+
+// the median and the right half of a split node
+type Split<K, V> = (K, V, Node<K, V>);
+
+// split a full node
+fn split<K, V>(node: &mut Node<K, V>) -> Split<K, V> {
+    let mid = node.keys.len() / 2;
+    let mut right = Node::new();
+    right.keys = node.keys.split_off(mid + 1);
+    right.values = node.values.split_off(mid + 1);
+    if !node.is_leaf() {
+        right.children = node.children.split_off(mid + 1);
+    }
+    let (k, v) = (node.keys.pop().unwrap(), node.values.pop().unwrap());
+    node.update();
+    right.update();
+    (k, v, right)
+}
+fn insert<K: Ord, V>(node: &mut Node<K, V>, key: K, value: V, order: usize) -> Option<Split<K, V>> {
+    match node.keys.binary_search(&key) {
+        Ok(i) => {
+            node.values[i] = value;
+            return None;
+        }
+        Err(i) => {
+            if node.is_leaf() {
+                node.keys.insert(i, key);
+                node.values.insert(i, value);
+            } else if let Some((k, v, right)) = insert(&mut node.children[i], key, value, order) {
+                node.keys.insert(i, k);
+                node.values.insert(i, v);
+                node.children.insert(i + 1, right);
+            }
+        }
+    }
+    node.update();
+    if node.keys.len() < order {
+        None
+    } else {
+        Some(split(node))
+    }
+}
+
+fn remove<K: Ord, V>(node: &mut Node<K, V>, key: &K, min: usize) -> Option<V> {
+    let value = match node.keys.binary_search(key) {
+        Ok(i) if node.is_leaf() => {
+            node.keys.remove(i);
+            Some(node.values.remove(i))
+        }
+        Ok(i) => {
+            // replaced by the predecessor
+            let (k, v) = pop_max(&mut node.children[i], min);
+            node.keys[i] = k;
+            let value = std::mem::replace(&mut node.values[i], v);
+            fix(node, i, min);
+            Some(value)
+        }
+        Err(i) => {
+            let value = remove(node.children.get_mut(i)?, key, min)?;
+            fix(node, i, min);
+            Some(value)
+        }
+    };
+    node.update();
+    value
+}
+// node must not be empty
+fn pop_min<K, V>(node: &mut Node<K, V>, min: usize) -> (K, V) {
+    let kv = if node.is_leaf() {
+        (node.keys.remove(0), node.values.remove(0))
+    } else {
+        let kv = pop_min(&mut node.children[0], min);
+        fix(node, 0, min);
+        kv
+    };
+    node.update();
+    kv
+}
+fn pop_max<K, V>(node: &mut Node<K, V>, min: usize) -> (K, V) {
+    let kv = if node.is_leaf() {
+        (node.keys.pop().unwrap(), node.values.pop().unwrap())
+    } else {
+        let i = node.children.len() - 1;
+        let kv = pop_max(&mut node.children[i], min);
+        fix(node, i, min);
+        kv
+    };
+    node.update();
+    kv
+}
+// fix the i-th child if it has too few keys, by borrowing a key from a
+// sibling through the parent, or merging with a sibling
+fn fix<K, V>(node: &mut Node<K, V>, i: usize, min: usize) {
+    if node.children[i].keys.len() >= min {
+        return;
+    }
+
+    if i > 0 && node.children[i - 1].keys.len() > min {
+        let (left, right) = node.children.split_at_mut(i);
+        let (left, child) = (&mut left[i - 1], &mut right[0]);
+        let k = std::mem::replace(&mut node.keys[i - 1], left.keys.pop().unwrap());
+        let v = std::mem::replace(&mut node.values[i - 1], left.values.pop().unwrap());
+        child.keys.insert(0, k);
+        child.values.insert(0, v);
+        if let Some(c) = left.children.pop() {
+            child.children.insert(0, c);
+        }
+        left.update();
+        child.update();
+    } else if i + 1 < node.children.len() && node.children[i + 1].keys.len() > min {
+        let (left, right) = node.children.split_at_mut(i + 1);
+        let (child, right) = (&mut left[i], &mut right[0]);
+        let k = std::mem::replace(&mut node.keys[i], right.keys.remove(0));
+        let v = std::mem::replace(&mut node.values[i], right.values.remove(0));
+        child.keys.push(k);
+        child.values.push(v);
+        if !right.is_leaf() {
+            child.children.push(right.children.remove(0));
+        }
+        right.update();
+        child.update();
+    } else {
+        // merge j, the separator and j + 1
+        let j = if i > 0 { i - 1 } else { i };
+        let right = node.children.remove(j + 1);
+        let left = &mut node.children[j];
+        left.keys.push(node.keys.remove(j));
+        left.values.push(node.values.remove(j));
+        left.keys.extend(right.keys);
+        left.values.extend(right.values);
+        left.children.extend(right.children);
+        left.update();
+    }
+}
+
+impl<K: Ord, V> SymbolTable<K, V> for BTree<K, V> {
+    fn len(&self) -> usize {
+        BTree::len(self)
+    }
+    fn get(&self, key: &K) -> Option<&V> {
+        BTree::get(self, key)
+    }
+    fn put(&mut self, key: K, value: V) {
+        BTree::put(self, key, value)
+    }
+    fn delete(&mut self, key: &K) {
+        BTree::delete(self, key)
+    }
+    fn keys(&self) -> Box<dyn Iterator<Item = &K> + '_> {
+        Box::new(self.iter().map(|(k, _)| k))
+    }
+}
+impl<K: Ord, V> OrderedSymbolTable<K, V> for BTree<K, V> {
+    fn min(&self) -> Option<&K> {
+        BTree::min(self)
+    }
+    fn max(&self) -> Option<&K> {
+        BTree::max(self)
+    }
+    fn floor(&self, key: &K) -> Option<&K> {
+        BTree::floor(self, key)
+    }
+    fn ceiling(&self, key: &K) -> Option<&K> {
+        BTree::ceiling(self, key)
+    }
+    fn rank(&self, key: &K) -> usize {
+        BTree::rank(self, key)
+    }
+    fn select(&self, i: usize) -> Option<&K> {
+        BTree::select(self, i)
+    }
+    fn delete_min(&mut self) {
+        BTree::delete_min(self)
+    }
+    fn delete_max(&mut self) {
+        BTree::delete_max(self)
+    }
+    fn keys_range<'a>(&'a self, lo: &'a K, hi: &'a K) -> Box<dyn Iterator<Item = &'a K> + 'a> {
+        Box::new(self.range(lo..=hi).map(|(k, _)| k))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{Rng, SeedableRng, StdRng};
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn range() {
+        let mut st = BTree::with_order(4);
+        for i in 0..100 {
+            st.put(i * 2, i);
+        }
+        let keys = |st: &BTree<i32, i32>, r: (Bound<i32>, Bound<i32>)| {
+            st.range(r).map(|(k, _)| *k).collect::<Vec<_>>()
+        };
+        use Bound::*;
+        assert_eq!(vec![4, 6, 8], keys(&st, (Included(3), Excluded(10))));
+        assert_eq!(vec![4, 6, 8, 10], keys(&st, (Included(4), Included(10))));
+        assert_eq!(vec![6, 8], keys(&st, (Excluded(4), Excluded(10))));
+        assert_eq!(vec![0, 2], keys(&st, (Unbounded, Excluded(4))));
+        assert_eq!(vec![196, 198], keys(&st, (Included(195), Unbounded)));
+        assert_eq!(100, st.range(..).count());
+        assert_eq!(0, st.range(10..10).count());
+        assert_eq!(0, st.range(300..).count());
+        assert_eq!(vec![&4, &6], st.keys_range(&3, &7).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn height() {
+        // full nodes of 2 keys after sorted insertions of 3^k-1 keys
+        let mut st = BTree::with_order(3);
+        for i in 0..80 {
+            st.put(i, ());
+        }
+        assert!(st.height() <= 6);
+        assert_eq!(None, st.check_error());
+
+        let mut st = BTree::new();
+        for i in 0..10000 {
+            st.put(i, ());
+        }
+        assert!(st.height() <= 5);
+        for i in 0..10000 {
+            st.delete(&i);
+        }
+        assert_eq!(0, st.height());
+        assert!(st.is_empty());
+        assert_eq!(None, st.check_error());
+    }
+
+    #[test]
+    fn random() {
+        for order in 3..9 {
+            let mut rng = StdRng::from_seed(&[order][..]);
+            let mut st = BTree::with_order(order);
+            let mut map = BTreeMap::new();
+            for i in 0..3000 {
+                let k: u32 = rng.gen_range(0, 500);
+                match i % 5 {
+                    0 | 1 => assert_eq!(map.remove(&k), st.remove(&k)),
+                    2 if i % 7 == 0 => assert_eq!(map.pop_first(), st.pop_min()),
+                    2 => assert_eq!(map.pop_last(), st.pop_max()),
+                    _ => {
+                        map.insert(k, i);
+                        st.put(k, i);
+                    }
+                }
+                if i % 100 == 0 {
+                    assert_eq!(None, st.check_error(), "order {}", order);
+                }
+            }
+            assert_eq!(None, st.check_error());
+            assert!(map.iter().eq(st.iter()));
+        }
+    }
+}
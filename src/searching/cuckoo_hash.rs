@@ -0,0 +1,267 @@
+use super::hash_table::MaxLoad;
+use super::{DefaultHashBuilder, HashTable, SymbolTable};
+
+use std::hash::{BuildHasher, Hash};
+
+/// Cuckoo hashing symbol table
+///
+/// Two tables with a hash function each, every key is in one of its two
+/// slots, so a search probes at most two slots. A key being put kicks out
+/// the key in its slot of the first table, which goes to its slot in the
+/// other table and may kick out another one, and so on. The tables double
+/// if it doesn't settle within a number of kicks. Puts kick a lot and fail
+/// more and more often above a load factor of 0.5.
+///
+/// A key that doesn't settle even in tables much larger than needed, which
+/// only happens with a poor hasher, goes to a stash searched sequentially.
+pub struct CuckooHashST<K, V, S = DefaultHashBuilder> {
+    // two tables of m slots: [0, m) and [m, 2m), then the stash from 2m
+    slots: Vec<Option<(K, V)>>,
+    stash: Vec<(K, V)>,
+    m: usize,
+    n: usize,
+    hash_builder: S,
+    max_load: MaxLoad,
+}
+
+impl<K: Hash + Eq, V> CuckooHashST<K, V> {
+    pub fn new(size: usize) -> Self {
+        Self::with_hasher(size, DefaultHashBuilder::default())
+    }
+}
+
+impl<K: Hash + Eq, V, S: BuildHasher> CuckooHashST<K, V, S> {
+    pub const DEFAULT_MAX_LOAD: f64 = 0.45;
+    const MAX_KICKS: usize = 32;
+
+    /// `size` slots in each table
+    pub fn with_hasher(size: usize, hash_builder: S) -> Self {
+        let m = size.max(1);
+        CuckooHashST {
+            slots: (0..2 * m).map(|_| None).collect(),
+            stash: Vec::new(),
+            m,
+            n: 0,
+            hash_builder,
+            max_load: MaxLoad::new(Self::DEFAULT_MAX_LOAD, 1.0),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.n
+    }
+    pub fn is_empty(&self) -> bool {
+        self.n == 0
+    }
+
+    pub fn contains(&self, key: &K) -> bool {
+        self.get(key).is_some()
+    }
+    pub fn get(&self, key: &K) -> Option<&V> {
+        let i = self.find(key)?;
+        self.at(i).map(|(_, v)| v)
+    }
+    pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        let i = self.find(key)?;
+        self.at_mut(i).map(|(_, v)| v)
+    }
+    pub fn put(&mut self, key: K, value: V) {
+        if let Some(v) = self.get_mut(&key) {
+            *v = value;
+            return;
+        }
+        if self.max_load.exceeded(self.n + 1, self.slots.len()) {
+            self.resize(self.m * 2);
+        }
+        self.insert(key, value);
+    }
+    pub fn delete(&mut self, key: &K) {
+        self.remove(key);
+    }
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        let i = self.find(key)?;
+        Some(self.remove_at(i).1)
+    }
+    pub fn pop(&mut self) -> Option<(K, V)> {
+        let i = match self.slots.iter().position(|s| s.is_some()) {
+            Some(i) => i,
+            None if self.stash.is_empty() => return None,
+            None => self.slots.len(),
+        };
+        Some(self.remove_at(i))
+    }
+    pub fn keys(&self) -> impl Iterator<Item = &K> {
+        let stash = self.stash.iter();
+        self.slots.iter().flatten().chain(stash).map(|(k, _)| k)
+    }
+}
+
+// private methods
+impl<K: Hash + Eq, V, S: BuildHasher> CuckooHashST<K, V, S> {
+    // slot of the key in the t-th table
+    fn hash(&self, t: usize, key: &K) -> usize {
+        t * self.m + self.hash_builder.hash_one((t as u8, key)) as usize % self.m
+    }
+
+    fn at(&self, i: usize) -> Option<&(K, V)> {
+        match self.slots.get(i) {
+            Some(slot) => slot.as_ref(),
+            None => self.stash.get(i - self.slots.len()),
+        }
+    }
+    fn at_mut(&mut self, i: usize) -> Option<&mut (K, V)> {
+        let m2 = self.slots.len();
+        match self.slots.get_mut(i) {
+            Some(slot) => slot.as_mut(),
+            None => self.stash.get_mut(i - m2),
+        }
+    }
+
+    fn find(&self, key: &K) -> Option<usize> {
+        (0..2)
+            .map(|t| self.hash(t, key))
+            .find(|&i| self.slots[i].as_ref().is_some_and(|(k, _)| k == key))
+            .or_else(|| {
+                let j = self.stash.iter().position(|(k, _)| k == key)?;
+                Some(self.slots.len() + j)
+            })
+    }
+
+    // the key must be absent
+    fn insert(&mut self, key: K, value: V) {
+        let mut kv = (key, value);
+        for kick in 0..Self::MAX_KICKS {
+            let i = self.hash(kick % 2, &kv.0);
+            match self.slots[i].replace(kv) {
+                None => {
+                    self.n += 1;
+                    return;
+                }
+                Some(kicked) => kv = kicked,
+            }
+        }
+
+        // most likely a cycle, rehash into larger tables, unless they are
+        // much larger than needed already
+        if self.m <= 64 * (self.n + 1) {
+            self.resize(self.m * 2);
+            self.insert(kv.0, kv.1);
+        } else {
+            self.stash.push(kv);
+            self.n += 1;
+        }
+    }
+
+    fn remove_at(&mut self, i: usize) -> (K, V) {
+        let kv = match self.slots.get_mut(i) {
+            Some(slot) => slot.take().unwrap(),
+            None => self.stash.swap_remove(i - self.slots.len()),
+        };
+        self.n -= 1;
+
+        if self.max_load.shrinkable(self.n, self.slots.len()) {
+            self.resize(self.m / 2);
+        }
+
+        kv
+    }
+
+    fn resize(&mut self, size: usize) {
+        let slots = (0..2 * size).map(|_| None).collect();
+        let slots = std::mem::replace(&mut self.slots, slots);
+        let stash = std::mem::take(&mut self.stash);
+        self.m = size;
+        self.n = 0;
+        for (key, value) in slots.into_iter().flatten().chain(stash) {
+            self.insert(key, value);
+        }
+    }
+}
+
+impl<K: Hash + Eq, V, S: BuildHasher> SymbolTable<K, V> for CuckooHashST<K, V, S> {
+    fn len(&self) -> usize {
+        self.n
+    }
+    fn get(&self, key: &K) -> Option<&V> {
+        CuckooHashST::get(self, key)
+    }
+    fn put(&mut self, key: K, value: V) {
+        CuckooHashST::put(self, key, value)
+    }
+    fn delete(&mut self, key: &K) {
+        CuckooHashST::delete(self, key)
+    }
+    fn keys(&self) -> Box<dyn Iterator<Item = &K> + '_> {
+        Box::new(CuckooHashST::keys(self))
+    }
+}
+
+impl<K: Hash + Eq, V, S: BuildHasher> HashTable<K, V> for CuckooHashST<K, V, S> {
+    fn table_size(&self) -> usize {
+        self.slots.len()
+    }
+    fn max_load_factor(&self) -> f64 {
+        self.max_load.value()
+    }
+    /// must be in (0, 1)
+    fn set_max_load_factor(&mut self, max_load: f64) {
+        self.max_load.set(max_load);
+        while self.max_load.exceeded(self.n, self.slots.len()) {
+            self.resize(self.m * 2);
+        }
+    }
+    /// 1 or 2 in the tables, then the position in the stash after both
+    fn probe_lengths(&self) -> Box<dyn Iterator<Item = usize> + '_> {
+        let m = self.m;
+        let slots = self.slots.iter().enumerate().filter(|(_, s)| s.is_some());
+        let stash = (0..self.stash.len()).map(|j| 2 + j + 1);
+        Box::new(slots.map(move |(i, _)| i / m + 1).chain(stash))
+    }
+}
+
+type Slots<K, V> = std::iter::Flatten<std::vec::IntoIter<Option<(K, V)>>>;
+impl<K, V, S> IntoIterator for CuckooHashST<K, V, S> {
+    type Item = (K, V);
+    type IntoIter = std::iter::Chain<Slots<K, V>, std::vec::IntoIter<(K, V)>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.slots.into_iter().flatten().chain(self.stash)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::hash_table::tests::ConstState;
+    use super::*;
+
+    #[test]
+    fn probe() {
+        let mut st = CuckooHashST::new(8);
+        for i in 0..1000 {
+            st.put(i, i);
+            assert!(st.max_probe() <= 2);
+        }
+        assert!(st.stash.is_empty());
+    }
+
+    #[test]
+    fn poor_hasher() {
+        // two keys fit, the others go to the stash
+        let mut st = CuckooHashST::with_hasher(8, ConstState::default());
+        for i in 0..10 {
+            st.put(i, i);
+        }
+        assert_eq!(8, st.stash.len());
+        assert_eq!(10, st.max_probe());
+        for i in 0..10 {
+            assert_eq!(Some(&i), st.get(&i));
+        }
+        *st.get_mut(&9).unwrap() = 0;
+        assert_eq!(Some(0), st.remove(&9));
+        assert_eq!(None, st.get(&9));
+        assert_eq!(9, st.keys().count());
+        while st.pop().is_some() {}
+        assert!(st.is_empty());
+        assert!(st.stash.is_empty());
+    }
+}
@@ -0,0 +1,162 @@
+use super::linear_probing_hash;
+use super::{DefaultHashBuilder, LinearProbingHashST};
+
+use std::fmt;
+use std::hash::{BuildHasher, Hash};
+use std::iter::FromIterator;
+
+/// Hash set
+///
+/// Keys of a `LinearProbingHashST` with `()` values. Set operations probe
+/// the other set for each key of one, so they're linear in expectation,
+/// in no particular order.
+pub struct HashSET<K, S = DefaultHashBuilder> {
+    st: LinearProbingHashST<K, (), S>,
+}
+
+impl<K: Hash + Eq> Default for HashSET<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Hash + Eq> HashSET<K> {
+    pub fn new() -> Self {
+        Self::with_capacity(0)
+    }
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::with_capacity_and_hasher(capacity, DefaultHashBuilder::default())
+    }
+}
+
+impl<K: Hash + Eq, S: BuildHasher> HashSET<K, S> {
+    pub fn with_capacity_and_hasher(capacity: usize, hash_builder: S) -> Self {
+        HashSET {
+            st: LinearProbingHashST::with_capacity_and_hasher(capacity, hash_builder),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.st.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.st.is_empty()
+    }
+    pub fn contains(&self, key: &K) -> bool {
+        self.st.contains(key)
+    }
+    pub fn add(&mut self, key: K) {
+        self.st.put(key, ());
+    }
+    pub fn delete(&mut self, key: &K) {
+        self.st.delete(key);
+    }
+    /// returns whether the key was in the set
+    pub fn remove(&mut self, key: &K) -> bool {
+        self.st.remove(key).is_some()
+    }
+    pub fn iter(&self) -> impl Iterator<Item = &K> {
+        self.st.keys()
+    }
+
+    pub fn union<'a>(&'a self, other: &'a Self) -> impl Iterator<Item = &'a K> {
+        self.iter().chain(other.difference(self))
+    }
+    pub fn intersection<'a>(&'a self, other: &'a Self) -> impl Iterator<Item = &'a K> {
+        self.iter().filter(move |k| other.contains(k))
+    }
+    pub fn difference<'a>(&'a self, other: &'a Self) -> impl Iterator<Item = &'a K> {
+        self.iter().filter(move |k| !other.contains(k))
+    }
+    pub fn symmetric_difference<'a>(&'a self, other: &'a Self) -> impl Iterator<Item = &'a K> {
+        self.difference(other).chain(other.difference(self))
+    }
+    pub fn is_subset(&self, other: &Self) -> bool {
+        self.len() <= other.len() && self.iter().all(|k| other.contains(k))
+    }
+    pub fn is_superset(&self, other: &Self) -> bool {
+        other.is_subset(self)
+    }
+    pub fn is_disjoint(&self, other: &Self) -> bool {
+        // probe the larger one
+        let (small, large) = if self.len() <= other.len() {
+            (self, other)
+        } else {
+            (other, self)
+        };
+        small.iter().all(|k| !large.contains(k))
+    }
+}
+
+impl<K: Hash + Eq, S: BuildHasher> PartialEq for HashSET<K, S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.is_subset(other)
+    }
+}
+impl<K: Hash + Eq, S: BuildHasher> Eq for HashSET<K, S> {}
+
+impl<K: Hash + Eq + fmt::Debug, S: BuildHasher> fmt::Debug for HashSET<K, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<K: Hash + Eq, S: BuildHasher + Default> FromIterator<K> for HashSET<K, S> {
+    fn from_iter<I: IntoIterator<Item = K>>(iter: I) -> Self {
+        HashSET {
+            st: iter.into_iter().map(|k| (k, ())).collect(),
+        }
+    }
+}
+impl<K: Hash + Eq, S: BuildHasher> Extend<K> for HashSET<K, S> {
+    fn extend<I: IntoIterator<Item = K>>(&mut self, iter: I) {
+        self.st.extend(iter.into_iter().map(|k| (k, ())));
+    }
+}
+
+impl<K, S> IntoIterator for HashSET<K, S> {
+    type Item = K;
+    type IntoIter = std::iter::Map<linear_probing_hash::IntoIter<K, ()>, fn((K, ())) -> K>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.st.into_iter().map(|(k, _)| k)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sorted<'a>(it: impl Iterator<Item = &'a i32>) -> Vec<i32> {
+        let mut keys = it.cloned().collect::<Vec<_>>();
+        keys.sort_unstable();
+        keys
+    }
+
+    #[test]
+    fn set_operations() {
+        let a = (0..10).collect::<HashSET<_>>();
+        let b = (5..15).step_by(2).collect::<HashSET<_>>();
+        assert_eq!(
+            (0..10).chain(vec![11, 13]).collect::<Vec<_>>(),
+            sorted(a.union(&b))
+        );
+        assert_eq!(vec![5, 7, 9], sorted(a.intersection(&b)));
+        assert_eq!(vec![0, 1, 2, 3, 4, 6, 8], sorted(a.difference(&b)));
+        assert_eq!(
+            vec![0, 1, 2, 3, 4, 6, 8, 11, 13],
+            sorted(a.symmetric_difference(&b))
+        );
+
+        let c = (5..10).step_by(2).collect::<HashSET<_>>();
+        assert!(c.is_subset(&a) && c.is_subset(&b));
+        assert!(a.is_superset(&c));
+        assert!(!b.is_subset(&a));
+        assert!(!a.is_disjoint(&b));
+        assert!(c.is_disjoint(&(0..5).collect()));
+
+        assert_eq!(a, a.union(&c).cloned().collect());
+        assert_ne!(a, b);
+        assert_eq!("{7}", format!("{:?}", (7..8).collect::<HashSET<_>>()));
+    }
+}
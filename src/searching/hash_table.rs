@@ -0,0 +1,144 @@
+use super::SymbolTable;
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::BuildHasherDefault;
+
+/// the deterministic hasher used by hash tables by default
+pub type DefaultHashBuilder = BuildHasherDefault<DefaultHasher>;
+
+/// HashTable
+///
+/// Load factor control and probe statistics of hash symbol tables, to study
+/// how keys cluster in them.
+pub trait HashTable<K, V>: SymbolTable<K, V> {
+    /// number of slots, or of lists for separate chaining
+    fn table_size(&self) -> usize;
+    fn max_load_factor(&self) -> f64;
+    /// the table doubles when a put would exceed it, and halves when the load
+    /// drops to a quarter of it, panics if out of the range of the table
+    fn set_max_load_factor(&mut self, max_load: f64);
+    /// length of the probe sequence of a search hit, for each key
+    fn probe_lengths(&self) -> Box<dyn Iterator<Item = usize> + '_>;
+
+    fn load_factor(&self) -> f64 {
+        self.len() as f64 / self.table_size() as f64
+    }
+    /// the longest probe sequence of a search hit
+    fn max_probe(&self) -> usize {
+        self.probe_lengths().max().unwrap_or(0)
+    }
+    /// the average length of probe sequences of search hits
+    fn average_probe(&self) -> f64 {
+        if self.is_empty() {
+            return 0.0;
+        }
+        self.probe_lengths().sum::<usize>() as f64 / self.len() as f64
+    }
+}
+
+/// Max load factor of a table, and when to resize by it
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct MaxLoad {
+    value: f64,
+    bound: f64,
+}
+impl MaxLoad {
+    /// valid values are in (0, bound)
+    pub(crate) fn new(value: f64, bound: f64) -> Self {
+        let mut max_load = MaxLoad { value, bound };
+        max_load.set(value);
+        max_load
+    }
+
+    pub(crate) fn value(&self) -> f64 {
+        self.value
+    }
+    pub(crate) fn set(&mut self, value: f64) {
+        assert!(
+            value > 0.0 && value < self.bound,
+            "max load factor must be in (0, {})",
+            self.bound
+        );
+        self.value = value;
+    }
+
+    /// n keys in m slots are too many, time to double
+    pub(crate) fn exceeded(&self, n: usize, m: usize) -> bool {
+        n as f64 > m as f64 * self.value
+    }
+    /// n keys in m slots are down to a quarter of it, time to halve, which
+    /// brings the load back to half of it
+    pub(crate) fn shrinkable(&self, n: usize, m: usize) -> bool {
+        n > 0 && n as f64 <= m as f64 * self.value / 4.0
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::super::*;
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{BuildHasher, BuildHasherDefault, Hasher};
+
+    /// hashes everything to the same value
+    #[derive(Default)]
+    pub(crate) struct ConstHasher;
+    impl Hasher for ConstHasher {
+        fn finish(&self) -> u64 {
+            0
+        }
+        fn write(&mut self, _: &[u8]) {}
+    }
+    pub(crate) type ConstState = BuildHasherDefault<ConstHasher>;
+
+    /// `DefaultHasher` keyed by a seed, another hash function for each seed
+    /// but reproducible, unlike `RandomState`
+    #[derive(Clone)]
+    pub(crate) struct SeededState(pub(crate) u64);
+    impl BuildHasher for SeededState {
+        type Hasher = DefaultHasher;
+
+        fn build_hasher(&self) -> DefaultHasher {
+            let mut h = DefaultHasher::new();
+            h.write_u64(self.0);
+            h
+        }
+    }
+
+    fn load_factor<T>(mut st: T, max_load: f64)
+    where
+        T: HashTable<u32, u32> + IntoIterator<Item = (u32, u32)>,
+    {
+        assert_eq!(0, st.max_probe());
+        assert_eq!(0.0, st.average_probe());
+        for i in 0..1000 {
+            st.put(i, i);
+            assert!(st.load_factor() <= max_load);
+        }
+        assert_eq!(max_load, st.max_load_factor());
+        assert!(st.max_probe() as f64 >= st.average_probe());
+
+        st.set_max_load_factor(max_load / 2.0);
+        assert!(st.load_factor() <= max_load / 2.0);
+        for i in 0..990 {
+            st.delete(&i);
+        }
+        assert!(st.load_factor() >= max_load / 2.0 / 4.0);
+        let mut all = st.into_iter().map(|(k, _)| k).collect::<Vec<_>>();
+        all.sort_unstable();
+        assert_eq!((990..1000).collect::<Vec<_>>(), all);
+    }
+
+    #[test]
+    fn load_factors() {
+        load_factor(LinearProbingHashST::with_hasher(1, SeededState(48)), 0.5);
+        load_factor(SeparateChainingHashST::with_hasher(1, SeededState(48)), 8.0);
+        load_factor(RobinHoodHashST::with_hasher(1, SeededState(48)), 0.9);
+        load_factor(CuckooHashST::with_hasher(1, SeededState(48)), 0.45);
+    }
+
+    #[test]
+    #[should_panic(expected = "max load factor must be in (0, 1)")]
+    fn bad_max_load() {
+        LinearProbingHashST::<u32, u32>::new(1).set_max_load_factor(1.0);
+    }
+}
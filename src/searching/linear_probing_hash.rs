@@ -1,24 +1,60 @@
-use std::collections::hash_map::DefaultHasher;
-use std::hash::{Hash, Hasher};
+use super::hash_table::MaxLoad;
+use super::{DefaultHashBuilder, HashTable, SymbolTable};
 
-pub struct LinearProbingHashST<K, V> {
-    keys: Vec<Option<K>>,
-    values: Vec<Option<V>>,
-    m: usize,
+use std::hash::{BuildHasher, Hash};
+use std::iter::FromIterator;
+
+pub struct LinearProbingHashST<K, V, S = DefaultHashBuilder> {
+    slots: Vec<Option<(K, V)>>,
     n: usize,
+    hash_builder: S,
+    max_load: MaxLoad,
 }
 
-impl<K, V> LinearProbingHashST<K, V>
-where
-    K: Hash + Clone + Eq,
-    V: Clone,
-{
+impl<K: Hash + Eq, V> LinearProbingHashST<K, V> {
     pub fn new(size: usize) -> Self {
+        Self::with_hasher(size, DefaultHashBuilder::default())
+    }
+    /// a table holding at least `capacity` keys without resizing
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::with_capacity_and_hasher(capacity, DefaultHashBuilder::default())
+    }
+}
+
+impl<K: Hash + Eq, V, S: BuildHasher> LinearProbingHashST<K, V, S> {
+    pub const DEFAULT_MAX_LOAD: f64 = 0.5;
+
+    /// a table of `size` slots
+    pub fn with_hasher(size: usize, hash_builder: S) -> Self {
         LinearProbingHashST {
-            keys: vec![None; size],
-            values: vec![None; size],
-            m: size,
+            slots: (0..size.max(1)).map(|_| None).collect(),
             n: 0,
+            hash_builder,
+            max_load: MaxLoad::new(Self::DEFAULT_MAX_LOAD, 1.0),
+        }
+    }
+    pub fn with_capacity_and_hasher(capacity: usize, hash_builder: S) -> Self {
+        let mut st = Self::with_hasher(1, hash_builder);
+        st.reserve(capacity);
+        st
+    }
+
+    /// number of keys the table can hold without resizing
+    pub fn capacity(&self) -> usize {
+        (self.slots.len() as f64 * self.max_load.value()) as usize
+    }
+    /// makes room for `additional` more keys without resizing
+    pub fn reserve(&mut self, additional: usize) {
+        let size = self.size_for(self.n + additional);
+        if size > self.slots.len() {
+            self.resize(size);
+        }
+    }
+    /// shrinks the table to the fewest slots holding the keys
+    pub fn shrink_to_fit(&mut self) {
+        let size = self.size_for(self.n);
+        if size < self.slots.len() {
+            self.resize(size);
         }
     }
 
@@ -33,137 +69,204 @@ where
         self.get(key).is_some()
     }
     pub fn get(&self, key: &K) -> Option<&V> {
+        let i = self.find(key)?;
+        self.slots[i].as_ref().map(|(_, v)| v)
+    }
+    pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        let i = self.find(key)?;
+        self.slots[i].as_mut().map(|(_, v)| v)
+    }
+    pub fn put(&mut self, key: K, value: V) {
+        if let Some(v) = self.get_mut(&key) {
+            *v = value;
+            return;
+        }
+        if self.max_load.exceeded(self.n + 1, self.slots.len()) {
+            self.resize(self.slots.len() * 2);
+        }
+        self.insert(key, value);
+    }
+    pub fn delete(&mut self, key: &K) {
+        self.remove(key);
+    }
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        let i = self.find(key)?;
+        Some(self.remove_at(i).1)
+    }
+    pub fn pop(&mut self) -> Option<(K, V)> {
+        let i = self.slots.iter().position(|s| s.is_some())?;
+        Some(self.remove_at(i))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        self.slots.iter().flatten().map(|(k, v)| (k, v))
+    }
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (&K, &mut V)> {
+        self.slots.iter_mut().flatten().map(|(k, v)| (&*k, v))
+    }
+    pub fn keys(&self) -> impl Iterator<Item = &K> {
+        self.iter().map(|(k, _)| k)
+    }
+    pub fn values(&self) -> impl Iterator<Item = &V> {
+        self.iter().map(|(_, v)| v)
+    }
+}
+
+// private methods
+impl<K: Hash + Eq, V, S: BuildHasher> LinearProbingHashST<K, V, S> {
+    fn hash(&self, key: &K) -> usize {
+        self.hash_builder.hash_one(key) as usize % self.slots.len()
+    }
+
+    // fewest slots holding n keys within the max load factor
+    fn size_for(&self, n: usize) -> usize {
+        let mut size = ((n as f64 / self.max_load.value()).ceil() as usize).max(1);
+        while self.max_load.exceeded(n, size) {
+            size += 1;
+        }
+        size
+    }
+
+    fn find(&self, key: &K) -> Option<usize> {
         let mut i = self.hash(key);
-        while let Some(ref k) = self.keys[i] {
+        while let Some((k, _)) = &self.slots[i] {
             if k == key {
-                return self.values[i].as_ref();
+                return Some(i);
             }
-            i = (i + 1) % self.n;
+            i = (i + 1) % self.slots.len();
         }
 
         None
     }
-    pub fn put(&mut self, key: K, value: V) {
-        if self.n >= self.m / 2 {
-            self.resize(self.m * 2);
-        }
 
+    // the key must be absent, and there must be an empty slot
+    fn insert(&mut self, key: K, value: V) {
         let mut i = self.hash(&key);
-        while self.keys[i].is_some() {
-            if self.keys[i].as_ref().map_or(false, |k| k == &key) {
-                self.values[i] = Some(value);
-                return;
-            }
-            i = (i + 1) % self.m;
+        while self.slots[i].is_some() {
+            i = (i + 1) % self.slots.len();
         }
 
-        self.keys[i] = Some(key);
-        self.values[i] = Some(value);
+        self.slots[i] = Some((key, value));
         self.n += 1;
     }
-    pub fn delete(&mut self, key: &K) {
-        let mut i = self.hash(key);
-        let mut found = false;
-        while let Some(ref k) = self.keys[i] {
-            if k == key {
-                self.keys[i].take();
-                self.values[i].take();
-                self.n -= 1;
-                found = true;
-                break;
-            }
-            i = (i + 1) % self.m;
-        }
-        if !found {
-            return;
-        }
+
+    fn remove_at(&mut self, i: usize) -> (K, V) {
+        let m = self.slots.len();
+        let kv = self.slots[i].take().unwrap();
+        self.n -= 1;
 
         // rehash all keys in same cluster
-        i = (i + 1) % self.m;
-        while let (Some(k), Some(v)) = (self.keys[i].take(), self.values[i].take()) {
+        let mut i = (i + 1) % m;
+        while let Some((k, v)) = self.slots[i].take() {
             self.n -= 1;
-            self.put(k, v);
-            i = (i + 1) % self.m;
+            self.insert(k, v);
+            i = (i + 1) % m;
         }
 
-        // shrink if neccessary
-        if self.n > 0 && self.n <= self.m / 8 {
-            self.resize(self.m / 2);
+        // shrink if necessary
+        if self.max_load.shrinkable(self.n, m) {
+            self.resize(m / 2);
         }
+
+        kv
     }
-    pub fn pop(&mut self) -> Option<(K, V)> {
-        if self.n == 0 {
-            return None;
-        }
 
-        for i in 0..self.m {
-            if let (Some(k), Some(v)) = (self.keys[i].take(), self.values[i].take()) {
-                return Some((k, v));
-            }
+    fn resize(&mut self, size: usize) {
+        let slots = (0..size.max(1)).map(|_| None).collect();
+        let slots = std::mem::replace(&mut self.slots, slots);
+        self.n = 0;
+        for (key, value) in slots.into_iter().flatten() {
+            self.insert(key, value);
         }
-        None
     }
 }
 
-// private methods
-impl<K, V> LinearProbingHashST<K, V>
-where
-    K: Hash + Clone + Eq,
-    V: Clone,
-{
-    fn hash(&self, key: &K) -> usize {
-        let mut hasher = DefaultHasher::new();
-        key.hash(&mut hasher);
-        hasher.finish() as usize % self.m
+impl<K: Hash + Eq, V, S: BuildHasher> SymbolTable<K, V> for LinearProbingHashST<K, V, S> {
+    fn len(&self) -> usize {
+        self.n
     }
+    fn get(&self, key: &K) -> Option<&V> {
+        LinearProbingHashST::get(self, key)
+    }
+    fn put(&mut self, key: K, value: V) {
+        LinearProbingHashST::put(self, key, value)
+    }
+    fn delete(&mut self, key: &K) {
+        LinearProbingHashST::delete(self, key)
+    }
+    fn keys(&self) -> Box<dyn Iterator<Item = &K> + '_> {
+        Box::new(LinearProbingHashST::keys(self))
+    }
+}
 
-    fn resize(&mut self, size: usize) {
-        let mut keys = vec![None; size];
-        let mut values = vec![None; size];
-
-        std::mem::swap(&mut keys, &mut self.keys);
-        std::mem::swap(&mut values, &mut self.values);
-        self.m = size;
-        self.n = 0;
-
-        for (key, value) in keys.into_iter().zip(values.into_iter()) {
-            if let (Some(key), Some(value)) = (key, value) {
-                self.put(key, value);
-            }
+impl<K: Hash + Eq, V, S: BuildHasher> HashTable<K, V> for LinearProbingHashST<K, V, S> {
+    fn table_size(&self) -> usize {
+        self.slots.len()
+    }
+    fn max_load_factor(&self) -> f64 {
+        self.max_load.value()
+    }
+    /// must be in (0, 1)
+    fn set_max_load_factor(&mut self, max_load: f64) {
+        self.max_load.set(max_load);
+        while self.max_load.exceeded(self.n, self.slots.len()) {
+            self.resize(self.slots.len() * 2);
         }
     }
+    /// distance from the hash of a key to its slot, plus one
+    fn probe_lengths(&self) -> Box<dyn Iterator<Item = usize> + '_> {
+        let m = self.slots.len();
+        Box::new(self.slots.iter().enumerate().filter_map(move |(i, s)| {
+            let h = self.hash(&s.as_ref()?.0);
+            Some((i + m - h) % m + 1)
+        }))
+    }
 }
 
-impl<K, V> IntoIterator for LinearProbingHashST<K, V>
+impl<K, V, S> FromIterator<(K, V)> for LinearProbingHashST<K, V, S>
 where
-    K: Hash + Clone + Eq,
-    V: Clone,
+    K: Hash + Eq,
+    S: BuildHasher + Default,
 {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let iter = iter.into_iter();
+        let mut st = Self::with_capacity_and_hasher(iter.size_hint().0, S::default());
+        st.extend(iter);
+        st
+    }
+}
+impl<K: Hash + Eq, V, S: BuildHasher> Extend<(K, V)> for LinearProbingHashST<K, V, S> {
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        self.reserve(iter.size_hint().0);
+        for (key, value) in iter {
+            self.put(key, value);
+        }
+    }
+}
+
+impl<K, V, S> IntoIterator for LinearProbingHashST<K, V, S> {
     type Item = (K, V);
     type IntoIter = IntoIter<K, V>;
 
     fn into_iter(self) -> Self::IntoIter {
-        // take self
-        IntoIter(self)
+        IntoIter(self.slots.into_iter().flatten())
     }
 }
-pub struct IntoIter<K, V>(LinearProbingHashST<K, V>);
-impl<K, V> Iterator for IntoIter<K, V>
-where
-    K: Hash + Clone + Eq,
-    V: Clone,
-{
+pub struct IntoIter<K, V>(std::iter::Flatten<std::vec::IntoIter<Option<(K, V)>>>);
+impl<K, V> Iterator for IntoIter<K, V> {
     type Item = (K, V);
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.0.pop()
+        self.0.next()
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use rand::{thread_rng, Rng};
+    use rand::{thread_rng, Rng, SeedableRng, StdRng};
+    use std::collections::HashMap;
 
     #[test]
     fn empty() {
@@ -204,6 +307,19 @@ mod tests {
         }
     }
 
+    #[test]
+    fn small_tables() {
+        // probing wraps around the table size, not the number of keys
+        let mut st = LinearProbingHashST::<usize, usize>::new(0);
+        for i in 0..20 {
+            st.put(i, i);
+        }
+        for i in 0..20 {
+            assert_eq!(Some(&i), st.get(&i));
+        }
+        assert_eq!(None, st.get(&20));
+    }
+
     #[test]
     fn delete() {
         let mut st = LinearProbingHashST::<usize, usize>::new(16);
@@ -249,4 +365,138 @@ mod tests {
 
         assert!(st.into_iter().all(|(k, v)| k == v));
     }
+
+    #[test]
+    fn pop() {
+        let mut st = LinearProbingHashST::<usize, usize>::new(0);
+        for i in 0..10 {
+            st.put(i, i * 2);
+        }
+        let (k, v) = st.pop().unwrap();
+        assert_eq!(k * 2, v);
+        assert_eq!(9, st.len());
+        assert_eq!(None, st.get(&k));
+        assert!((0..10)
+            .filter(|&i| i != k)
+            .all(|i| st.get(&i) == Some(&(i * 2))));
+        assert_eq!(9, st.keys().count());
+    }
+
+    #[test]
+    fn no_clone() {
+        struct NoClone(usize);
+        let mut st = LinearProbingHashST::new(1);
+        for i in 0..10 {
+            st.put(i.to_string(), NoClone(i));
+        }
+        for (_, v) in st.iter_mut() {
+            v.0 *= 2;
+        }
+        assert_eq!(Some(6), st.get(&"3".into()).map(|v| v.0));
+        assert_eq!(90, st.values().map(|v| v.0).sum::<usize>());
+        let mut keys = st.keys().cloned().collect::<Vec<_>>();
+        keys.sort_unstable();
+        assert_eq!("0123456789", keys.concat());
+        assert_eq!(10, st.iter().count());
+    }
+
+    #[test]
+    fn capacity() {
+        let mut st = LinearProbingHashST::with_capacity(100);
+        assert!(st.capacity() >= 100);
+        let slots = st.slots.len();
+        for i in 0..100 {
+            st.put(i, i);
+        }
+        assert_eq!(slots, st.slots.len());
+
+        st.reserve(1000);
+        assert!(st.capacity() >= 1100);
+        st.shrink_to_fit();
+        assert!(st.capacity() >= 100 && st.capacity() < 110);
+        assert_eq!((0..100).sum::<i32>(), st.values().sum());
+
+        let mut st = LinearProbingHashST::<u32, u32>::new(64);
+        st.shrink_to_fit();
+        assert_eq!(1, st.slots.len());
+        assert_eq!(0, st.capacity());
+        st.put(1, 1);
+        assert_eq!(Some(&1), st.get(&1));
+    }
+
+    #[test]
+    fn from_iter_extend() {
+        let mut st = (0..10)
+            .map(|i| (i, i * i))
+            .collect::<LinearProbingHashST<_, _>>();
+        assert_eq!(10, st.len());
+        assert_eq!(Some(&81), st.get(&9));
+        st.extend((5..15).map(|i| (i, 0)));
+        assert_eq!(15, st.len());
+        assert_eq!(Some(&16), st.get(&4));
+        assert_eq!(Some(&0), st.get(&5));
+
+        let mut pairs = st.into_iter().collect::<Vec<_>>();
+        pairs.sort_unstable();
+        assert_eq!((0, 0), pairs[0]);
+        assert_eq!((14, 0), pairs[14]);
+    }
+
+    #[test]
+    fn pop_all() {
+        let mut st = (0..100)
+            .map(|i| (i, i))
+            .collect::<LinearProbingHashST<_, _>>();
+        for n in (0..100).rev() {
+            let (k, v) = st.pop().unwrap();
+            assert_eq!(k, v);
+            assert_eq!(n, st.len());
+            assert!(!st.contains(&k));
+        }
+        assert_eq!(None, st.pop());
+        assert!(st.is_empty());
+    }
+
+    #[test]
+    fn differential() {
+        let mut rng = StdRng::from_seed(&[49][..]);
+        let mut st = LinearProbingHashST::new(1);
+        let mut map = HashMap::new();
+        for i in 0..10000 {
+            let k: u16 = rng.gen_range(0, 300);
+            match rng.gen_range(0, 10) {
+                0..=3 => assert_eq!(map.insert(k, i), {
+                    let old = st.get(&k).copied();
+                    st.put(k, i);
+                    old
+                }),
+                4..=6 => assert_eq!(map.remove(&k), st.remove(&k)),
+                7 => {
+                    if let Some(v) = map.get_mut(&k) {
+                        *v += 1;
+                    }
+                    if let Some(v) = st.get_mut(&k) {
+                        *v += 1;
+                    }
+                }
+                8 => match st.pop() {
+                    Some((k, v)) => assert_eq!(Some(v), map.remove(&k)),
+                    None => assert!(map.is_empty()),
+                },
+                _ => match i % 3 {
+                    0 => st.reserve(k as usize),
+                    1 => st.shrink_to_fit(),
+                    _ => st.set_max_load_factor(0.3 + (k % 60) as f64 / 100.0),
+                },
+            }
+            assert_eq!(map.len(), st.len());
+            assert_eq!(map.get(&k), st.get(&k));
+            assert!(st.load_factor() <= st.max_load_factor());
+        }
+        let mut pairs = st.iter().map(|(k, v)| (*k, *v)).collect::<Vec<_>>();
+        pairs.sort_unstable();
+        let mut expected = map.into_iter().collect::<Vec<_>>();
+        expected.sort_unstable();
+        assert_eq!(expected, pairs);
+    }
 }
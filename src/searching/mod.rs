@@ -1,13 +1,41 @@
+mod avl_tree;
 mod binary_search;
 mod binary_search_st;
 mod bstree;
+mod btree;
+mod cuckoo_hash;
+mod hash_set;
+mod hash_table;
 mod linear_probing_hash;
 mod low_bound;
+mod paged_btree;
+mod persistent_rbtree;
 mod rbtree;
+mod robin_hood_hash;
+mod separate_chaining_hash;
+mod set;
+mod skip_list;
+mod splay_tree;
+mod symbol_table;
+mod treap;
 
+pub use self::avl_tree::*;
 pub use self::binary_search::*;
 pub use self::binary_search_st::*;
 pub use self::bstree::*;
+pub use self::btree::*;
+pub use self::cuckoo_hash::*;
+pub use self::hash_set::*;
+pub use self::hash_table::*;
 pub use self::linear_probing_hash::*;
 pub use self::low_bound::*;
+pub use self::paged_btree::*;
+pub use self::persistent_rbtree::*;
 pub use self::rbtree::*;
+pub use self::robin_hood_hash::*;
+pub use self::separate_chaining_hash::*;
+pub use self::set::*;
+pub use self::skip_list::*;
+pub use self::splay_tree::*;
+pub use self::symbol_table::*;
+pub use self::treap::*;
@@ -0,0 +1,835 @@
+use std::fs::{File, OpenOptions};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::marker::PhantomData;
+use std::ops::{Bound, RangeBounds};
+use std::path::Path;
+
+pub type PageId = u64;
+
+/// Storage of fixed-size pages, addressed by page number from 0
+pub trait PageStore {
+    fn page_size(&self) -> usize;
+    /// number of pages in the store
+    fn page_count(&self) -> u64;
+    fn read_page(&mut self, id: PageId, buf: &mut [u8]) -> io::Result<()>;
+    /// writing page `page_count()` appends a new page
+    fn write_page(&mut self, id: PageId, buf: &[u8]) -> io::Result<()>;
+    fn sync(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Pages kept in memory
+pub struct MemoryPageStore {
+    page_size: usize,
+    pages: Vec<Box<[u8]>>,
+}
+impl MemoryPageStore {
+    pub fn new(page_size: usize) -> Self {
+        MemoryPageStore {
+            page_size,
+            pages: Vec::new(),
+        }
+    }
+}
+impl PageStore for MemoryPageStore {
+    fn page_size(&self) -> usize {
+        self.page_size
+    }
+    fn page_count(&self) -> u64 {
+        self.pages.len() as u64
+    }
+    fn read_page(&mut self, id: PageId, buf: &mut [u8]) -> io::Result<()> {
+        let page = self
+            .pages
+            .get(id as usize)
+            .ok_or_else(|| out_of_range(id))?;
+        buf.copy_from_slice(page);
+        Ok(())
+    }
+    fn write_page(&mut self, id: PageId, buf: &[u8]) -> io::Result<()> {
+        if id == self.page_count() {
+            self.pages.push(buf.into());
+        } else {
+            let page = self
+                .pages
+                .get_mut(id as usize)
+                .ok_or_else(|| out_of_range(id))?;
+            page.copy_from_slice(buf);
+        }
+        Ok(())
+    }
+}
+
+/// Pages stored in a file, page `i` at offset `i * page_size`
+pub struct FilePageStore {
+    file: File,
+    page_size: usize,
+    count: u64,
+}
+impl FilePageStore {
+    /// creates an empty store, truncating the file if it exists
+    pub fn create<P: AsRef<Path>>(path: P, page_size: usize) -> io::Result<Self> {
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(path)?;
+        Ok(FilePageStore {
+            file,
+            page_size,
+            count: 0,
+        })
+    }
+    pub fn open<P: AsRef<Path>>(path: P, page_size: usize) -> io::Result<Self> {
+        let file = OpenOptions::new().read(true).write(true).open(path)?;
+        let count = file.metadata()?.len() / page_size as u64;
+        Ok(FilePageStore {
+            file,
+            page_size,
+            count,
+        })
+    }
+}
+impl PageStore for FilePageStore {
+    fn page_size(&self) -> usize {
+        self.page_size
+    }
+    fn page_count(&self) -> u64 {
+        self.count
+    }
+    fn read_page(&mut self, id: PageId, buf: &mut [u8]) -> io::Result<()> {
+        if id >= self.count {
+            return Err(out_of_range(id));
+        }
+        self.file
+            .seek(SeekFrom::Start(id * self.page_size as u64))?;
+        self.file.read_exact(buf)
+    }
+    fn write_page(&mut self, id: PageId, buf: &[u8]) -> io::Result<()> {
+        if id > self.count {
+            return Err(out_of_range(id));
+        }
+        self.file
+            .seek(SeekFrom::Start(id * self.page_size as u64))?;
+        self.file.write_all(buf)?;
+        if id == self.count {
+            self.count += 1;
+        }
+        Ok(())
+    }
+    fn sync(&mut self) -> io::Result<()> {
+        self.file.sync_data()
+    }
+}
+
+fn out_of_range(id: PageId) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidInput,
+        format!("page {} is out of range", id),
+    )
+}
+fn invalid_data(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+/// Fixed-size binary encoding of keys and values in pages
+pub trait PageCodec: Sized {
+    const SIZE: usize;
+    fn encode(&self, buf: &mut [u8]);
+    fn decode(buf: &[u8]) -> Self;
+}
+macro_rules! impl_page_codec {
+    ($($t:ty),*) => {$(
+        impl PageCodec for $t {
+            const SIZE: usize = std::mem::size_of::<$t>();
+            fn encode(&self, buf: &mut [u8]) {
+                buf[..Self::SIZE].copy_from_slice(&self.to_le_bytes());
+            }
+            fn decode(buf: &[u8]) -> Self {
+                let mut bytes = [0; std::mem::size_of::<$t>()];
+                bytes.copy_from_slice(&buf[..Self::SIZE]);
+                <$t>::from_le_bytes(bytes)
+            }
+        }
+    )*};
+}
+impl_page_codec!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128);
+impl<const N: usize> PageCodec for [u8; N] {
+    const SIZE: usize = N;
+    fn encode(&self, buf: &mut [u8]) {
+        buf[..N].copy_from_slice(self);
+    }
+    fn decode(buf: &[u8]) -> Self {
+        let mut bytes = [0; N];
+        bytes.copy_from_slice(&buf[..N]);
+        bytes
+    }
+}
+
+// header page: magic, page size, key size, value size, root, len, free list
+const MAGIC: &[u8; 4] = b"BTRE";
+const HEADER_SIZE: usize = 40;
+// node page: leaf flag, number of keys, keys, values, children
+const NODE_HEADER_SIZE: usize = 3;
+const NIL: PageId = 0;
+
+// the old value, or the median and the page of the right half of a split
+// node, after an insertion
+type Inserted<K, V> = (Option<V>, Option<(K, V, PageId)>);
+
+struct Node<K, V> {
+    id: PageId,
+    keys: Vec<K>,
+    values: Vec<V>,
+    children: Vec<PageId>,
+}
+impl<K, V> Node<K, V> {
+    fn is_leaf(&self) -> bool {
+        self.children.is_empty()
+    }
+}
+
+/// Paged B-Tree
+///
+/// A B-Tree whose nodes are stored one per page in a `PageStore`, and only
+/// read into memory along the search paths, so it can index more keys than
+/// fit in RAM. Keys and values have fixed-size encodings, and the order is
+/// the number of children fitting in a page. Page 0 keeps the header, and
+/// pages freed by merges are linked in a free list for reuse.
+pub struct PagedBTree<K, V, S> {
+    store: S,
+    order: usize,
+    root: PageId,
+    len: u64,
+    free: PageId,
+    buf: Vec<u8>,
+    _marker: PhantomData<(K, V)>,
+}
+
+impl<K: PageCodec + Ord + Clone, V: PageCodec + Clone, S: PageStore> PagedBTree<K, V, S> {
+    fn order_of(page_size: usize) -> usize {
+        let capacity = page_size.saturating_sub(NODE_HEADER_SIZE + 8) / (K::SIZE + V::SIZE + 8);
+        (capacity + 1).min(u16::MAX as usize)
+    }
+
+    /// creates an empty tree in an empty store
+    pub fn create(store: S) -> io::Result<Self> {
+        if store.page_count() != 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                "page store is not empty",
+            ));
+        }
+        let page_size = store.page_size();
+        let order = Self::order_of(page_size);
+        if page_size < HEADER_SIZE || order < 3 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "page size is too small",
+            ));
+        }
+        let mut tree = PagedBTree {
+            store,
+            order,
+            root: 1,
+            len: 0,
+            free: NIL,
+            buf: vec![0; page_size],
+            _marker: PhantomData,
+        };
+        tree.write_header()?;
+        tree.save(&Node {
+            id: 1,
+            keys: Vec::new(),
+            values: Vec::new(),
+            children: Vec::new(),
+        })?;
+        Ok(tree)
+    }
+    /// opens the tree created in the store before
+    pub fn open(mut store: S) -> io::Result<Self> {
+        let page_size = store.page_size();
+        if page_size < HEADER_SIZE {
+            return Err(invalid_data("page size is too small"));
+        }
+        let mut buf = vec![0; page_size];
+        store.read_page(0, &mut buf)?;
+        if &buf[0..4] != MAGIC {
+            return Err(invalid_data("not a B-tree"));
+        }
+        if u32::decode(&buf[4..]) as usize != page_size
+            || u32::decode(&buf[8..]) as usize != K::SIZE
+            || u32::decode(&buf[12..]) as usize != V::SIZE
+        {
+            return Err(invalid_data("page size or key/value sizes don't match"));
+        }
+        Ok(PagedBTree {
+            store,
+            order: Self::order_of(page_size),
+            root: u64::decode(&buf[16..]),
+            len: u64::decode(&buf[24..]),
+            free: u64::decode(&buf[32..]),
+            buf,
+            _marker: PhantomData,
+        })
+    }
+    /// writes all pages to the underlying storage
+    pub fn sync(&mut self) -> io::Result<()> {
+        self.store.sync()
+    }
+    pub fn into_store(self) -> S {
+        self.store
+    }
+
+    pub fn order(&self) -> usize {
+        self.order
+    }
+    pub fn len(&self) -> usize {
+        self.len as usize
+    }
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+    fn min_keys(&self) -> usize {
+        (self.order - 1) / 2
+    }
+
+    pub fn contains(&mut self, key: &K) -> io::Result<bool> {
+        Ok(self.get(key)?.is_some())
+    }
+    pub fn get(&mut self, key: &K) -> io::Result<Option<V>> {
+        let mut id = self.root;
+        loop {
+            let mut x = self.load(id)?;
+            match x.keys.binary_search(key) {
+                Ok(i) => return Ok(Some(x.values.swap_remove(i))),
+                Err(i) => match x.children.get(i) {
+                    Some(&c) => id = c,
+                    None => return Ok(None),
+                },
+            }
+        }
+    }
+    pub fn min(&mut self) -> io::Result<Option<K>> {
+        let mut x = self.load(self.root)?;
+        while let Some(&c) = x.children.first() {
+            x = self.load(c)?;
+        }
+        Ok(x.keys.into_iter().next())
+    }
+    pub fn max(&mut self) -> io::Result<Option<K>> {
+        let mut x = self.load(self.root)?;
+        while let Some(&c) = x.children.last() {
+            x = self.load(c)?;
+        }
+        Ok(x.keys.pop())
+    }
+
+    /// returns the old value of the key
+    pub fn put(&mut self, key: K, value: V) -> io::Result<Option<V>> {
+        let (old, split) = self.insert(self.root, key, value)?;
+        if let Some((k, v, right)) = split {
+            // the root splits, the tree grows one level
+            let root = Node {
+                id: self.allocate()?,
+                keys: vec![k],
+                values: vec![v],
+                children: vec![self.root, right],
+            };
+            self.save(&root)?;
+            self.root = root.id;
+        }
+        if old.is_none() {
+            self.len += 1;
+        }
+        self.write_header()?;
+        Ok(old)
+    }
+    pub fn remove(&mut self, key: &K) -> io::Result<Option<V>> {
+        let mut root = self.load(self.root)?;
+        let value = self.remove_from(&mut root, key)?;
+        if value.is_none() {
+            return Ok(None);
+        }
+        if root.keys.is_empty() && !root.is_leaf() {
+            // the root runs out of keys after a merge, the tree shrinks one level
+            self.release(root.id)?;
+            self.root = root.children[0];
+        } else {
+            self.save(&root)?;
+        }
+        self.len -= 1;
+        self.write_header()?;
+        Ok(value)
+    }
+
+    pub fn iter(&mut self) -> io::Result<PagedRange<'_, K, V, S>> {
+        self.range(..)
+    }
+    /// lazy iterator of key-value pairs within the range in order, only the
+    /// nodes on the current path are kept in memory
+    pub fn range<R: RangeBounds<K>>(&mut self, range: R) -> io::Result<PagedRange<'_, K, V, S>> {
+        let lo = range.start_bound();
+        let mut stack = Vec::new();
+        let mut id = Some(self.root);
+        while let Some(c) = id {
+            let x = self.load(c)?;
+            let i = x.keys.partition_point(|k| match lo {
+                Bound::Included(lo) => k < lo,
+                Bound::Excluded(lo) => k <= lo,
+                Bound::Unbounded => false,
+            });
+            id = x.children.get(i).copied();
+            stack.push((x, i));
+        }
+        Ok(PagedRange {
+            hi: range.end_bound().cloned(),
+            tree: self,
+            stack,
+        })
+    }
+
+    pub fn check(&mut self) -> io::Result<bool> {
+        Ok(self.check_error()?.is_none())
+    }
+    pub fn check_error(&mut self) -> io::Result<Option<String>> {
+        let (mut count, mut leaf_depth) = (0, None);
+        // (page, depth, min, max)
+        let mut stack = vec![(self.root, 0, None::<K>, None::<K>)];
+        while let Some((id, depth, min, max)) = stack.pop() {
+            let n = self.load(id)?;
+            if n.keys.windows(2).any(|w| w[0] >= w[1])
+                || n.keys
+                    .first()
+                    .is_some_and(|k| min.as_ref().is_some_and(|m| k <= m))
+                || n.keys
+                    .last()
+                    .is_some_and(|k| max.as_ref().is_some_and(|m| k >= m))
+            {
+                return Ok(Some("Keys are not in order".into()));
+            }
+            if n.keys.len() >= self.order
+                || (depth > 0 && n.keys.len() < self.min_keys())
+                || (depth == 0 && n.keys.is_empty() && !n.is_leaf())
+            {
+                return Ok(Some(format!("Node has {} keys", n.keys.len())));
+            }
+            if n.is_leaf() && *leaf_depth.get_or_insert(depth) != depth {
+                return Ok(Some("Leaves are not at the same depth".into()));
+            }
+            count += n.keys.len() as u64;
+            for (i, &c) in n.children.iter().enumerate() {
+                let lo = if i > 0 {
+                    n.keys.get(i - 1).cloned()
+                } else {
+                    min.clone()
+                };
+                let hi = n.keys.get(i).cloned().or_else(|| max.clone());
+                stack.push((c, depth + 1, lo, hi));
+            }
+        }
+        if count != self.len {
+            return Ok(Some("Size is not consistent".into()));
+        }
+        Ok(None)
+    }
+
+    fn write_header(&mut self) -> io::Result<()> {
+        let buf = &mut self.buf;
+        buf.iter_mut().for_each(|b| *b = 0);
+        buf[0..4].copy_from_slice(MAGIC);
+        (buf.len() as u32).encode(&mut buf[4..]);
+        (K::SIZE as u32).encode(&mut buf[8..]);
+        (V::SIZE as u32).encode(&mut buf[12..]);
+        self.root.encode(&mut buf[16..]);
+        self.len.encode(&mut buf[24..]);
+        self.free.encode(&mut buf[32..]);
+        self.store.write_page(0, &self.buf)
+    }
+    // reuses a page from the free list, or appends a new one
+    fn allocate(&mut self) -> io::Result<PageId> {
+        if self.free != NIL {
+            let id = self.free;
+            self.store.read_page(id, &mut self.buf)?;
+            self.free = u64::decode(&self.buf);
+            return Ok(id);
+        }
+        let id = self.store.page_count();
+        self.buf.iter_mut().for_each(|b| *b = 0);
+        self.store.write_page(id, &self.buf)?;
+        Ok(id)
+    }
+    fn release(&mut self, id: PageId) -> io::Result<()> {
+        self.buf.iter_mut().for_each(|b| *b = 0);
+        self.free.encode(&mut self.buf);
+        self.store.write_page(id, &self.buf)?;
+        self.free = id;
+        Ok(())
+    }
+
+    fn load(&mut self, id: PageId) -> io::Result<Node<K, V>> {
+        self.store.read_page(id, &mut self.buf)?;
+        let buf = &self.buf;
+        let n = u16::decode(&buf[1..]) as usize;
+        let capacity = self.order - 1;
+        if n > capacity {
+            return Err(invalid_data("corrupted node page"));
+        }
+        let (ks, vs) = (NODE_HEADER_SIZE, NODE_HEADER_SIZE + capacity * K::SIZE);
+        let cs = vs + capacity * V::SIZE;
+        let keys = (0..n)
+            .map(|i| K::decode(&buf[ks + i * K::SIZE..]))
+            .collect();
+        let values = (0..n)
+            .map(|i| V::decode(&buf[vs + i * V::SIZE..]))
+            .collect();
+        let children = if buf[0] == 1 {
+            Vec::new()
+        } else {
+            (0..=n).map(|i| u64::decode(&buf[cs + i * 8..])).collect()
+        };
+        Ok(Node {
+            id,
+            keys,
+            values,
+            children,
+        })
+    }
+    fn save(&mut self, node: &Node<K, V>) -> io::Result<()> {
+        let capacity = self.order - 1;
+        let (ks, vs) = (NODE_HEADER_SIZE, NODE_HEADER_SIZE + capacity * K::SIZE);
+        let cs = vs + capacity * V::SIZE;
+        let buf = &mut self.buf;
+        buf.iter_mut().for_each(|b| *b = 0);
+        buf[0] = node.is_leaf() as u8;
+        (node.keys.len() as u16).encode(&mut buf[1..]);
+        for (i, k) in node.keys.iter().enumerate() {
+            k.encode(&mut buf[ks + i * K::SIZE..]);
+        }
+        for (i, v) in node.values.iter().enumerate() {
+            v.encode(&mut buf[vs + i * V::SIZE..]);
+        }
+        for (i, c) in node.children.iter().enumerate() {
+            c.encode(&mut buf[cs + i * 8..]);
+        }
+        self.store.write_page(node.id, &self.buf)
+    }
+
+    fn insert(&mut self, id: PageId, key: K, value: V) -> io::Result<Inserted<K, V>> {
+        let mut node = self.load(id)?;
+        match node.keys.binary_search(&key) {
+            Ok(i) => {
+                let old = std::mem::replace(&mut node.values[i], value);
+                self.save(&node)?;
+                return Ok((Some(old), None));
+            }
+            Err(i) if node.is_leaf() => {
+                node.keys.insert(i, key);
+                node.values.insert(i, value);
+            }
+            Err(i) => match self.insert(node.children[i], key, value)? {
+                (old, None) => return Ok((old, None)),
+                (_, Some((k, v, right))) => {
+                    node.keys.insert(i, k);
+                    node.values.insert(i, v);
+                    node.children.insert(i + 1, right);
+                }
+            },
+        }
+        if node.keys.len() < self.order {
+            self.save(&node)?;
+            return Ok((None, None));
+        }
+
+        let mid = node.keys.len() / 2;
+        let right = Node {
+            id: self.allocate()?,
+            keys: node.keys.split_off(mid + 1),
+            values: node.values.split_off(mid + 1),
+            children: if node.is_leaf() {
+                Vec::new()
+            } else {
+                node.children.split_off(mid + 1)
+            },
+        };
+        let (k, v) = (node.keys.pop().unwrap(), node.values.pop().unwrap());
+        self.save(&node)?;
+        self.save(&right)?;
+        Ok((None, Some((k, v, right.id))))
+    }
+
+    // node is saved by the caller
+    fn remove_from(&mut self, node: &mut Node<K, V>, key: &K) -> io::Result<Option<V>> {
+        match node.keys.binary_search(key) {
+            Ok(i) if node.is_leaf() => {
+                node.keys.remove(i);
+                Ok(Some(node.values.remove(i)))
+            }
+            Ok(i) => {
+                // replaced by the predecessor
+                let mut child = self.load(node.children[i])?;
+                let (k, v) = self.pop_max(&mut child)?;
+                node.keys[i] = k;
+                let value = std::mem::replace(&mut node.values[i], v);
+                self.fix(node, i, child)?;
+                Ok(Some(value))
+            }
+            Err(_) if node.is_leaf() => Ok(None),
+            Err(i) => {
+                let mut child = self.load(node.children[i])?;
+                let value = self.remove_from(&mut child, key)?;
+                if value.is_some() {
+                    self.fix(node, i, child)?;
+                }
+                Ok(value)
+            }
+        }
+    }
+    fn pop_max(&mut self, node: &mut Node<K, V>) -> io::Result<(K, V)> {
+        if node.is_leaf() {
+            return Ok((node.keys.pop().unwrap(), node.values.pop().unwrap()));
+        }
+        let i = node.children.len() - 1;
+        let mut child = self.load(node.children[i])?;
+        let kv = self.pop_max(&mut child)?;
+        self.fix(node, i, child)?;
+        Ok(kv)
+    }
+    // saves the changed i-th child, after borrowing a key from a sibling
+    // through the parent or merging with a sibling if it has too few keys
+    fn fix(&mut self, node: &mut Node<K, V>, i: usize, mut child: Node<K, V>) -> io::Result<()> {
+        let min = self.min_keys();
+        if child.keys.len() >= min {
+            return self.save(&child);
+        }
+
+        let left = match i {
+            0 => None,
+            _ => Some(self.load(node.children[i - 1])?),
+        };
+        if let Some(mut left) = left.filter(|l| l.keys.len() > min) {
+            let k = std::mem::replace(&mut node.keys[i - 1], left.keys.pop().unwrap());
+            let v = std::mem::replace(&mut node.values[i - 1], left.values.pop().unwrap());
+            child.keys.insert(0, k);
+            child.values.insert(0, v);
+            if let Some(c) = left.children.pop() {
+                child.children.insert(0, c);
+            }
+            self.save(&left)?;
+            return self.save(&child);
+        }
+        let right = match node.children.get(i + 1) {
+            Some(&c) => Some(self.load(c)?),
+            None => None,
+        };
+        if let Some(mut right) = right.filter(|r| r.keys.len() > min) {
+            let k = std::mem::replace(&mut node.keys[i], right.keys.remove(0));
+            let v = std::mem::replace(&mut node.values[i], right.values.remove(0));
+            child.keys.push(k);
+            child.values.push(v);
+            if !right.is_leaf() {
+                child.children.push(right.children.remove(0));
+            }
+            self.save(&right)?;
+            return self.save(&child);
+        }
+
+        // merge j, the separator and j + 1
+        let (j, mut left, right) = if i > 0 {
+            (i - 1, self.load(node.children[i - 1])?, child)
+        } else {
+            (i, child, self.load(node.children[i + 1])?)
+        };
+        left.keys.push(node.keys.remove(j));
+        left.values.push(node.values.remove(j));
+        node.children.remove(j + 1);
+        left.keys.extend(right.keys);
+        left.values.extend(right.values);
+        left.children.extend(right.children);
+        self.save(&left)?;
+        self.release(right.id)
+    }
+}
+
+/// Iterator of `PagedBTree::range`, an I/O error ends the iteration
+pub struct PagedRange<'a, K, V, S> {
+    tree: &'a mut PagedBTree<K, V, S>,
+    // (node, index of the next key) on the path to the next key
+    stack: Vec<(Node<K, V>, usize)>,
+    hi: Bound<K>,
+}
+impl<'a, K, V, S> Iterator for PagedRange<'a, K, V, S>
+where
+    K: PageCodec + Ord + Clone,
+    V: PageCodec + Clone,
+    S: PageStore,
+{
+    type Item = io::Result<(K, V)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (n, i) = self.stack.pop()?;
+            if i >= n.keys.len() {
+                continue;
+            }
+            let in_range = match &self.hi {
+                Bound::Included(hi) => n.keys[i] <= *hi,
+                Bound::Excluded(hi) => n.keys[i] < *hi,
+                Bound::Unbounded => true,
+            };
+            if !in_range {
+                self.stack.clear();
+                return None;
+            }
+
+            let kv = (n.keys[i].clone(), n.values[i].clone());
+            let mut id = n.children.get(i + 1).copied();
+            self.stack.push((n, i + 1));
+            while let Some(c) = id {
+                match self.tree.load(c) {
+                    Ok(x) => {
+                        id = x.children.first().copied();
+                        self.stack.push((x, 0));
+                    }
+                    Err(e) => {
+                        self.stack.clear();
+                        return Some(Err(e));
+                    }
+                }
+            }
+            return Some(Ok(kv));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{Rng, SeedableRng, StdRng};
+    use std::collections::BTreeMap;
+
+    fn keys<S: PageStore>(it: PagedRange<'_, u32, u64, S>) -> Vec<u32> {
+        it.map(|r| r.unwrap().0).collect()
+    }
+
+    #[test]
+    fn basic() {
+        let mut st = PagedBTree::<u32, u64, _>::create(MemoryPageStore::new(64)).unwrap();
+        // (64 - 11) / (4 + 8 + 8) = 2 keys per page
+        assert_eq!(3, st.order());
+        assert!(st.is_empty());
+        assert_eq!(None, st.min().unwrap());
+        for i in 0..100 {
+            assert_eq!(None, st.put(i * 2, i as u64).unwrap());
+        }
+        assert_eq!(Some(3), st.put(6, 30).unwrap());
+        assert_eq!(100, st.len());
+        assert_eq!(None, st.check_error().unwrap());
+        assert_eq!(Some(30), st.get(&6).unwrap());
+        assert_eq!(None, st.get(&7).unwrap());
+        assert_eq!(Some(0), st.min().unwrap());
+        assert_eq!(Some(198), st.max().unwrap());
+
+        assert_eq!(vec![4, 6, 8], keys(st.range(3..10).unwrap()));
+        assert_eq!(vec![196, 198], keys(st.range(195..).unwrap()));
+        assert_eq!(100, st.iter().unwrap().count());
+
+        for i in 0..50 {
+            assert_eq!(Some(i as u64 * 2), st.remove(&(i * 4)).unwrap());
+        }
+        assert_eq!(None, st.remove(&0).unwrap());
+        assert_eq!(50, st.len());
+        assert_eq!(None, st.check_error().unwrap());
+    }
+
+    #[test]
+    fn too_small() {
+        let st = PagedBTree::<u64, [u8; 32], _>::create(MemoryPageStore::new(64));
+        assert_eq!(io::ErrorKind::InvalidInput, st.err().unwrap().kind());
+    }
+
+    #[test]
+    fn reopen() {
+        let mut st = PagedBTree::<u32, u64, _>::create(MemoryPageStore::new(128)).unwrap();
+        for i in 0..1000 {
+            st.put(i, i as u64 * 10).unwrap();
+        }
+        for i in 0..500 {
+            st.remove(&(i * 2)).unwrap();
+        }
+        let pages = st.store.page_count();
+        // freed pages are reused
+        for i in 0..500 {
+            st.put(i * 2, 0).unwrap();
+        }
+        assert!(st.store.page_count() <= pages + 1);
+
+        let mut st = PagedBTree::<u32, u64, _>::open(st.into_store()).unwrap();
+        assert_eq!(1000, st.len());
+        assert_eq!(Some(10), st.get(&1).unwrap());
+        assert_eq!(None, st.check_error().unwrap());
+
+        let err = PagedBTree::<u64, u64, _>::open(st.into_store())
+            .err()
+            .unwrap();
+        assert_eq!(io::ErrorKind::InvalidData, err.kind());
+        let err = PagedBTree::<u32, u64, _>::open(MemoryPageStore::new(128))
+            .err()
+            .unwrap();
+        assert_eq!(io::ErrorKind::InvalidInput, err.kind());
+    }
+
+    #[test]
+    fn file() {
+        let path =
+            std::env::temp_dir().join(format!("algs4-paged-btree-{}.db", std::process::id()));
+        {
+            let store = FilePageStore::create(&path, 256).unwrap();
+            let mut st = PagedBTree::<u64, [u8; 8], _>::create(store).unwrap();
+            for i in 0..2000u64 {
+                st.put(i * 7 % 2000, (i * 7 % 2000).to_le_bytes()).unwrap();
+            }
+            st.sync().unwrap();
+        }
+        {
+            let store = FilePageStore::open(&path, 256).unwrap();
+            let mut st = PagedBTree::<u64, [u8; 8], _>::open(store).unwrap();
+            assert_eq!(2000, st.len());
+            assert_eq!(None, st.check_error().unwrap());
+            let all = st.iter().unwrap().map(|r| r.unwrap()).collect::<Vec<_>>();
+            assert_eq!(
+                (0..2000)
+                    .map(|i| (i, u64::to_le_bytes(i)))
+                    .collect::<Vec<_>>(),
+                all
+            );
+        }
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn random() {
+        for &page_size in &[64, 100, 4096] {
+            let mut rng = StdRng::from_seed(&[page_size][..]);
+            let mut st =
+                PagedBTree::<u32, u64, _>::create(MemoryPageStore::new(page_size)).unwrap();
+            let mut map = BTreeMap::new();
+            for i in 0..3000u64 {
+                let k: u32 = rng.gen_range(0, 500);
+                if i % 5 < 2 {
+                    assert_eq!(map.remove(&k), st.remove(&k).unwrap());
+                } else {
+                    assert_eq!(map.insert(k, i), st.put(k, i).unwrap());
+                }
+                if i % 100 == 0 {
+                    assert_eq!(None, st.check_error().unwrap(), "page size {}", page_size);
+                }
+            }
+            assert_eq!(None, st.check_error().unwrap());
+            let all = st.iter().unwrap().map(|r| r.unwrap()).collect::<Vec<_>>();
+            assert_eq!(map.into_iter().collect::<Vec<_>>(), all);
+        }
+    }
+}
@@ -0,0 +1,618 @@
+use super::{OrderedSymbolTable, SymbolTable};
+
+use std::cmp::Ordering::{Equal, Greater, Less};
+use std::rc::Rc;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Color {
+    Black,
+    Red,
+}
+use Color::*;
+impl Color {
+    fn flip(&mut self) {
+        *self = if *self == Red { Black } else { Red };
+    }
+}
+
+type Link<K, V> = Option<Rc<Node<K, V>>>;
+#[derive(Clone)]
+struct Node<K, V> {
+    color: Color,
+    key: K,
+    value: V,
+    left: Link<K, V>,
+    right: Link<K, V>,
+    size: usize,
+}
+impl<K, V> Node<K, V> {
+    fn new(key: K, value: V) -> Self {
+        Node {
+            color: Red,
+            key,
+            value,
+            left: None,
+            right: None,
+            size: 1,
+        }
+    }
+}
+
+/// Persistent Left-Leaning Red Black Tree
+///
+/// Nodes are shared by `Rc` between versions, `insert`/`remove` copy nodes
+/// along the search path only (by `Rc::make_mut`) and return a new version,
+/// so O(logN) new nodes per update, and `clone` is O(1).
+pub struct PersistentRBTree<K, V> {
+    root: Link<K, V>,
+}
+
+impl<K, V> Clone for PersistentRBTree<K, V> {
+    fn clone(&self) -> Self {
+        PersistentRBTree {
+            root: self.root.clone(),
+        }
+    }
+}
+impl<K, V> Default for PersistentRBTree<K, V> {
+    fn default() -> Self {
+        PersistentRBTree { root: None }
+    }
+}
+
+impl<K: Ord, V> PersistentRBTree<K, V> {
+    pub fn new() -> Self {
+        PersistentRBTree { root: None }
+    }
+
+    pub fn len(&self) -> usize {
+        size(&self.root)
+    }
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn contains(&self, key: &K) -> bool {
+        self.get(key).is_some()
+    }
+    pub fn get(&self, key: &K) -> Option<&V> {
+        let mut x = self.root.as_ref();
+        while let Some(n) = x {
+            match key.cmp(&n.key) {
+                Equal => return Some(&n.value),
+                Less => x = n.left.as_ref(),
+                Greater => x = n.right.as_ref(),
+            }
+        }
+        None
+    }
+
+    pub fn min(&self) -> Option<&K> {
+        let mut x = self.root.as_ref()?;
+        while let Some(n) = x.left.as_ref() {
+            x = n;
+        }
+        Some(&x.key)
+    }
+    pub fn max(&self) -> Option<&K> {
+        let mut x = self.root.as_ref()?;
+        while let Some(n) = x.right.as_ref() {
+            x = n;
+        }
+        Some(&x.key)
+    }
+    pub fn floor(&self, key: &K) -> Option<&K> {
+        let (mut x, mut floor) = (self.root.as_ref(), None);
+        while let Some(n) = x {
+            match key.cmp(&n.key) {
+                Equal => return Some(&n.key),
+                Less => x = n.left.as_ref(),
+                Greater => {
+                    floor = Some(&n.key);
+                    x = n.right.as_ref();
+                }
+            }
+        }
+        floor
+    }
+    pub fn ceiling(&self, key: &K) -> Option<&K> {
+        let (mut x, mut ceiling) = (self.root.as_ref(), None);
+        while let Some(n) = x {
+            match key.cmp(&n.key) {
+                Equal => return Some(&n.key),
+                Less => {
+                    ceiling = Some(&n.key);
+                    x = n.left.as_ref();
+                }
+                Greater => x = n.right.as_ref(),
+            }
+        }
+        ceiling
+    }
+    /// number of keys less than key
+    pub fn rank(&self, key: &K) -> usize {
+        let (mut x, mut rank) = (self.root.as_ref(), 0);
+        while let Some(n) = x {
+            match key.cmp(&n.key) {
+                Equal => return rank + size(&n.left),
+                Less => x = n.left.as_ref(),
+                Greater => {
+                    rank += size(&n.left) + 1;
+                    x = n.right.as_ref();
+                }
+            }
+        }
+        rank
+    }
+    pub fn select(&self, mut i: usize) -> Option<&K> {
+        let mut x = self.root.as_ref();
+        while let Some(n) = x {
+            let ls = size(&n.left);
+            match i.cmp(&ls) {
+                Equal => return Some(&n.key),
+                Less => x = n.left.as_ref(),
+                Greater => {
+                    i -= ls + 1;
+                    x = n.right.as_ref();
+                }
+            }
+        }
+        None
+    }
+
+    pub fn iter(&self) -> PersistentIter<'_, K, V> {
+        let mut it = PersistentIter { stack: Vec::new() };
+        it.push_left(self.root.as_ref());
+        it
+    }
+
+    /// whether two versions share the same root
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        match (&self.root, &other.root) {
+            (Some(a), Some(b)) => Rc::ptr_eq(a, b),
+            (None, None) => true,
+            _ => false,
+        }
+    }
+
+    pub fn check(&self) -> bool {
+        self.check_error().is_none()
+    }
+    pub fn check_error(&self) -> Option<String> {
+        if !is_bst(&self.root, None, None) {
+            return Some("Not a BST".into());
+        }
+        if !is_23(&self.root) {
+            return Some("Not a 23 tree".into());
+        }
+        if !is_balanced(&self.root, black_height(&self.root)) {
+            return Some("Not balanced".into());
+        }
+        if !is_size_consistent(&self.root) {
+            return Some("Sizes are not consistent".into());
+        }
+        None
+    }
+}
+
+impl<K: Ord + Clone, V: Clone> PersistentRBTree<K, V> {
+    /// a new version with the key, self is untouched
+    pub fn insert(&self, key: K, value: V) -> Self {
+        let mut root = put(self.root.clone(), key, value);
+        Rc::make_mut(&mut root).color = Black;
+        PersistentRBTree { root: Some(root) }
+    }
+    /// a new version without the key, shares the whole tree if not found
+    pub fn remove(&self, key: &K) -> Self {
+        let mut root = match self.root.clone() {
+            Some(root) if self.contains(key) => root,
+            _ => return self.clone(),
+        };
+
+        if !is_red(&root.left) && !is_red(&root.right) {
+            Rc::make_mut(&mut root).color = Red;
+        }
+        let mut root = delete(root, key);
+        if let Some(r) = root.as_mut() {
+            Rc::make_mut(r).color = Black;
+        }
+        PersistentRBTree { root }
+    }
+    pub fn remove_min(&self) -> Self {
+        match self.min() {
+            Some(k) => self.remove(&k.clone()),
+            None => self.clone(),
+        }
+    }
+    pub fn remove_max(&self) -> Self {
+        match self.max() {
+            Some(k) => self.remove(&k.clone()),
+            None => self.clone(),
+        }
+    }
+}
+
+// helpers over links
+fn size<K, V>(x: &Link<K, V>) -> usize {
+    x.as_ref().map_or(0, |n| n.size)
+}
+fn is_red<K, V>(x: &Link<K, V>) -> bool {
+    x.as_ref().is_some_and(|n| n.color == Red)
+}
+fn is_red_left<K, V>(x: &Link<K, V>) -> bool {
+    x.as_ref().is_some_and(|n| is_red(&n.left))
+}
+
+fn is_bst<K: Ord, V>(x: &Link<K, V>, min: Option<&K>, max: Option<&K>) -> bool {
+    x.as_ref().is_none_or(|n| {
+        min.is_none_or(|k| n.key > *k)
+            && max.is_none_or(|k| n.key < *k)
+            && is_bst(&n.left, min, Some(&n.key))
+            && is_bst(&n.right, Some(&n.key), max)
+    })
+}
+// no red right link, and no two red links in a row
+fn is_23<K, V>(x: &Link<K, V>) -> bool {
+    x.as_ref().is_none_or(|n| {
+        !(is_red(&n.right) || n.color == Red && is_red(&n.left))
+            && is_23(&n.left)
+            && is_23(&n.right)
+    })
+}
+fn black_height<K, V>(x: &Link<K, V>) -> usize {
+    x.as_ref().map_or(0, |n| {
+        black_height(&n.left) + if n.color == Black { 1 } else { 0 }
+    })
+}
+fn is_balanced<K, V>(x: &Link<K, V>, mut height: usize) -> bool {
+    match x {
+        None => height == 0,
+        Some(n) => {
+            if n.color == Black {
+                if height == 0 {
+                    return false;
+                }
+                height -= 1;
+            }
+            is_balanced(&n.left, height) && is_balanced(&n.right, height)
+        }
+    }
+}
+fn is_size_consistent<K, V>(x: &Link<K, V>) -> bool {
+    x.as_ref().is_none_or(|n| {
+        n.size == 1 + size(&n.left) + size(&n.right)
+            && is_size_consistent(&n.left)
+            && is_size_consistent(&n.right)
+    })
+}
+
+// Balancing operations take a node and return the new top. `Rc::make_mut`
+// copies a node only if it's shared with other versions, so nodes on the
+// search path are copied once, and fresh nodes are modified in place.
+fn rotate_left<K: Clone, V: Clone>(mut h: Rc<Node<K, V>>) -> Rc<Node<K, V>> {
+    let hn = Rc::make_mut(&mut h);
+    let mut x = hn.right.take().unwrap();
+    let xn = Rc::make_mut(&mut x);
+    hn.right = xn.left.take();
+    xn.color = hn.color;
+    hn.color = Red;
+    xn.size = hn.size;
+    hn.size = 1 + size(&hn.left) + size(&hn.right);
+    xn.left = Some(h);
+    x
+}
+fn rotate_right<K: Clone, V: Clone>(mut h: Rc<Node<K, V>>) -> Rc<Node<K, V>> {
+    let hn = Rc::make_mut(&mut h);
+    let mut x = hn.left.take().unwrap();
+    let xn = Rc::make_mut(&mut x);
+    hn.left = xn.right.take();
+    xn.color = hn.color;
+    hn.color = Red;
+    xn.size = hn.size;
+    hn.size = 1 + size(&hn.left) + size(&hn.right);
+    xn.right = Some(h);
+    x
+}
+fn flip_colors<K: Clone, V: Clone>(h: &mut Rc<Node<K, V>>) {
+    let hn = Rc::make_mut(h);
+    hn.color.flip();
+    for child in [&mut hn.left, &mut hn.right].iter_mut() {
+        if let Some(c) = child.as_mut() {
+            Rc::make_mut(c).color.flip();
+        }
+    }
+}
+// restore left-leaning on the way up
+fn balance<K: Clone, V: Clone>(mut h: Rc<Node<K, V>>) -> Rc<Node<K, V>> {
+    if is_red(&h.right) && !is_red(&h.left) {
+        h = rotate_left(h);
+    }
+    if is_red(&h.left) && is_red_left(&h.left) {
+        h = rotate_right(h);
+    }
+    if is_red(&h.left) && is_red(&h.right) {
+        flip_colors(&mut h);
+    }
+    let hn = Rc::make_mut(&mut h);
+    hn.size = 1 + size(&hn.left) + size(&hn.right);
+    h
+}
+// make the left child or its left child red
+fn move_red_left<K: Clone, V: Clone>(mut h: Rc<Node<K, V>>) -> Rc<Node<K, V>> {
+    flip_colors(&mut h);
+    if is_red_left(&h.right) {
+        let hn = Rc::make_mut(&mut h);
+        hn.right = Some(rotate_right(hn.right.take().unwrap()));
+        h = rotate_left(h);
+        flip_colors(&mut h);
+    }
+    h
+}
+// make the right child or its left child red
+fn move_red_right<K: Clone, V: Clone>(mut h: Rc<Node<K, V>>) -> Rc<Node<K, V>> {
+    flip_colors(&mut h);
+    if is_red_left(&h.left) {
+        h = rotate_right(h);
+        flip_colors(&mut h);
+    }
+    h
+}
+
+fn put<K: Ord + Clone, V: Clone>(h: Link<K, V>, key: K, value: V) -> Rc<Node<K, V>> {
+    let mut h = match h {
+        None => return Rc::new(Node::new(key, value)),
+        Some(h) => h,
+    };
+
+    let hn = Rc::make_mut(&mut h);
+    match key.cmp(&hn.key) {
+        Equal => hn.value = value,
+        Less => hn.left = Some(put(hn.left.take(), key, value)),
+        Greater => hn.right = Some(put(hn.right.take(), key, value)),
+    }
+    balance(h)
+}
+fn delete_min<K: Ord + Clone, V: Clone>(mut h: Rc<Node<K, V>>) -> Link<K, V> {
+    h.left.as_ref()?;
+    if !is_red(&h.left) && !is_red_left(&h.left) {
+        h = move_red_left(h);
+    }
+    let hn = Rc::make_mut(&mut h);
+    hn.left = delete_min(hn.left.take().unwrap());
+    Some(balance(h))
+}
+// the key must exist
+fn delete<K: Ord + Clone, V: Clone>(mut h: Rc<Node<K, V>>, key: &K) -> Link<K, V> {
+    if *key < h.key {
+        if !is_red(&h.left) && !is_red_left(&h.left) {
+            h = move_red_left(h);
+        }
+        let hn = Rc::make_mut(&mut h);
+        hn.left = delete(hn.left.take().unwrap(), key);
+    } else {
+        if is_red(&h.left) {
+            h = rotate_right(h);
+        }
+        if *key == h.key && h.right.is_none() {
+            return None;
+        }
+        if !is_red(&h.right) && !is_red_left(&h.right) {
+            h = move_red_right(h);
+        }
+        let hn = Rc::make_mut(&mut h);
+        let right = hn.right.take().unwrap();
+        if *key == hn.key {
+            // replaced by the successor
+            let mut min = &right;
+            while let Some(n) = min.left.as_ref() {
+                min = n;
+            }
+            hn.key = min.key.clone();
+            hn.value = min.value.clone();
+            hn.right = delete_min(right);
+        } else {
+            hn.right = delete(right, key);
+        }
+    }
+    Some(balance(h))
+}
+
+/// Iterating in order
+pub struct PersistentIter<'a, K, V> {
+    stack: Vec<&'a Node<K, V>>,
+}
+impl<'a, K, V> PersistentIter<'a, K, V> {
+    fn push_left(&mut self, mut x: Option<&'a Rc<Node<K, V>>>) {
+        while let Some(n) = x {
+            self.stack.push(n);
+            x = n.left.as_ref();
+        }
+    }
+}
+impl<'a, K, V> Iterator for PersistentIter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let n = self.stack.pop()?;
+        self.push_left(n.right.as_ref());
+        Some((&n.key, &n.value))
+    }
+}
+
+impl<K: Ord + Clone, V: Clone> SymbolTable<K, V> for PersistentRBTree<K, V> {
+    fn len(&self) -> usize {
+        PersistentRBTree::len(self)
+    }
+    fn get(&self, key: &K) -> Option<&V> {
+        PersistentRBTree::get(self, key)
+    }
+    fn put(&mut self, key: K, value: V) {
+        *self = self.insert(key, value);
+    }
+    fn delete(&mut self, key: &K) {
+        *self = self.remove(key);
+    }
+    fn keys(&self) -> Box<dyn Iterator<Item = &K> + '_> {
+        Box::new(self.iter().map(|(k, _)| k))
+    }
+}
+impl<K: Ord + Clone, V: Clone> OrderedSymbolTable<K, V> for PersistentRBTree<K, V> {
+    fn min(&self) -> Option<&K> {
+        PersistentRBTree::min(self)
+    }
+    fn max(&self) -> Option<&K> {
+        PersistentRBTree::max(self)
+    }
+    fn floor(&self, key: &K) -> Option<&K> {
+        PersistentRBTree::floor(self, key)
+    }
+    fn ceiling(&self, key: &K) -> Option<&K> {
+        PersistentRBTree::ceiling(self, key)
+    }
+    fn rank(&self, key: &K) -> usize {
+        PersistentRBTree::rank(self, key)
+    }
+    fn select(&self, i: usize) -> Option<&K> {
+        PersistentRBTree::select(self, i)
+    }
+    fn delete_min(&mut self) {
+        *self = self.remove_min();
+    }
+    fn delete_max(&mut self) {
+        *self = self.remove_max();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{Rng, SeedableRng, StdRng};
+    use std::collections::{BTreeMap, HashSet};
+
+    // nodes reachable from the tree
+    fn nodes<K, V>(x: &Link<K, V>, set: &mut HashSet<*const Node<K, V>>) {
+        if let Some(n) = x {
+            set.insert(&**n);
+            nodes(&n.left, set);
+            nodes(&n.right, set);
+        }
+    }
+
+    #[test]
+    fn empty() {
+        let st = PersistentRBTree::<u32, u32>::new();
+        assert_eq!(0, st.len());
+        assert!(st.is_empty());
+        assert_eq!(None, st.min());
+        assert_eq!(None, st.get(&1));
+        assert_eq!(None, st.floor(&1));
+        assert_eq!(None, st.select(0));
+        assert_eq!(0, st.rank(&1));
+        assert!(st.remove(&1).ptr_eq(&st));
+        assert_eq!(None, st.check_error());
+    }
+
+    #[test]
+    fn order_methods() {
+        let mut st = PersistentRBTree::new();
+        for (i, k) in "SEARCHXMPL".chars().enumerate() {
+            st = st.insert(k, i);
+            assert_eq!(None, st.check_error());
+        }
+        assert_eq!(10, st.len());
+        assert_eq!(Some(&'A'), st.min());
+        assert_eq!(Some(&'X'), st.max());
+        assert_eq!(Some(&'H'), st.floor(&'I'));
+        assert_eq!(Some(&'L'), st.ceiling(&'I'));
+        assert_eq!(None, st.floor(&'0'));
+        assert_eq!(None, st.ceiling(&'Y'));
+        assert_eq!(3, st.rank(&'H'));
+        assert_eq!(4, st.rank(&'I'));
+        assert_eq!(Some(&'H'), st.select(3));
+        assert_eq!(None, st.select(10));
+        assert_eq!(Some(&9), st.get(&'L'));
+        assert_eq!("ACEHLMPRSX", st.iter().map(|(k, _)| *k).collect::<String>());
+    }
+
+    #[test]
+    fn versions() {
+        let v0 = PersistentRBTree::new();
+        let v1 = v0.insert(1, "a");
+        let v2 = v1.insert(2, "b");
+        let v3 = v2.insert(1, "c");
+        let v4 = v3.remove(&2);
+        let v5 = v4.clone();
+
+        assert_eq!(0, v0.len());
+        assert_eq!(Some(&"a"), v1.get(&1));
+        assert_eq!(None, v1.get(&2));
+        assert_eq!(Some(&"a"), v2.get(&1));
+        assert_eq!(Some(&"c"), v3.get(&1));
+        assert_eq!(Some(&"b"), v3.get(&2));
+        assert_eq!(None, v4.get(&2));
+        assert!(v5.ptr_eq(&v4));
+        assert!(!v4.ptr_eq(&v3));
+        assert!(v4.remove(&3).ptr_eq(&v4));
+    }
+
+    #[test]
+    fn structural_sharing() {
+        let n = 1024;
+        let mut st = PersistentRBTree::new();
+        for i in 0..n {
+            st = st.insert(i, i);
+        }
+        let mut old = HashSet::new();
+        nodes(&st.root, &mut old);
+        assert_eq!(n, old.len());
+
+        // 2*lgN is the max height of the tree, rotations and flips copy at
+        // most a few siblings on the path
+        for updated in [st.insert(n / 3, 0), st.insert(n, n), st.remove(&(n / 2))].iter() {
+            let mut new = HashSet::new();
+            nodes(&updated.root, &mut new);
+            let copied = new.difference(&old).count();
+            assert!(copied > 0 && copied <= 4 * 2 * 10, "{} copied", copied);
+            assert_eq!(None, updated.check_error());
+        }
+        assert_eq!(n, st.len());
+        assert_eq!(None, st.check_error());
+    }
+
+    #[test]
+    fn random_snapshots() {
+        let mut rng = StdRng::from_seed(&[45][..]);
+        let mut st = PersistentRBTree::new();
+        let mut map = BTreeMap::new();
+        let mut snapshots = Vec::new();
+        for i in 0..2000 {
+            let k = rng.gen_range(0, 300);
+            if rng.gen_range(0, 3) == 0 {
+                st = st.remove(&k);
+                map.remove(&k);
+            } else {
+                st = st.insert(k, i);
+                map.insert(k, i);
+            }
+            if i % 100 == 0 {
+                assert_eq!(None, st.check_error());
+                snapshots.push((st.clone(), map.clone()));
+            }
+        }
+
+        for (st, map) in snapshots.iter() {
+            assert_eq!(None, st.check_error());
+            assert_eq!(map.len(), st.len());
+            assert!(map.iter().eq(st.iter()));
+        }
+
+        while !st.is_empty() {
+            st = if rng.gen() {
+                st.remove_min()
+            } else {
+                st.remove_max()
+            };
+            assert_eq!(None, st.check_error());
+        }
+    }
+}
@@ -1,5 +1,8 @@
+use super::{Entry, OrderedSymbolTable, SymbolTable, SymbolTableMut};
+
 use std::cmp::Ordering::{Equal, Greater, Less};
 use std::fmt;
+use std::ops::{Bound, RangeBounds};
 
 #[derive(Clone, PartialEq, Eq)]
 enum Color {
@@ -103,10 +106,85 @@ impl<K: Ord, V> RBTree<K, V> {
     }
 
     pub fn iter(&self) -> Iter<'_, K, V> {
-        Iter {
+        Iter(self.range(..))
+    }
+    /// lazy iterator of keys within the range, in order from both ends
+    pub fn range<R: RangeBounds<K>>(&self, range: R) -> Range<'_, K, V> {
+        let (lo, hi) = (range.start_bound(), range.end_bound());
+        let mut r = Range {
+            front: Vec::new(),
+            back: Vec::new(),
+            remaining: self.size_bounds(lo, hi),
+        };
+        // path to the first one within lo, and the last one within hi
+        let mut t = self.root.as_ref();
+        while let Some(n) = t {
+            if above(&n.key, lo) {
+                r.front.push(&**n);
+                t = n.left.as_ref();
+            } else {
+                t = n.right.as_ref();
+            }
+        }
+        let mut t = self.root.as_ref();
+        while let Some(n) = t {
+            if below(&n.key, hi) {
+                r.back.push(&**n);
+                t = n.right.as_ref();
+            } else {
+                t = n.left.as_ref();
+            }
+        }
+        r
+    }
+    /// lazy iterator of keys within the range with mutable values, in order
+    pub fn range_mut<R: RangeBounds<K>>(&mut self, range: R) -> RangeMut<'_, K, V> {
+        let remaining = self.size_bounds(range.start_bound(), range.end_bound());
+        let mut r = RangeMut {
             stack: Vec::new(),
-            current: self.root.as_ref().map(|r| &**r),
+            remaining,
+        };
+        let lo = range.start_bound();
+        let mut t = self.root.as_mut();
+        while let Some(n) = t {
+            let Node {
+                key,
+                value,
+                left,
+                right,
+                ..
+            } = &mut **n;
+            if above(key, lo) {
+                r.stack.push((key, value, right));
+                t = left.as_mut();
+            } else {
+                t = right.as_mut();
+            }
         }
+        r
+    }
+    pub fn keys_range(&self, lo: &K, hi: &K) -> impl DoubleEndedIterator<Item = &K> {
+        self.range((Bound::Included(lo), Bound::Included(hi)))
+            .map(|(k, _)| k)
+    }
+    /// number of keys in [lo, hi], O(logN) by sizes of sub-trees
+    pub fn size_range(&self, lo: &K, hi: &K) -> usize {
+        self.size_bounds(Bound::Included(lo), Bound::Included(hi))
+    }
+
+    // number of keys within bounds
+    fn size_bounds(&self, lo: Bound<&K>, hi: Bound<&K>) -> usize {
+        let skip = match lo {
+            Bound::Included(k) => self.root.rank(k),
+            Bound::Excluded(k) => self.root.rank_le(k),
+            Bound::Unbounded => 0,
+        };
+        let end = match hi {
+            Bound::Included(k) => self.root.rank_le(k),
+            Bound::Excluded(k) => self.root.rank(k),
+            Bound::Unbounded => self.len(),
+        };
+        end.saturating_sub(skip)
     }
 
     pub fn check(&self) -> bool {
@@ -140,6 +218,12 @@ impl<K: Ord, V> RBTree<K, V> {
     pub fn get(&self, key: &K) -> Option<&V> {
         self.root.get(key)
     }
+    pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        self.root.get_mut(key)
+    }
+    pub fn entry(&mut self, key: K) -> Entry<'_, K, V, Self> {
+        SymbolTableMut::entry(self, key)
+    }
     pub fn put(&mut self, key: K, value: V) {
         let mut n = self.root.take().put(key, value);
         n.color = Black;
@@ -164,9 +248,119 @@ impl<K: Ord, V> RBTree<K, V> {
         self.root = n;
     }
     pub fn delete(&mut self, key: &K) {
-        let (mut n, _) = self.root.take().delete(key);
+        self.remove(key);
+    }
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        let (mut n, _, value) = self.root.take().remove(key);
         n.flip_red();
         self.root = n;
+        value
+    }
+
+    /// Builds a balanced tree from keys in ascending order in O(N), the value
+    /// of the last one is kept for equal keys.
+    ///
+    /// Panics if keys are not sorted.
+    pub fn from_sorted_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut items: Vec<(K, V)> = Vec::new();
+        for (k, v) in iter {
+            match items.last_mut() {
+                Some(last) if last.0 == k => last.1 = v,
+                Some(last) => {
+                    assert!(last.0 < k, "keys are not sorted");
+                    items.push((k, v));
+                }
+                None => items.push((k, v)),
+            }
+        }
+
+        // nodes at the deepest level are red, which is the only level that
+        // may be incomplete, so all paths have the same black-height
+        let n = items.len();
+        let red_depth = (usize::BITS - 1).saturating_sub(n.leading_zeros()) as usize;
+        let mut root = Tree::build(n, 0, red_depth, &mut items.into_iter());
+        root.flip_red();
+        RBTree { root }
+    }
+
+    /// Joins two trees with a key in the middle in O(logN).
+    ///
+    /// Panics unless keys of `left` < `key` < keys of `right`.
+    pub fn join(mut left: Self, key: K, value: V, mut right: Self) -> Self {
+        assert!(
+            left.max().is_none_or(|k| *k < key),
+            "left is not less than key"
+        );
+        assert!(
+            right.min().is_none_or(|k| key < *k),
+            "right is not greater than key"
+        );
+        RBTree {
+            root: Tree::join(left.root.take(), key, value, right.root.take()),
+        }
+    }
+    /// Splits the tree at the key in O(logN), returns keys >= `key`, and
+    /// keeps the smaller ones.
+    pub fn split_off(&mut self, key: &K) -> Self {
+        let (left, mid, right) = self.root.take().split(key);
+        self.root = left;
+        let mut root = match mid {
+            Some((k, v)) => Tree::join(Tree::new(), k, v, right),
+            None => right,
+        };
+        root.flip_red();
+        RBTree { root }
+    }
+    /// Moves all keys of `other` into the tree, values of `other` win on
+    /// equal keys. O(logN) if keys of the two trees don't interleave, which
+    /// is a join, otherwise O(N) by merging and rebuilding.
+    pub fn append(&mut self, other: &mut Self) {
+        if other.is_empty() {
+            return;
+        }
+        if self.is_empty() {
+            std::mem::swap(self, other);
+            return;
+        }
+
+        if self.max() < other.min() {
+            let (k, v) = other.pop_min().unwrap();
+            let root = Tree::join(self.root.take(), k, v, other.root.take());
+            self.root = root;
+        } else if other.max() < self.min() {
+            let (k, v) = self.pop_min().unwrap();
+            let root = Tree::join(other.root.take(), k, v, self.root.take());
+            self.root = root;
+        } else {
+            let (mut a, mut b) = (
+                Vec::with_capacity(self.len()),
+                Vec::with_capacity(other.len()),
+            );
+            self.root.take().drain(&mut a);
+            other.root.take().drain(&mut b);
+
+            let mut merged = Vec::with_capacity(a.len() + b.len());
+            let (mut a, mut b) = (a.into_iter().peekable(), b.into_iter().peekable());
+            loop {
+                let next = match (a.peek(), b.peek()) {
+                    (Some(x), Some(y)) => match x.0.cmp(&y.0) {
+                        Less => a.next(),
+                        Greater => b.next(),
+                        Equal => {
+                            a.next();
+                            b.next()
+                        }
+                    },
+                    (Some(_), None) => a.next(),
+                    (None, _) => b.next(),
+                };
+                match next {
+                    Some(item) => merged.push(item),
+                    None => break,
+                }
+            }
+            *self = Self::from_sorted_iter(merged);
+        }
     }
 }
 
@@ -311,7 +505,27 @@ impl<K: Ord, V> Tree<K, V> {
         self.0.as_ref().map_or(0, |b| match key.cmp(&b.key) {
             Equal => b.left.as_ref().map_or(0, |b| b.size),
             Less => b.left.rank(key),
-            Greater => b.left.rank(key) + 1 + b.right.rank(key),
+            Greater => b.left.size() + 1 + b.right.rank(key),
+        })
+    }
+    // rank of the key, and whether it's found
+    fn search(&self, key: &K) -> Result<usize, usize> {
+        self.0.as_ref().map_or(Err(0), |b| match key.cmp(&b.key) {
+            Equal => Ok(b.left.size()),
+            Less => b.left.search(key),
+            Greater => {
+                let ls = b.left.size() + 1;
+                b.right.search(key).map(|r| ls + r).map_err(|r| ls + r)
+            }
+        })
+    }
+
+    // number of keys <= key
+    fn rank_le(&self, key: &K) -> usize {
+        self.0.as_ref().map_or(0, |b| match key.cmp(&b.key) {
+            Equal => b.left.size() + 1,
+            Less => b.left.rank_le(key),
+            Greater => b.left.size() + 1 + b.right.rank_le(key),
         })
     }
 
@@ -322,6 +536,23 @@ impl<K: Ord, V> Tree<K, V> {
             Greater => node.right.get(key),
         })
     }
+    fn get_mut<'a>(&'a mut self, key: &K) -> Option<&'a mut V> {
+        self.0.as_mut().and_then(|node| match key.cmp(&node.key) {
+            Equal => Some(&mut node.value),
+            Less => node.left.get_mut(key),
+            Greater => node.right.get_mut(key),
+        })
+    }
+    fn select_mut(&mut self, i: usize) -> Option<(&K, &mut V)> {
+        self.0.as_mut().and_then(|b| {
+            let ls = b.left.size();
+            match i.cmp(&ls) {
+                Equal => Some((&b.key, &mut b.value)),
+                Less => b.left.select_mut(i),
+                Greater => b.right.select_mut(i - ls - 1),
+            }
+        })
+    }
     fn put(self, key: K, value: V) -> NodePtr<K, V> {
         match self.0 {
             None => Box::new(Node::new(key, value)),
@@ -415,47 +646,55 @@ impl<K: Ord, V> Tree<K, V> {
             }
         })
     }
-    fn delete(self, key: &K) -> (Tree<K, V>, bool) {
-        self.map_or((Tree::new(), true), |mut b| {
+    // returns the new tree, whether it's balanced, and the removed value
+    fn remove(self, key: &K) -> (Tree<K, V>, bool, Option<V>) {
+        self.map_or((Tree::new(), true, None), |mut b| {
             let balanced: bool;
+            let removed: Option<V>;
             let mut is_left = false;
             match key.cmp(&b.key) {
                 Less => {
-                    let (child, sub_b) = b.left.delete(key);
+                    let (child, sub_b, v) = b.left.remove(key);
                     b.left = child;
                     balanced = sub_b;
+                    removed = v;
                     is_left = true;
                 }
                 Greater => {
-                    let (child, sub_b) = b.right.delete(key);
+                    let (child, sub_b, v) = b.right.remove(key);
                     b.right = child;
                     balanced = sub_b;
+                    removed = v;
                 }
                 Equal => {
                     if b.left.is_none() {
-                        return Self::fix_self_with_right_child(&mut b);
+                        let (x, balanced) = Self::fix_self_with_right_child(&mut b);
+                        return (x, balanced, Some(b.value));
                     }
                     if b.right.is_none() {
-                        return Self::fix_self_with_left_child(&mut b);
+                        let (x, balanced) = Self::fix_self_with_left_child(&mut b);
+                        return (x, balanced, Some(b.value));
                     }
 
                     // replace with b's successor (min of right sub-tree)
-                    let (x, mut s, sub_b) = Self::_pop_min(b.right.unwrap());
+                    let (x, mut s, sub_b) = Self::_pop_min(b.right.take().unwrap());
                     b.right = x;
                     std::mem::swap(&mut b.key, &mut s.key);
                     std::mem::swap(&mut b.value, &mut s.value);
                     balanced = sub_b;
+                    removed = Some(s.value);
                 }
             }
 
             b.size = 1 + b.left.size() + b.right.size();
-            if balanced {
+            let (x, balanced) = if balanced {
                 (b.into(), balanced)
             } else if is_left {
                 Self::fix_left_with_sibling(b)
             } else {
                 Self::fix_right_with_sibling(b)
-            }
+            };
+            (x, balanced, removed)
         })
     }
     fn _pop_min(mut b: NodePtr<K, V>) -> (Tree<K, V>, NodePtr<K, V>, bool) {
@@ -479,6 +718,141 @@ impl<K: Ord, V> Tree<K, V> {
         }
     }
 
+    // build a tree of n items in order, red at the deepest level
+    fn build<I: Iterator<Item = (K, V)>>(
+        n: usize,
+        depth: usize,
+        red_depth: usize,
+        items: &mut I,
+    ) -> Self {
+        if n == 0 {
+            return Tree::new();
+        }
+        let left = Self::build(n / 2, depth + 1, red_depth, items);
+        let (key, value) = items.next().unwrap();
+        let right = Self::build(n - n / 2 - 1, depth + 1, red_depth, items);
+
+        let mut b = Box::new(Node::new(key, value));
+        if depth < red_depth {
+            b.color = Black;
+        }
+        b.left = left;
+        b.right = right;
+        b.size = n;
+        Tree::from(b)
+    }
+    // move all items in order into the vector
+    fn drain(self, items: &mut Vec<(K, V)>) {
+        if let Some(b) = self.0 {
+            let Node {
+                key,
+                value,
+                left,
+                right,
+                ..
+            } = *b;
+            left.drain(items);
+            items.push((key, value));
+            right.drain(items);
+        }
+    }
+
+    // join two trees with a key in the middle, the result has a black root
+    //
+    // Descend along the spine of the higher tree to a black node with the
+    // same black-height as the lower one, replace it by a red node holding
+    // both, then fix consective red nodes upward as insertion does.
+    fn join(mut left: Self, key: K, value: V, mut right: Self) -> Self {
+        left.flip_red();
+        right.flip_red();
+        let (hl, hr) = (left.black_height(0), right.black_height(0));
+
+        let mut node = Box::new(Node::new(key, value));
+        let mut root = match hl.cmp(&hr) {
+            Equal => {
+                node.size = 1 + left.size() + right.size();
+                node.left = left;
+                node.right = right;
+                node
+            }
+            Greater => Self::join_right(left, hl, node, right, hr),
+            Less => Self::join_left(left, hl, node, right, hr),
+        };
+        root.color = Black;
+        Tree::from(root)
+    }
+    // `t` is higher than `r`, the result may be a red node with a red right child
+    fn join_right(t: Self, h: isize, mut node: NodePtr<K, V>, r: Self, hr: isize) -> NodePtr<K, V> {
+        if h == hr && !t.is_red() {
+            node.size = 1 + t.size() + r.size();
+            node.left = t;
+            node.right = r;
+            return node;
+        }
+
+        let mut b = t.unwrap();
+        let h = if b.color.is_red() { h } else { h - 1 };
+        b.right = Tree::from(Self::join_right(b.right.take(), h, node, r, hr));
+        b.size = 1 + b.left.size() + b.right.size();
+        if !b.color.is_red() && b.right.is_red() && b.right.is_red_right_child() {
+            if b.left.is_red() {
+                // split 4-tree, b may conflict with its parent now
+                b.flip_colors();
+            } else {
+                b = Self::rotate_left(b);
+            }
+        }
+        b
+    }
+    // `t` is higher than `l`, the result may be a red node with a red left child
+    fn join_left(l: Self, hl: isize, mut node: NodePtr<K, V>, t: Self, h: isize) -> NodePtr<K, V> {
+        if h == hl && !t.is_red() {
+            node.size = 1 + l.size() + t.size();
+            node.left = l;
+            node.right = t;
+            return node;
+        }
+
+        let mut b = t.unwrap();
+        let h = if b.color.is_red() { h } else { h - 1 };
+        b.left = Tree::from(Self::join_left(l, hl, node, b.left.take(), h));
+        b.size = 1 + b.left.size() + b.right.size();
+        if !b.color.is_red() && b.left.is_red() && b.left.is_red_left_child() {
+            if b.right.is_red() {
+                b.flip_colors();
+            } else {
+                b = Self::rotate_right(b);
+            }
+        }
+        b
+    }
+    // split into keys < key, the key, and keys > key
+    fn split(self, key: &K) -> (Self, Option<(K, V)>, Self) {
+        match self.0 {
+            None => (Tree::new(), None, Tree::new()),
+            Some(b) => {
+                let Node {
+                    key: k,
+                    value,
+                    left,
+                    right,
+                    ..
+                } = *b;
+                match key.cmp(&k) {
+                    Equal => (left, Some((k, value)), right),
+                    Less => {
+                        let (l, mid, r) = left.split(key);
+                        (l, mid, Self::join(r, k, value, right))
+                    }
+                    Greater => {
+                        let (l, mid, r) = right.split(key);
+                        (Self::join(left, k, value, l), mid, r)
+                    }
+                }
+            }
+        }
+    }
+
     // operations
     fn rotate_left(mut node: NodePtr<K, V>) -> NodePtr<K, V> {
         let mut x = node.right.unwrap();
@@ -629,6 +1003,71 @@ impl<K: Ord, V> Tree<K, V> {
     }
 }
 
+impl<K: Ord, V> SymbolTable<K, V> for RBTree<K, V> {
+    fn len(&self) -> usize {
+        RBTree::len(self)
+    }
+    fn get(&self, key: &K) -> Option<&V> {
+        RBTree::get(self, key)
+    }
+    fn put(&mut self, key: K, value: V) {
+        RBTree::put(self, key, value)
+    }
+    fn delete(&mut self, key: &K) {
+        RBTree::delete(self, key)
+    }
+    fn keys(&self) -> Box<dyn Iterator<Item = &K> + '_> {
+        Box::new(self.iter().map(|(k, _)| k))
+    }
+}
+impl<K: Ord, V> OrderedSymbolTable<K, V> for RBTree<K, V> {
+    fn min(&self) -> Option<&K> {
+        RBTree::min(self)
+    }
+    fn max(&self) -> Option<&K> {
+        RBTree::max(self)
+    }
+    fn floor(&self, key: &K) -> Option<&K> {
+        RBTree::floor(self, key)
+    }
+    fn ceiling(&self, key: &K) -> Option<&K> {
+        RBTree::ceiling(self, key)
+    }
+    fn rank(&self, key: &K) -> usize {
+        RBTree::rank(self, key)
+    }
+    fn select(&self, i: usize) -> Option<&K> {
+        RBTree::select(self, i)
+    }
+    fn delete_min(&mut self) {
+        RBTree::delete_min(self)
+    }
+    fn delete_max(&mut self) {
+        RBTree::delete_max(self)
+    }
+    fn keys_range<'a>(&'a self, lo: &'a K, hi: &'a K) -> Box<dyn Iterator<Item = &'a K> + 'a> {
+        Box::new(RBTree::keys_range(self, lo, hi))
+    }
+    fn size_range(&self, lo: &K, hi: &K) -> usize {
+        RBTree::size_range(self, lo, hi)
+    }
+}
+
+impl<K: Ord, V> SymbolTableMut<K, V> for RBTree<K, V> {
+    fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        RBTree::get_mut(self, key)
+    }
+    fn select_mut(&mut self, i: usize) -> Option<(&K, &mut V)> {
+        self.root.select_mut(i)
+    }
+    fn remove(&mut self, key: &K) -> Option<V> {
+        RBTree::remove(self, key)
+    }
+    fn search(&self, key: &K) -> Result<usize, usize> {
+        self.root.search(key)
+    }
+}
+
 impl<K: Ord, V> IntoIterator for RBTree<K, V> {
     type Item = (K, V);
     type IntoIter = IntoIter<K, V>;
@@ -647,35 +1086,131 @@ impl<K: Ord, V> Iterator for IntoIter<K, V> {
     }
 }
 
-/// Iterating as inorder traversal
-pub struct Iter<'a, K, V> {
-    stack: Vec<&'a Node<K, V>>,
-    current: Option<&'a Node<K, V>>,
+fn above<K: Ord>(key: &K, lo: Bound<&K>) -> bool {
+    match lo {
+        Bound::Included(lo) => key >= lo,
+        Bound::Excluded(lo) => key > lo,
+        Bound::Unbounded => true,
+    }
 }
+fn below<K: Ord>(key: &K, hi: Bound<&K>) -> bool {
+    match hi {
+        Bound::Included(hi) => key <= hi,
+        Bound::Excluded(hi) => key < hi,
+        Bound::Unbounded => true,
+    }
+}
+
+/// Iterating as inorder traversal
+pub struct Iter<'a, K, V>(Range<'a, K, V>);
 impl<'a, K: Ord, V> Iterator for Iter<'a, K, V> {
     type Item = (&'a K, &'a V);
 
     fn next(&mut self) -> Option<Self::Item> {
-        // push left childen for visiting (travel to the min)
-        while let Some(l) = self.current {
-            self.stack.push(l);
-            self.current = l.left.as_ref().map(|x| &**x);
+        self.0.next()
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+impl<'a, K: Ord, V> DoubleEndedIterator for Iter<'a, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.0.next_back()
+    }
+}
+impl<'a, K: Ord, V> ExactSizeIterator for Iter<'a, K, V> {}
+
+/// Iterating a range from both ends
+///
+/// Each end keeps a stack as the path to its next node, at most O(logN), and
+/// the number of remaining keys tells when two ends meet.
+pub struct Range<'a, K, V> {
+    front: Vec<&'a Node<K, V>>,
+    back: Vec<&'a Node<K, V>>,
+    remaining: usize,
+}
+impl<'a, K: Ord, V> Iterator for Range<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+
+        // process the top in stack and push the left spine of its right child
+        let n = self.front.pop()?;
+        let mut t = n.right.as_ref();
+        while let Some(x) = t {
+            self.front.push(x);
+            t = x.left.as_ref();
+        }
+        Some((&n.key, &n.value))
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+impl<'a, K: Ord, V> DoubleEndedIterator for Range<'a, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
         }
+        self.remaining -= 1;
 
-        // process the top in stack and point current to the right child
-        self.stack.pop().map(|n| {
-            if let Some(ref r) = n.right.0 {
-                self.current = Some(r);
-            }
-            (&n.key, &n.value)
-        })
+        let n = self.back.pop()?;
+        let mut t = n.left.as_ref();
+        while let Some(x) = t {
+            self.back.push(x);
+            t = x.right.as_ref();
+        }
+        Some((&n.key, &n.value))
+    }
+}
+impl<'a, K: Ord, V> ExactSizeIterator for Range<'a, K, V> {}
+
+/// Iterating a range with mutable values, in order
+///
+/// Nodes are split into disjoint borrows of the key, the value and the right
+/// sub-tree, so the stack holds no overlapping `&mut`.
+pub struct RangeMut<'a, K, V> {
+    stack: Vec<(&'a K, &'a mut V, &'a mut Tree<K, V>)>,
+    remaining: usize,
+}
+impl<'a, K: Ord, V> Iterator for RangeMut<'a, K, V> {
+    type Item = (&'a K, &'a mut V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+
+        let (key, value, right) = self.stack.pop()?;
+        let mut t = right.as_mut();
+        while let Some(n) = t {
+            let Node {
+                key,
+                value,
+                left,
+                right,
+                ..
+            } = &mut **n;
+            self.stack.push((key, value, right));
+            t = left.as_mut();
+        }
+        Some((key, value))
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
     }
 }
+impl<'a, K: Ord, V> ExactSizeIterator for RangeMut<'a, K, V> {}
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use rand::{thread_rng, Rng};
+    use rand::{thread_rng, Rng, SeedableRng, StdRng};
 
     #[test]
     fn empty() {
@@ -878,6 +1413,262 @@ mod tests {
         assert_eq!(&19, st.max().unwrap());
         assert_eq!(None, st.check_error());
     }
+    #[test]
+    fn entry() {
+        let mut st = RBTree::<usize, usize>::new();
+        for i in 0..100 {
+            *st.entry(i % 7).or_default() += i;
+        }
+        assert_eq!(7, st.len());
+        assert_eq!(Some(&(0..100).filter(|i| i % 7 == 3).sum()), st.get(&3));
+        st.entry(3).and_modify(|v| *v = 0).or_insert(1);
+        st.entry(7).and_modify(|v| *v = 0).or_insert(1);
+        assert_eq!(Some(&0), st.get(&3));
+        assert_eq!(Some(&1), st.get(&7));
+
+        *st.get_mut(&5).unwrap() = 55;
+        assert_eq!(Some(55), st.remove(&5));
+        assert_eq!(None, st.remove(&5));
+        for i in 0..8 {
+            st.remove(&i);
+            assert!(st.check());
+        }
+        assert!(st.is_empty());
+    }
+
+    #[test]
+    fn range() {
+        let mut st = RBTree::new();
+        for i in 0..20 {
+            st.put(i * 2, i);
+        }
+        let keys = |r: Range<'_, i32, i32>| r.map(|(k, _)| *k).collect::<Vec<_>>();
+
+        assert_eq!(vec![4, 6, 8], keys(st.range(3..10)));
+        assert_eq!(vec![4, 6, 8, 10], keys(st.range(4..=10)));
+        assert_eq!(vec![0, 2], keys(st.range(..4)));
+        assert_eq!(vec![36, 38], keys(st.range(35..)));
+        assert_eq!(20, st.range(..).len());
+        assert_eq!(0, st.range(10..10).count());
+        assert_eq!(0, st.range(50..).count());
+        assert_eq!(0, st.range(..0).count());
+        assert_eq!(
+            vec![6, 8],
+            keys(st.range((Bound::Excluded(4), Bound::Excluded(10))))
+        );
+
+        // from both ends until they meet
+        let mut r = st.range(3..=11);
+        assert_eq!(4, r.len());
+        assert_eq!(Some((&10, &5)), r.next_back());
+        assert_eq!(Some((&4, &2)), r.next());
+        assert_eq!(Some((&8, &4)), r.next_back());
+        assert_eq!(Some((&6, &3)), r.next());
+        assert_eq!(None, r.next());
+        assert_eq!(None, r.next_back());
+
+        let rev = st.iter().rev().map(|(k, _)| *k).collect::<Vec<_>>();
+        assert_eq!((0..20).rev().map(|i| i * 2).collect::<Vec<_>>(), rev);
+
+        assert_eq!(vec![&4, &6, &8], st.keys_range(&3, &9).collect::<Vec<_>>());
+        assert_eq!(3, st.size_range(&3, &9));
+        assert_eq!(4, st.size_range(&4, &10));
+        assert_eq!(0, st.size_range(&9, &3));
+        assert_eq!(20, st.size_range(&-1, &100));
+    }
+
+    #[test]
+    fn range_mut() {
+        let mut st = RBTree::new();
+        for i in 0..20 {
+            st.put(i, i);
+        }
+        let mut r = st.range_mut(5..8);
+        assert_eq!(3, r.len());
+        for (k, v) in r.by_ref() {
+            *v = k * 10;
+        }
+        assert_eq!(None, r.next());
+
+        for (k, v) in st.range_mut(..) {
+            *v += k;
+        }
+        assert_eq!(Some(&4), st.get(&2));
+        assert_eq!(Some(&55), st.get(&5));
+        assert_eq!(Some(&77), st.get(&7));
+        assert_eq!(Some(&16), st.get(&8));
+        let (lo, hi) = (8, 3);
+        assert_eq!(0, st.range_mut(lo..hi).count());
+    }
+
+    #[test]
+    fn random_range() {
+        let mut rng = StdRng::from_seed(&[43][..]);
+        let mut st = RBTree::new();
+        let mut keys = Vec::new();
+        for _ in 0..200 {
+            let k: i32 = rng.gen_range(0, 500);
+            st.put(k, ());
+            keys.push(k);
+        }
+        keys.sort();
+        keys.dedup();
+
+        for _ in 0..50 {
+            let (lo, hi) = (rng.gen_range(-10, 510), rng.gen_range(-10, 510));
+            let expected = keys
+                .iter()
+                .filter(|&&k| lo <= k && k < hi)
+                .collect::<Vec<_>>();
+            let actual = st.range(lo..hi).map(|(k, _)| k).collect::<Vec<_>>();
+            assert_eq!(expected, actual);
+            let back = st.range(lo..hi).rev().map(|(k, _)| k).collect::<Vec<_>>();
+            assert_eq!(expected.iter().rev().cloned().collect::<Vec<_>>(), back);
+            let inclusive = keys.iter().filter(|&&k| lo <= k && k <= hi).count();
+            assert_eq!(inclusive, st.size_range(&lo, &hi));
+        }
+    }
+
+    #[test]
+    fn from_sorted_iter() {
+        for n in 0..40 {
+            let st = RBTree::from_sorted_iter((0..n).map(|i| (i, i * 2)));
+            assert_eq!(n, st.len());
+            assert_eq!(None, st.check_error());
+            assert_eq!(
+                (0..n).map(|i| (i, i * 2)).collect::<Vec<_>>(),
+                st.iter().map(|(k, v)| (*k, *v)).collect::<Vec<_>>()
+            );
+        }
+
+        let st = RBTree::from_sorted_iter(vec![(1, 'a'), (1, 'b'), (2, 'c')]);
+        assert_eq!(2, st.len());
+        assert_eq!(Some(&'b'), st.get(&1));
+    }
+
+    #[test]
+    #[should_panic(expected = "keys are not sorted")]
+    fn from_unsorted_iter() {
+        RBTree::from_sorted_iter(vec![(2, ()), (1, ())]);
+    }
+
+    #[test]
+    fn join() {
+        for (nl, nr) in [
+            (0, 0),
+            (0, 5),
+            (5, 0),
+            (1, 100),
+            (100, 1),
+            (30, 40),
+            (200, 3),
+        ]
+        .iter()
+        {
+            let (nl, nr) = (*nl, *nr);
+            let mut left = RBTree::new();
+            for i in 0..nl {
+                left.put(i, i);
+            }
+            let right = RBTree::from_sorted_iter((nl + 1..nl + 1 + nr).map(|i| (i, i)));
+
+            let st = RBTree::join(left, nl, nl, right);
+            assert_eq!(nl + nr + 1, st.len());
+            assert_eq!(None, st.check_error());
+            assert!(st.iter().enumerate().all(|(i, (k, _))| i == *k));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "right is not greater than key")]
+    fn join_overlapped() {
+        let right = RBTree::from_sorted_iter(vec![(1, ()), (2, ())]);
+        RBTree::join(RBTree::new(), 1, (), right);
+    }
+
+    #[test]
+    fn split_off() {
+        let mut rng = StdRng::from_seed(&[44][..]);
+        for n in [0, 1, 2, 10, 100, 257].iter() {
+            let n = *n;
+            for _ in 0..10 {
+                let mut st = RBTree::new();
+                for i in 0..n {
+                    st.put(i * 2, i);
+                }
+                let key = rng.gen_range(-1, 2 * n + 2);
+                let right = st.split_off(&key);
+                assert_eq!(None, st.check_error());
+                assert_eq!(None, right.check_error());
+                assert_eq!(n as usize, st.len() + right.len());
+                assert!(st.iter().all(|(k, _)| *k < key));
+                assert!(right.iter().all(|(k, _)| *k >= key));
+                assert_eq!(right.len(), (0..n).filter(|i| i * 2 >= key).count());
+            }
+        }
+    }
+
+    #[test]
+    fn append() {
+        // disjoint, both orders
+        let mut a = RBTree::from_sorted_iter((0..50).map(|i| (i, 0)));
+        let mut b = RBTree::from_sorted_iter((50..60).map(|i| (i, 1)));
+        a.append(&mut b);
+        assert_eq!(60, a.len());
+        assert!(b.is_empty());
+        assert_eq!(None, a.check_error());
+
+        let mut c = RBTree::from_sorted_iter((-30..0).map(|i| (i, 2)));
+        a.append(&mut c);
+        assert_eq!(90, a.len());
+        assert_eq!(None, a.check_error());
+        assert_eq!(Some(&-30), a.min());
+        assert_eq!(Some(&59), a.max());
+
+        // interleaved, values of other win
+        let mut d = RBTree::new();
+        for i in (-40..70).step_by(3) {
+            d.put(i, 3);
+        }
+        let len = d.len();
+        a.append(&mut d);
+        assert_eq!(None, a.check_error());
+        // -40, -37, -34, -31 and 62, 65, 68 are new
+        assert_eq!(90 + 4 + 3, a.len());
+        assert!(d.is_empty());
+        assert_eq!(Some(&3), a.get(&-40));
+        assert_eq!(Some(&3), a.get(&-1));
+        assert_eq!(Some(&3), a.get(&2));
+        assert_eq!(Some(&0), a.get(&1));
+        assert!(len > 30);
+
+        let mut e = RBTree::new();
+        e.append(&mut a);
+        assert_eq!(97, e.len());
+        assert!(a.is_empty());
+    }
+
+    #[test]
+    fn random_split_join() {
+        let mut rng = StdRng::from_seed(&[45][..]);
+        let mut st = RBTree::new();
+        for _ in 0..500 {
+            let k: u32 = rng.gen_range(0, 2000);
+            st.put(k, k);
+        }
+        for _ in 0..50 {
+            let n = st.len();
+            let key = rng.gen_range(0, 2000);
+            let mut right = st.split_off(&key);
+            assert_eq!(None, st.check_error());
+            assert_eq!(None, right.check_error());
+            st.append(&mut right);
+            assert_eq!(n, st.len());
+            assert_eq!(None, st.check_error());
+        }
+        assert!(st.iter().all(|(k, v)| k == v));
+    }
+
     #[test]
     fn put_fix_right() {
         let mut st = RBTree::<usize, usize>::new();
@@ -0,0 +1,248 @@
+use super::hash_table::MaxLoad;
+use super::{DefaultHashBuilder, HashTable, SymbolTable};
+
+use std::hash::{BuildHasher, Hash};
+
+/// Robin Hood hashing symbol table
+///
+/// Linear probing, where each slot remembers its distance from the hash of
+/// its key. A key being put takes the slot of a richer key, i.e. one closer
+/// to its hash, and carries on with that key, so distances are evened out
+/// and a search can stop as soon as it's farther than the key in the slot.
+/// Deletion shifts the following keys back instead of rehashing them.
+pub struct RobinHoodHashST<K, V, S = DefaultHashBuilder> {
+    slots: Vec<Option<(K, V)>>,
+    dists: Vec<usize>,
+    n: usize,
+    hash_builder: S,
+    max_load: MaxLoad,
+}
+
+impl<K: Hash + Eq, V> RobinHoodHashST<K, V> {
+    pub fn new(size: usize) -> Self {
+        Self::with_hasher(size, DefaultHashBuilder::default())
+    }
+}
+
+impl<K: Hash + Eq, V, S: BuildHasher> RobinHoodHashST<K, V, S> {
+    pub const DEFAULT_MAX_LOAD: f64 = 0.9;
+
+    pub fn with_hasher(size: usize, hash_builder: S) -> Self {
+        let size = size.max(1);
+        RobinHoodHashST {
+            slots: (0..size).map(|_| None).collect(),
+            dists: vec![0; size],
+            n: 0,
+            hash_builder,
+            max_load: MaxLoad::new(Self::DEFAULT_MAX_LOAD, 1.0),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.n
+    }
+    pub fn is_empty(&self) -> bool {
+        self.n == 0
+    }
+
+    pub fn contains(&self, key: &K) -> bool {
+        self.get(key).is_some()
+    }
+    pub fn get(&self, key: &K) -> Option<&V> {
+        let i = self.find(key)?;
+        self.slots[i].as_ref().map(|(_, v)| v)
+    }
+    pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        let i = self.find(key)?;
+        self.slots[i].as_mut().map(|(_, v)| v)
+    }
+    pub fn put(&mut self, key: K, value: V) {
+        if let Some(v) = self.get_mut(&key) {
+            *v = value;
+            return;
+        }
+        if self.max_load.exceeded(self.n + 1, self.slots.len()) {
+            self.resize(self.slots.len() * 2);
+        }
+        self.insert(key, value);
+    }
+    pub fn delete(&mut self, key: &K) {
+        self.remove(key);
+    }
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        let i = self.find(key)?;
+        Some(self.remove_at(i).1)
+    }
+    pub fn pop(&mut self) -> Option<(K, V)> {
+        let i = self.slots.iter().position(|s| s.is_some())?;
+        Some(self.remove_at(i))
+    }
+    pub fn keys(&self) -> impl Iterator<Item = &K> {
+        self.slots.iter().flatten().map(|(k, _)| k)
+    }
+}
+
+// private methods
+impl<K: Hash + Eq, V, S: BuildHasher> RobinHoodHashST<K, V, S> {
+    fn hash(&self, key: &K) -> usize {
+        self.hash_builder.hash_one(key) as usize % self.slots.len()
+    }
+
+    fn find(&self, key: &K) -> Option<usize> {
+        let m = self.slots.len();
+        let mut i = self.hash(key);
+        for d in 0.. {
+            match &self.slots[i] {
+                Some((k, _)) if k == key => return Some(i),
+                // the key would have taken this slot
+                Some(_) if self.dists[i] >= d => i = (i + 1) % m,
+                _ => break,
+            }
+        }
+        None
+    }
+
+    // the key must be absent, and there must be an empty slot
+    fn insert(&mut self, key: K, value: V) {
+        let m = self.slots.len();
+        let (mut i, mut d) = (self.hash(&key), 0);
+        let mut kv = (key, value);
+        loop {
+            match &mut self.slots[i] {
+                None => {
+                    self.slots[i] = Some(kv);
+                    self.dists[i] = d;
+                    self.n += 1;
+                    return;
+                }
+                Some(slot) => {
+                    // take from the rich
+                    if self.dists[i] < d {
+                        std::mem::swap(slot, &mut kv);
+                        std::mem::swap(&mut self.dists[i], &mut d);
+                    }
+                }
+            }
+            i = (i + 1) % m;
+            d += 1;
+        }
+    }
+
+    fn remove_at(&mut self, i: usize) -> (K, V) {
+        let m = self.slots.len();
+        let kv = self.slots[i].take().unwrap();
+        self.n -= 1;
+
+        // shift back the following keys until an empty slot or a key at its hash
+        let (mut i, mut j) = (i, (i + 1) % m);
+        while self.slots[j].is_some() && self.dists[j] > 0 {
+            self.slots[i] = self.slots[j].take();
+            self.dists[i] = self.dists[j] - 1;
+            i = j;
+            j = (j + 1) % m;
+        }
+
+        if self.max_load.shrinkable(self.n, m) {
+            self.resize(m / 2);
+        }
+
+        kv
+    }
+
+    fn resize(&mut self, size: usize) {
+        let slots = (0..size).map(|_| None).collect();
+        let slots = std::mem::replace(&mut self.slots, slots);
+        self.dists = vec![0; size];
+        self.n = 0;
+        for (key, value) in slots.into_iter().flatten() {
+            self.insert(key, value);
+        }
+    }
+}
+
+impl<K: Hash + Eq, V, S: BuildHasher> SymbolTable<K, V> for RobinHoodHashST<K, V, S> {
+    fn len(&self) -> usize {
+        self.n
+    }
+    fn get(&self, key: &K) -> Option<&V> {
+        RobinHoodHashST::get(self, key)
+    }
+    fn put(&mut self, key: K, value: V) {
+        RobinHoodHashST::put(self, key, value)
+    }
+    fn delete(&mut self, key: &K) {
+        RobinHoodHashST::delete(self, key)
+    }
+    fn keys(&self) -> Box<dyn Iterator<Item = &K> + '_> {
+        Box::new(RobinHoodHashST::keys(self))
+    }
+}
+
+impl<K: Hash + Eq, V, S: BuildHasher> HashTable<K, V> for RobinHoodHashST<K, V, S> {
+    fn table_size(&self) -> usize {
+        self.slots.len()
+    }
+    fn max_load_factor(&self) -> f64 {
+        self.max_load.value()
+    }
+    /// must be in (0, 1)
+    fn set_max_load_factor(&mut self, max_load: f64) {
+        self.max_load.set(max_load);
+        while self.max_load.exceeded(self.n, self.slots.len()) {
+            self.resize(self.slots.len() * 2);
+        }
+    }
+    fn probe_lengths(&self) -> Box<dyn Iterator<Item = usize> + '_> {
+        let slots = self.slots.iter().zip(self.dists.iter());
+        Box::new(slots.filter(|(s, _)| s.is_some()).map(|(_, d)| d + 1))
+    }
+}
+
+impl<K, V, S> IntoIterator for RobinHoodHashST<K, V, S> {
+    type Item = (K, V);
+    type IntoIter = std::iter::Flatten<std::vec::IntoIter<Option<(K, V)>>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.slots.into_iter().flatten()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::hash_table::tests::ConstState;
+    use super::super::LinearProbingHashST;
+    use super::*;
+
+    #[test]
+    fn probe() {
+        // same keys, same slots, same load factor, but shorter max probe
+        let mut lp = LinearProbingHashST::new(1024);
+        let mut rh = RobinHoodHashST::new(1024);
+        rh.set_max_load_factor(0.5);
+        for i in 0..500 {
+            lp.put(i, i);
+            rh.put(i, i);
+        }
+        assert_eq!(lp.load_factor(), rh.load_factor());
+        // the total of distances doesn't change
+        assert_eq!(lp.average_probe(), rh.average_probe());
+        assert!(rh.max_probe() <= lp.max_probe());
+    }
+
+    #[test]
+    fn backward_shift() {
+        // a single cluster from slot 0
+        let mut st = RobinHoodHashST::with_hasher(16, ConstState::default());
+        for i in 0..8 {
+            st.put(i, i);
+        }
+        assert_eq!(8, st.max_probe());
+        assert_eq!(Some(0), st.remove(&0));
+        assert_eq!(7, st.max_probe());
+        assert_eq!(4.0, st.average_probe());
+        *st.get_mut(&7).unwrap() = 0;
+        assert_eq!(Some((1, 1)), st.pop());
+        assert_eq!(Some(0), st.remove(&7));
+        assert_eq!(5, st.len());
+    }
+}
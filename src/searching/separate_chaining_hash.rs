@@ -0,0 +1,177 @@
+use super::hash_table::MaxLoad;
+use super::{DefaultHashBuilder, HashTable, SymbolTable};
+
+use std::hash::{BuildHasher, Hash};
+
+/// Separate chaining hash symbol table
+///
+/// Keys hashing to the same bucket are kept in a list, searched sequentially.
+/// The table doubles when the average length of lists would exceed the max
+/// load factor, and halves when it drops to a quarter of that.
+pub struct SeparateChainingHashST<K, V, S = DefaultHashBuilder> {
+    buckets: Vec<Vec<(K, V)>>,
+    n: usize,
+    hash_builder: S,
+    max_load: MaxLoad,
+}
+
+impl<K: Hash + Eq, V> SeparateChainingHashST<K, V> {
+    pub fn new(size: usize) -> Self {
+        Self::with_hasher(size, DefaultHashBuilder::default())
+    }
+}
+
+impl<K: Hash + Eq, V, S: BuildHasher> SeparateChainingHashST<K, V, S> {
+    pub const DEFAULT_MAX_LOAD: f64 = 8.0;
+
+    pub fn with_hasher(size: usize, hash_builder: S) -> Self {
+        SeparateChainingHashST {
+            buckets: (0..size.max(1)).map(|_| Vec::new()).collect(),
+            n: 0,
+            hash_builder,
+            max_load: MaxLoad::new(Self::DEFAULT_MAX_LOAD, f64::INFINITY),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.n
+    }
+    pub fn is_empty(&self) -> bool {
+        self.n == 0
+    }
+
+    pub fn contains(&self, key: &K) -> bool {
+        self.get(key).is_some()
+    }
+    pub fn get(&self, key: &K) -> Option<&V> {
+        let b = &self.buckets[self.hash(key)];
+        b.iter().find(|(k, _)| k == key).map(|(_, v)| v)
+    }
+    pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        let i = self.hash(key);
+        let b = &mut self.buckets[i];
+        b.iter_mut().find(|(k, _)| k == key).map(|(_, v)| v)
+    }
+    pub fn put(&mut self, key: K, value: V) {
+        if let Some(v) = self.get_mut(&key) {
+            *v = value;
+            return;
+        }
+        if self.max_load.exceeded(self.n + 1, self.buckets.len()) {
+            self.resize(self.buckets.len() * 2);
+        }
+        let i = self.hash(&key);
+        self.buckets[i].push((key, value));
+        self.n += 1;
+    }
+    pub fn delete(&mut self, key: &K) {
+        self.remove(key);
+    }
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        let i = self.hash(key);
+        let j = self.buckets[i].iter().position(|(k, _)| k == key)?;
+        Some(self.remove_at(i, j).1)
+    }
+    pub fn pop(&mut self) -> Option<(K, V)> {
+        let i = self.buckets.iter().position(|b| !b.is_empty())?;
+        Some(self.remove_at(i, 0))
+    }
+    pub fn keys(&self) -> impl Iterator<Item = &K> {
+        self.buckets.iter().flatten().map(|(k, _)| k)
+    }
+}
+
+// private methods
+impl<K: Hash + Eq, V, S: BuildHasher> SeparateChainingHashST<K, V, S> {
+    fn hash(&self, key: &K) -> usize {
+        self.hash_builder.hash_one(key) as usize % self.buckets.len()
+    }
+
+    fn remove_at(&mut self, i: usize, j: usize) -> (K, V) {
+        let kv = self.buckets[i].swap_remove(j);
+        self.n -= 1;
+
+        if self.max_load.shrinkable(self.n, self.buckets.len()) {
+            self.resize(self.buckets.len() / 2);
+        }
+
+        kv
+    }
+
+    fn resize(&mut self, size: usize) {
+        let buckets = (0..size.max(1)).map(|_| Vec::new()).collect();
+        let buckets = std::mem::replace(&mut self.buckets, buckets);
+        for (key, value) in buckets.into_iter().flatten() {
+            let i = self.hash(&key);
+            self.buckets[i].push((key, value));
+        }
+    }
+}
+
+impl<K: Hash + Eq, V, S: BuildHasher> SymbolTable<K, V> for SeparateChainingHashST<K, V, S> {
+    fn len(&self) -> usize {
+        self.n
+    }
+    fn get(&self, key: &K) -> Option<&V> {
+        SeparateChainingHashST::get(self, key)
+    }
+    fn put(&mut self, key: K, value: V) {
+        SeparateChainingHashST::put(self, key, value)
+    }
+    fn delete(&mut self, key: &K) {
+        SeparateChainingHashST::delete(self, key)
+    }
+    fn keys(&self) -> Box<dyn Iterator<Item = &K> + '_> {
+        Box::new(SeparateChainingHashST::keys(self))
+    }
+}
+
+impl<K: Hash + Eq, V, S: BuildHasher> HashTable<K, V> for SeparateChainingHashST<K, V, S> {
+    fn table_size(&self) -> usize {
+        self.buckets.len()
+    }
+    fn max_load_factor(&self) -> f64 {
+        self.max_load.value()
+    }
+    /// the max average length of lists, must be positive
+    fn set_max_load_factor(&mut self, max_load: f64) {
+        self.max_load.set(max_load);
+        while self.max_load.exceeded(self.n, self.buckets.len()) {
+            self.resize(self.buckets.len() * 2);
+        }
+    }
+    /// a list of length l takes 1, 2, .., l probes to hit its keys
+    fn probe_lengths(&self) -> Box<dyn Iterator<Item = usize> + '_> {
+        Box::new(self.buckets.iter().flat_map(|b| 1..=b.len()))
+    }
+}
+
+impl<K, V, S> IntoIterator for SeparateChainingHashST<K, V, S> {
+    type Item = (K, V);
+    type IntoIter = std::iter::Flatten<std::vec::IntoIter<Vec<(K, V)>>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.buckets.into_iter().flatten()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::hash_table::tests::ConstState;
+    use super::*;
+
+    #[test]
+    fn probe() {
+        let mut st = SeparateChainingHashST::with_hasher(8, ConstState::default());
+        for i in 0..10 {
+            st.put(i, i);
+        }
+        assert_eq!(10, st.max_probe());
+        assert_eq!(5.5, st.average_probe());
+        assert_eq!(Some(&9), st.get(&9));
+        *st.get_mut(&9).unwrap() = 0;
+        assert_eq!(Some(0), st.remove(&9));
+        while st.pop().is_some() {}
+        assert!(st.is_empty());
+    }
+}
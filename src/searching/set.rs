@@ -0,0 +1,210 @@
+use super::rbtree;
+use super::RBTree;
+
+use std::cmp::Ordering::{Equal, Greater, Less};
+use std::fmt;
+use std::iter::FromIterator;
+use std::ops::RangeBounds;
+
+/// Ordered set
+///
+/// Keys of a `RBTree` with `()` values, as the `SET` of the book. Set
+/// operations merge two sorted iterators lazily, so they're linear and
+/// yield keys in order.
+pub struct SET<K: Ord> {
+    st: RBTree<K, ()>,
+}
+
+impl<K: Ord> Default for SET<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Ord> SET<K> {
+    pub fn new() -> Self {
+        SET { st: RBTree::new() }
+    }
+
+    pub fn len(&self) -> usize {
+        self.st.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.st.is_empty()
+    }
+    pub fn contains(&self, key: &K) -> bool {
+        self.st.contains(key)
+    }
+    pub fn add(&mut self, key: K) {
+        self.st.put(key, ());
+    }
+    pub fn delete(&mut self, key: &K) {
+        self.st.delete(key);
+    }
+    /// returns whether the key was in the set
+    pub fn remove(&mut self, key: &K) -> bool {
+        self.st.remove(key).is_some()
+    }
+    pub fn pop_min(&mut self) -> Option<K> {
+        self.st.pop_min().map(|(k, _)| k)
+    }
+
+    pub fn min(&self) -> Option<&K> {
+        self.st.min()
+    }
+    pub fn max(&self) -> Option<&K> {
+        self.st.max()
+    }
+    pub fn floor(&self, key: &K) -> Option<&K> {
+        self.st.floor(key)
+    }
+    pub fn ceiling(&self, key: &K) -> Option<&K> {
+        self.st.ceiling(key)
+    }
+    pub fn rank(&self, key: &K) -> usize {
+        self.st.rank(key)
+    }
+    pub fn select(&self, i: usize) -> Option<&K> {
+        self.st.select(i)
+    }
+
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &K> {
+        self.st.iter().map(|(k, _)| k)
+    }
+    pub fn range<R: RangeBounds<K>>(&self, range: R) -> impl DoubleEndedIterator<Item = &K> {
+        self.st.range(range).map(|(k, _)| k)
+    }
+
+    pub fn union<'a>(&'a self, other: &'a Self) -> impl Iterator<Item = &'a K> {
+        merge(self.iter(), other.iter(), (true, true, true))
+    }
+    pub fn intersection<'a>(&'a self, other: &'a Self) -> impl Iterator<Item = &'a K> {
+        merge(self.iter(), other.iter(), (false, true, false))
+    }
+    pub fn difference<'a>(&'a self, other: &'a Self) -> impl Iterator<Item = &'a K> {
+        merge(self.iter(), other.iter(), (true, false, false))
+    }
+    pub fn symmetric_difference<'a>(&'a self, other: &'a Self) -> impl Iterator<Item = &'a K> {
+        merge(self.iter(), other.iter(), (true, false, true))
+    }
+    pub fn is_subset(&self, other: &Self) -> bool {
+        self.len() <= other.len() && self.difference(other).next().is_none()
+    }
+    pub fn is_superset(&self, other: &Self) -> bool {
+        other.is_subset(self)
+    }
+    pub fn is_disjoint(&self, other: &Self) -> bool {
+        self.intersection(other).next().is_none()
+    }
+}
+
+// merge two sorted iterators, keeping keys only in the left, in both, or only
+// in the right, by the flags
+fn merge<'a, K: Ord + 'a>(
+    a: impl Iterator<Item = &'a K>,
+    b: impl Iterator<Item = &'a K>,
+    (left, both, right): (bool, bool, bool),
+) -> impl Iterator<Item = &'a K> {
+    let (mut a, mut b) = (a.peekable(), b.peekable());
+    std::iter::from_fn(move || loop {
+        let ord = match (a.peek(), b.peek()) {
+            (Some(x), Some(y)) => x.cmp(y),
+            (Some(_), None) if left => Less,
+            (None, Some(_)) if right => Greater,
+            _ => return None,
+        };
+        match ord {
+            Less => {
+                let x = a.next();
+                if left {
+                    return x;
+                }
+            }
+            Greater => {
+                let y = b.next();
+                if right {
+                    return y;
+                }
+            }
+            Equal => {
+                b.next();
+                let x = a.next();
+                if both {
+                    return x;
+                }
+            }
+        }
+    })
+}
+
+impl<K: Ord> PartialEq for SET<K> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter())
+    }
+}
+impl<K: Ord> Eq for SET<K> {}
+
+impl<K: Ord + fmt::Debug> fmt::Debug for SET<K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<K: Ord> FromIterator<K> for SET<K> {
+    fn from_iter<I: IntoIterator<Item = K>>(iter: I) -> Self {
+        let mut set = SET::new();
+        set.extend(iter);
+        set
+    }
+}
+impl<K: Ord> Extend<K> for SET<K> {
+    fn extend<I: IntoIterator<Item = K>>(&mut self, iter: I) {
+        for key in iter {
+            self.add(key);
+        }
+    }
+}
+
+impl<K: Ord> IntoIterator for SET<K> {
+    type Item = K;
+    type IntoIter = std::iter::Map<rbtree::IntoIter<K, ()>, fn((K, ())) -> K>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.st.into_iter().map(|(k, _)| k)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn set_operations() {
+        let a = (0..10).collect::<SET<_>>();
+        let b = (5..15).step_by(2).collect::<SET<_>>();
+        let keys = |it: &mut dyn Iterator<Item = &i32>| it.cloned().collect::<Vec<_>>();
+        assert_eq!(
+            (0..10).chain(vec![11, 13]).collect::<Vec<_>>(),
+            keys(&mut a.union(&b))
+        );
+        assert_eq!(vec![5, 7, 9], keys(&mut a.intersection(&b)));
+        assert_eq!(vec![0, 1, 2, 3, 4, 6, 8], keys(&mut a.difference(&b)));
+        assert_eq!(vec![11, 13], keys(&mut b.difference(&a)));
+        assert_eq!(
+            vec![0, 1, 2, 3, 4, 6, 8, 11, 13],
+            keys(&mut a.symmetric_difference(&b))
+        );
+
+        let c = (5..10).step_by(2).collect::<SET<_>>();
+        assert!(c.is_subset(&a) && c.is_subset(&b));
+        assert!(a.is_superset(&c));
+        assert!(!b.is_subset(&a));
+        assert!(a.is_subset(&a));
+        assert!(!a.is_disjoint(&b));
+        assert!(c.is_disjoint(&(0..5).collect()));
+        assert!(SET::new().is_subset(&c));
+
+        assert_eq!(a, a.union(&c).cloned().collect());
+        assert_ne!(a, b);
+    }
+}
@@ -0,0 +1,469 @@
+use super::{OrderedSymbolTable, SymbolTable};
+
+use rand::{Rng, SeedableRng, XorShiftRng};
+
+const MAX_LEVEL: usize = 32;
+
+// A link to the next node of a level, `width` is the number of positions it
+// spans, where the head is at 0, the i-th key is at i+1, and the end (None)
+// is at N+1.
+#[derive(Clone, Copy)]
+struct Link {
+    next: Option<usize>,
+    width: usize,
+}
+struct Node<K, V> {
+    key: K,
+    value: V,
+    links: Vec<Link>,
+}
+
+/// Indexable Skip List
+///
+/// Sorted linked lists of levels, a node is promoted to the next level with
+/// probability 1/2, searching goes from the top level down, O(logN)
+/// expected. Widths of links make `rank`/`select` O(logN) as well.
+///
+/// Nodes live in a `Vec` and link to each other by indices, slots of deleted
+/// nodes are reused.
+pub struct SkipList<K, V> {
+    head: Vec<Link>,
+    nodes: Vec<Option<Node<K, V>>>,
+    free: Vec<usize>,
+    n: usize,
+    rng: XorShiftRng,
+}
+impl<K: Ord, V> Default for SkipList<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Ord, V> SkipList<K, V> {
+    /// with levels from an OS-seeded rng, see `with_seed` for tests
+    pub fn new() -> Self {
+        Self::with_rng(rand::weak_rng())
+    }
+    /// with reproducible levels
+    pub fn with_seed(seed: u32) -> Self {
+        // xorshift requires a non-zero seed
+        Self::with_rng(XorShiftRng::from_seed([seed, seed ^ 0x9e37_79b9, 1, 2]))
+    }
+    fn with_rng(rng: XorShiftRng) -> Self {
+        SkipList {
+            // the bottom level is always there
+            head: vec![Link {
+                next: None,
+                width: 1,
+            }],
+            nodes: Vec::new(),
+            free: Vec::new(),
+            n: 0,
+            rng,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.n
+    }
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    /// number of levels
+    pub fn levels(&self) -> usize {
+        self.head.len()
+    }
+
+    pub fn contains(&self, key: &K) -> bool {
+        self.get(key).is_some()
+    }
+    pub fn get(&self, key: &K) -> Option<&V> {
+        let x = self.ceiling_index(key)?;
+        let node = self.node(x);
+        if node.key == *key {
+            Some(&node.value)
+        } else {
+            None
+        }
+    }
+    pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        let x = self.ceiling_index(key)?;
+        let node = self.nodes[x].as_mut().unwrap();
+        if node.key == *key {
+            Some(&mut node.value)
+        } else {
+            None
+        }
+    }
+
+    pub fn min(&self) -> Option<&K> {
+        self.head[0].next.map(|x| &self.node(x).key)
+    }
+    pub fn max(&self) -> Option<&K> {
+        let (x, _) = self.descend(|_, _| true);
+        x.map(|x| &self.node(x).key)
+    }
+    pub fn floor(&self, key: &K) -> Option<&K> {
+        let (x, _) = self.descend(|k, _| k <= key);
+        x.map(|x| &self.node(x).key)
+    }
+    pub fn ceiling(&self, key: &K) -> Option<&K> {
+        self.ceiling_index(key).map(|x| &self.node(x).key)
+    }
+    /// number of keys less than key
+    pub fn rank(&self, key: &K) -> usize {
+        self.descend(|k, _| k < key).1
+    }
+    pub fn select(&self, i: usize) -> Option<&K> {
+        if i >= self.n {
+            return None;
+        }
+        let (x, _) = self.descend(|_, pos| pos <= i + 1);
+        x.map(|x| &self.node(x).key)
+    }
+
+    pub fn put(&mut self, key: K, value: V) {
+        let (mut update, mut pos) = self.predecessors(|k| *k < key);
+        if let Some(x) = self.link(update[0], 0).next {
+            let node = self.nodes[x].as_mut().unwrap();
+            if node.key == key {
+                node.value = value;
+                return;
+            }
+        }
+
+        let mut height = 1;
+        while height < MAX_LEVEL && self.rng.gen() {
+            height += 1;
+        }
+        while self.head.len() < height {
+            self.head.push(Link {
+                next: None,
+                width: self.n + 1,
+            });
+            update.push(None);
+            pos.push(0);
+        }
+
+        // split links passing over the new one, and widen the higher ones
+        let p = pos[0] + 1;
+        let x = match self.free.pop() {
+            Some(x) => x,
+            None => {
+                self.nodes.push(None);
+                self.nodes.len() - 1
+            }
+        };
+        let mut links = Vec::with_capacity(height);
+        for (level, (&prev, &prev_pos)) in update.iter().zip(pos.iter()).enumerate() {
+            let link = self.link_mut(prev, level);
+            if level < height {
+                links.push(Link {
+                    next: link.next,
+                    width: prev_pos + link.width + 1 - p,
+                });
+                link.next = Some(x);
+                link.width = p - prev_pos;
+            } else {
+                link.width += 1;
+            }
+        }
+        self.nodes[x] = Some(Node { key, value, links });
+        self.n += 1;
+    }
+    pub fn delete(&mut self, key: &K) {
+        self.remove(key);
+    }
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        let (update, _) = self.predecessors(|k| k < key);
+        match self.link(update[0], 0).next {
+            Some(x) if self.node(x).key == *key => Some(self.unlink(&update, x)),
+            _ => None,
+        }
+    }
+    pub fn delete_min(&mut self) {
+        if let Some(x) = self.head[0].next {
+            let update = vec![None; self.head.len()];
+            self.unlink(&update, x);
+        }
+    }
+    pub fn delete_max(&mut self) {
+        if let (Some(x), _) = self.descend(|_, _| true) {
+            let (update, _) = self.predecessors(|k| *k < self.node(x).key);
+            self.unlink(&update, x);
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        let mut x = self.head[0].next;
+        std::iter::from_fn(move || {
+            let node = self.node(x?);
+            x = node.links[0].next;
+            Some((&node.key, &node.value))
+        })
+    }
+
+    pub fn check(&self) -> bool {
+        self.check_error().is_none()
+    }
+    pub fn check_error(&self) -> Option<String> {
+        // positions by the bottom level
+        let mut positions = vec![0; self.nodes.len()];
+        let (mut x, mut count) = (self.head[0].next, 0);
+        let mut prev: Option<&K> = None;
+        while let Some(i) = x {
+            let node = self.node(i);
+            if prev.is_some_and(|k| *k >= node.key) {
+                return Some("Keys are not in order".into());
+            }
+            count += 1;
+            positions[i] = count;
+            prev = Some(&node.key);
+            x = node.links[0].next;
+        }
+        if count != self.n || self.nodes.iter().flatten().count() != self.n {
+            return Some("Size is not consistent".into());
+        }
+
+        for level in 0..self.head.len() {
+            let (mut x, mut pos) = (None, 0);
+            loop {
+                let link = self.link(x, level);
+                let next_pos = link.next.map_or(self.n + 1, |i| positions[i]);
+                if next_pos <= pos || link.width != next_pos - pos {
+                    return Some(format!("Widths are not consistent at level {}", level));
+                }
+                match link.next {
+                    None => break,
+                    Some(i) => {
+                        if self.node(i).links.len() <= level {
+                            return Some(format!("Node is not at level {}", level));
+                        }
+                        x = Some(i);
+                        pos = next_pos;
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    fn node(&self, x: usize) -> &Node<K, V> {
+        self.nodes[x].as_ref().unwrap()
+    }
+    // link of the node, or the head if None
+    fn link(&self, x: Option<usize>, level: usize) -> &Link {
+        match x {
+            None => &self.head[level],
+            Some(x) => &self.node(x).links[level],
+        }
+    }
+    fn link_mut(&mut self, x: Option<usize>, level: usize) -> &mut Link {
+        match x {
+            None => &mut self.head[level],
+            Some(x) => &mut self.nodes[x].as_mut().unwrap().links[level],
+        }
+    }
+
+    // go right from the top level down while `forward(key, position)` of the
+    // next node holds, returns the last node reached and its position
+    fn descend<F: Fn(&K, usize) -> bool>(&self, forward: F) -> (Option<usize>, usize) {
+        let (mut x, mut pos) = (None, 0);
+        for level in (0..self.head.len()).rev() {
+            loop {
+                let link = self.link(x, level);
+                match link.next {
+                    Some(i) if forward(&self.node(i).key, pos + link.width) => {
+                        x = Some(i);
+                        pos += link.width;
+                    }
+                    _ => break,
+                }
+            }
+        }
+        (x, pos)
+    }
+    // the last node of each level before keys not `before`, and its position
+    fn predecessors<F: Fn(&K) -> bool>(&self, before: F) -> (Vec<Option<usize>>, Vec<usize>) {
+        let levels = self.head.len();
+        let (mut update, mut positions) = (vec![None; levels], vec![0; levels]);
+        let (mut x, mut pos) = (None, 0);
+        for level in (0..levels).rev() {
+            loop {
+                let link = self.link(x, level);
+                match link.next {
+                    Some(i) if before(&self.node(i).key) => {
+                        x = Some(i);
+                        pos += link.width;
+                    }
+                    _ => break,
+                }
+            }
+            update[level] = x;
+            positions[level] = pos;
+        }
+        (update, positions)
+    }
+    fn ceiling_index(&self, key: &K) -> Option<usize> {
+        let (x, _) = self.descend(|k, _| k < key);
+        match x {
+            None => self.head[0].next,
+            Some(x) => self.node(x).links[0].next,
+        }
+    }
+    // remove node x with its predecessors of all levels
+    fn unlink(&mut self, update: &[Option<usize>], x: usize) -> V {
+        let node = self.nodes[x].take().unwrap();
+        for (level, &prev) in update.iter().enumerate() {
+            let link = self.link_mut(prev, level);
+            match node.links.get(level) {
+                Some(l) if link.next == Some(x) => {
+                    link.next = l.next;
+                    link.width += l.width - 1;
+                }
+                _ => link.width -= 1,
+            }
+        }
+        while self.head.len() > 1 && self.head.last().is_some_and(|l| l.next.is_none()) {
+            self.head.pop();
+        }
+        self.free.push(x);
+        self.n -= 1;
+        node.value
+    }
+}
+
+impl<K: Ord, V> SymbolTable<K, V> for SkipList<K, V> {
+    fn len(&self) -> usize {
+        SkipList::len(self)
+    }
+    fn get(&self, key: &K) -> Option<&V> {
+        SkipList::get(self, key)
+    }
+    fn put(&mut self, key: K, value: V) {
+        SkipList::put(self, key, value)
+    }
+    fn delete(&mut self, key: &K) {
+        SkipList::delete(self, key)
+    }
+    fn keys(&self) -> Box<dyn Iterator<Item = &K> + '_> {
+        Box::new(self.iter().map(|(k, _)| k))
+    }
+}
+impl<K: Ord, V> OrderedSymbolTable<K, V> for SkipList<K, V> {
+    fn min(&self) -> Option<&K> {
+        SkipList::min(self)
+    }
+    fn max(&self) -> Option<&K> {
+        SkipList::max(self)
+    }
+    fn floor(&self, key: &K) -> Option<&K> {
+        SkipList::floor(self, key)
+    }
+    fn ceiling(&self, key: &K) -> Option<&K> {
+        SkipList::ceiling(self, key)
+    }
+    fn rank(&self, key: &K) -> usize {
+        SkipList::rank(self, key)
+    }
+    fn select(&self, i: usize) -> Option<&K> {
+        SkipList::select(self, i)
+    }
+    fn delete_min(&mut self) {
+        SkipList::delete_min(self)
+    }
+    fn delete_max(&mut self) {
+        SkipList::delete_max(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{Rng, SeedableRng, StdRng};
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn empty() {
+        let mut st = SkipList::<u32, u32>::new();
+        assert_eq!(None, st.min());
+        assert_eq!(None, st.max());
+        assert_eq!(None, st.floor(&1));
+        assert_eq!(None, st.ceiling(&1));
+        assert_eq!(None, st.select(0));
+        assert_eq!(0, st.rank(&1));
+        assert_eq!(None, st.remove(&1));
+        st.delete_min();
+        st.delete_max();
+        assert_eq!(None, st.check_error());
+    }
+
+    #[test]
+    fn reuse_slots() {
+        let mut st = SkipList::with_seed(46);
+        for i in 0..10 {
+            st.put(i, i);
+        }
+        st.delete(&3);
+        st.delete_min();
+        st.put(10, 10);
+        st.put(11, 11);
+        assert_eq!(10, st.nodes.len());
+        st.put(12, 12);
+        assert_eq!(11, st.nodes.len());
+        assert_eq!(None, st.check_error());
+    }
+
+    #[test]
+    fn sorted_inputs() {
+        let n = 1 << 12;
+        let mut st = SkipList::with_seed(7);
+        for i in 0..n {
+            st.put(i, i);
+        }
+        assert_eq!(None, st.check_error());
+        assert!(
+            st.levels() >= 8 && st.levels() <= 24,
+            "{} levels",
+            st.levels()
+        );
+        assert_eq!(Some(&1000), st.select(1000));
+
+        for i in (0..n).step_by(2) {
+            st.delete(&i);
+        }
+        assert_eq!(None, st.check_error());
+        assert_eq!(n / 2, st.len());
+        assert_eq!(Some(&2001), st.select(1000));
+    }
+
+    #[test]
+    fn random() {
+        let mut rng = StdRng::from_seed(&[46][..]);
+        let mut st = SkipList::with_seed(46);
+        let mut map = BTreeMap::new();
+        for i in 0..2000 {
+            let k: u32 = rng.gen_range(0, 500);
+            match i % 7 {
+                0 | 1 => assert_eq!(map.remove(&k), st.remove(&k)),
+                2 => {
+                    map.pop_last();
+                    st.delete_max();
+                }
+                3 => {
+                    map.pop_first();
+                    st.delete_min();
+                }
+                _ => {
+                    st.put(k, i);
+                    map.insert(k, i);
+                }
+            }
+            if i % 50 == 0 {
+                assert_eq!(None, st.check_error());
+            }
+        }
+        assert_eq!(None, st.check_error());
+        assert!(map.iter().eq(st.iter()));
+    }
+}
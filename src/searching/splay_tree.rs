@@ -0,0 +1,461 @@
+use super::{OrderedSymbolTable, SymbolTable};
+
+use std::cmp::Ordering::{self, Equal, Greater, Less};
+
+type Tree<K, V> = Option<Box<Node<K, V>>>;
+struct Node<K, V> {
+    key: K,
+    value: V,
+    left: Tree<K, V>,
+    right: Tree<K, V>,
+    size: usize,
+}
+impl<K, V> Node<K, V> {
+    fn new(key: K, value: V) -> Self {
+        Node {
+            key,
+            value,
+            left: None,
+            right: None,
+            size: 1,
+        }
+    }
+    fn update(&mut self) {
+        self.size = 1 + size(&self.left) + size(&self.right);
+    }
+}
+
+/// Splay Tree
+///
+/// Every put/delete/get_mut moves the key to the root by rotations, which
+/// roughly halves depths of nodes on the path, so these are O(logN)
+/// amortized, and recently accessed keys are cheap to access again.
+///
+/// Reads through `&self` (`get`, `floor`, `rank`, ...) don't splay, so they
+/// cost the current depth of the key, which is up to N (e.g. after sorted
+/// puts), use `get_mut` for a splaying lookup. As the tree may be that deep,
+/// all operations are iterative.
+#[derive(Default)]
+pub struct SplayTree<K, V> {
+    root: Tree<K, V>,
+}
+
+// implement drop to avoid stack overflow on deep paths
+impl<K, V> Drop for SplayTree<K, V> {
+    fn drop(&mut self) {
+        let mut stack: Vec<Box<Node<K, V>>> = self.root.take().into_iter().collect();
+        while let Some(mut n) = stack.pop() {
+            stack.extend(n.left.take());
+            stack.extend(n.right.take());
+        }
+    }
+}
+
+impl<K: Ord, V> SplayTree<K, V> {
+    pub fn new() -> Self {
+        SplayTree { root: None }
+    }
+
+    pub fn len(&self) -> usize {
+        size(&self.root)
+    }
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    pub fn root(&self) -> Option<&K> {
+        self.root.as_ref().map(|n| &n.key)
+    }
+
+    pub fn contains(&self, key: &K) -> bool {
+        self.get(key).is_some()
+    }
+    pub fn get(&self, key: &K) -> Option<&V> {
+        let mut x = self.root.as_ref();
+        while let Some(n) = x {
+            match key.cmp(&n.key) {
+                Equal => return Some(&n.value),
+                Less => x = n.left.as_ref(),
+                Greater => x = n.right.as_ref(),
+            }
+        }
+        None
+    }
+    /// splays the key to the root
+    pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        let root = splay(self.root.take()?, |k| key.cmp(k));
+        let root = self.root.insert(root);
+        if root.key == *key {
+            Some(&mut root.value)
+        } else {
+            None
+        }
+    }
+
+    pub fn min(&self) -> Option<&K> {
+        let mut x = self.root.as_ref()?;
+        while let Some(n) = x.left.as_ref() {
+            x = n;
+        }
+        Some(&x.key)
+    }
+    pub fn max(&self) -> Option<&K> {
+        let mut x = self.root.as_ref()?;
+        while let Some(n) = x.right.as_ref() {
+            x = n;
+        }
+        Some(&x.key)
+    }
+    pub fn floor(&self, key: &K) -> Option<&K> {
+        let (mut x, mut floor) = (self.root.as_ref(), None);
+        while let Some(n) = x {
+            match key.cmp(&n.key) {
+                Equal => return Some(&n.key),
+                Less => x = n.left.as_ref(),
+                Greater => {
+                    floor = Some(&n.key);
+                    x = n.right.as_ref();
+                }
+            }
+        }
+        floor
+    }
+    pub fn ceiling(&self, key: &K) -> Option<&K> {
+        let (mut x, mut ceiling) = (self.root.as_ref(), None);
+        while let Some(n) = x {
+            match key.cmp(&n.key) {
+                Equal => return Some(&n.key),
+                Less => {
+                    ceiling = Some(&n.key);
+                    x = n.left.as_ref();
+                }
+                Greater => x = n.right.as_ref(),
+            }
+        }
+        ceiling
+    }
+    /// number of keys less than key
+    pub fn rank(&self, key: &K) -> usize {
+        let (mut x, mut rank) = (self.root.as_ref(), 0);
+        while let Some(n) = x {
+            match key.cmp(&n.key) {
+                Equal => return rank + size(&n.left),
+                Less => x = n.left.as_ref(),
+                Greater => {
+                    rank += size(&n.left) + 1;
+                    x = n.right.as_ref();
+                }
+            }
+        }
+        rank
+    }
+    pub fn select(&self, mut i: usize) -> Option<&K> {
+        let mut x = self.root.as_ref();
+        while let Some(n) = x {
+            let ls = size(&n.left);
+            match i.cmp(&ls) {
+                Equal => return Some(&n.key),
+                Less => x = n.left.as_ref(),
+                Greater => {
+                    i -= ls + 1;
+                    x = n.right.as_ref();
+                }
+            }
+        }
+        None
+    }
+
+    pub fn put(&mut self, key: K, value: V) {
+        let mut root = match self.root.take() {
+            None => {
+                self.root = Some(Box::new(Node::new(key, value)));
+                return;
+            }
+            Some(root) => splay(root, |k| key.cmp(k)),
+        };
+
+        // the new node takes place of the root, which is its neighbour
+        let mut n = Box::new(Node::new(key, value));
+        match n.key.cmp(&root.key) {
+            Equal => {
+                root.value = n.value;
+                self.root = Some(root);
+                return;
+            }
+            Less => {
+                n.left = root.left.take();
+                root.update();
+                n.right = Some(root);
+            }
+            Greater => {
+                n.right = root.right.take();
+                root.update();
+                n.left = Some(root);
+            }
+        }
+        n.update();
+        self.root = Some(n);
+    }
+    pub fn delete(&mut self, key: &K) {
+        self.remove(key);
+    }
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        let mut root = splay(self.root.take()?, |k| key.cmp(k));
+        if root.key != *key {
+            self.root = Some(root);
+            return None;
+        }
+
+        // max of the left sub-tree has no right child after splayed
+        self.root = match (root.left.take(), root.right.take()) {
+            (None, right) => right,
+            (Some(left), right) => {
+                let mut max = splay(left, |_| Greater);
+                max.right = right;
+                max.update();
+                Some(max)
+            }
+        };
+        Some(root.value)
+    }
+    pub fn delete_min(&mut self) {
+        if let Some(root) = self.root.take() {
+            self.root = splay(root, |_| Less).right.take();
+        }
+    }
+    pub fn delete_max(&mut self) {
+        if let Some(root) = self.root.take() {
+            self.root = splay(root, |_| Greater).left.take();
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        let mut stack = Vec::new();
+        let mut x = self.root.as_deref();
+        std::iter::from_fn(move || {
+            while let Some(n) = x {
+                stack.push(n);
+                x = n.left.as_deref();
+            }
+            let n = stack.pop()?;
+            x = n.right.as_deref();
+            Some((&n.key, &n.value))
+        })
+    }
+
+    pub fn check(&self) -> bool {
+        self.check_error().is_none()
+    }
+    pub fn check_error(&self) -> Option<String> {
+        // (node, min, max) as bounds of keys
+        let mut stack = Vec::new();
+        stack.extend(self.root.as_deref().map(|n| (n, None::<&K>, None::<&K>)));
+        while let Some((n, min, max)) = stack.pop() {
+            if min.is_some_and(|k| n.key <= *k) || max.is_some_and(|k| n.key >= *k) {
+                return Some("Not a BST".into());
+            }
+            if n.size != 1 + size(&n.left) + size(&n.right) {
+                return Some("Sizes are not consistent".into());
+            }
+            stack.extend(n.left.as_deref().map(|l| (l, min, Some(&n.key))));
+            stack.extend(n.right.as_deref().map(|r| (r, Some(&n.key), max)));
+        }
+        None
+    }
+}
+
+fn size<K, V>(t: &Tree<K, V>) -> usize {
+    t.as_ref().map_or(0, |n| n.size)
+}
+
+fn rotate_left<K, V>(mut n: Box<Node<K, V>>) -> Box<Node<K, V>> {
+    let mut x = n.right.take().unwrap();
+    n.right = x.left.take();
+    n.update();
+    x.left = Some(n);
+    x.update();
+    x
+}
+fn rotate_right<K, V>(mut n: Box<Node<K, V>>) -> Box<Node<K, V>> {
+    let mut x = n.left.take().unwrap();
+    n.left = x.right.take();
+    n.update();
+    x.right = Some(n);
+    x.update();
+    x
+}
+
+// Top-down splay: the target (`cmp` tells where it is to a key), or the last
+// node on the search path, becomes the root.
+//
+// Nodes passed by are split into two lists: less than the target, each one
+// is the right child of the previous, and greater than the target, each one
+// is the left child of the previous. Two lists are linked up at the end, so
+// no recursion nor parent pointer is needed.
+fn splay<K, V, F: Fn(&K) -> Ordering>(mut t: Box<Node<K, V>>, cmp: F) -> Box<Node<K, V>> {
+    let (mut less, mut greater) = (Vec::new(), Vec::new());
+    loop {
+        match cmp(&t.key) {
+            Equal => break,
+            Less => {
+                match t.left.as_ref() {
+                    None => break,
+                    // zig-zig
+                    Some(l) if cmp(&l.key) == Less => {
+                        t = rotate_right(t);
+                        if t.left.is_none() {
+                            break;
+                        }
+                    }
+                    _ => {}
+                }
+                let left = t.left.take().unwrap();
+                greater.push(t);
+                t = left;
+            }
+            Greater => {
+                match t.right.as_ref() {
+                    None => break,
+                    Some(r) if cmp(&r.key) == Greater => {
+                        t = rotate_left(t);
+                        if t.right.is_none() {
+                            break;
+                        }
+                    }
+                    _ => {}
+                }
+                let right = t.right.take().unwrap();
+                less.push(t);
+                t = right;
+            }
+        }
+    }
+
+    let mut left = t.left.take();
+    for mut n in less.into_iter().rev() {
+        n.right = left;
+        n.update();
+        left = Some(n);
+    }
+    let mut right = t.right.take();
+    for mut n in greater.into_iter().rev() {
+        n.left = right;
+        n.update();
+        right = Some(n);
+    }
+    t.left = left;
+    t.right = right;
+    t.update();
+    t
+}
+
+impl<K: Ord, V> SymbolTable<K, V> for SplayTree<K, V> {
+    fn len(&self) -> usize {
+        SplayTree::len(self)
+    }
+    fn get(&self, key: &K) -> Option<&V> {
+        SplayTree::get(self, key)
+    }
+    fn put(&mut self, key: K, value: V) {
+        SplayTree::put(self, key, value)
+    }
+    fn delete(&mut self, key: &K) {
+        SplayTree::delete(self, key)
+    }
+    fn keys(&self) -> Box<dyn Iterator<Item = &K> + '_> {
+        Box::new(self.iter().map(|(k, _)| k))
+    }
+}
+impl<K: Ord, V> OrderedSymbolTable<K, V> for SplayTree<K, V> {
+    fn min(&self) -> Option<&K> {
+        SplayTree::min(self)
+    }
+    fn max(&self) -> Option<&K> {
+        SplayTree::max(self)
+    }
+    fn floor(&self, key: &K) -> Option<&K> {
+        SplayTree::floor(self, key)
+    }
+    fn ceiling(&self, key: &K) -> Option<&K> {
+        SplayTree::ceiling(self, key)
+    }
+    fn rank(&self, key: &K) -> usize {
+        SplayTree::rank(self, key)
+    }
+    fn select(&self, i: usize) -> Option<&K> {
+        SplayTree::select(self, i)
+    }
+    fn delete_min(&mut self) {
+        SplayTree::delete_min(self)
+    }
+    fn delete_max(&mut self) {
+        SplayTree::delete_max(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{Rng, SeedableRng, StdRng};
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn splay() {
+        let mut st = SplayTree::new();
+        for i in 0..100 {
+            st.put(i, i);
+        }
+        // a path after sorted insertions, `get` doesn't change it
+        assert_eq!(Some(&99), st.root());
+        assert_eq!(Some(&0), st.get(&0));
+        assert_eq!(Some(&99), st.root());
+
+        *st.get_mut(&0).unwrap() = 100;
+        assert_eq!(Some(&0), st.root());
+        assert_eq!(Some(&100), st.get(&0));
+
+        // the last one on the search path if not found
+        assert_eq!(None, st.get_mut(&200));
+        assert_eq!(Some(&99), st.root());
+        assert_eq!(None, st.check_error());
+    }
+
+    #[test]
+    fn deep_path() {
+        let n = 100_000;
+        let mut st = SplayTree::new();
+        for i in 0..n {
+            st.put(i, ());
+        }
+        assert_eq!(Some(&0), st.min());
+        assert_eq!(None, st.check_error());
+        assert_eq!(n, st.iter().count());
+
+        // splaying the deepest one halves the depth
+        st.get_mut(&0);
+        st.delete(&1);
+        st.delete_max();
+        assert_eq!(n - 2, st.len());
+        assert_eq!(None, st.check_error());
+    }
+
+    #[test]
+    fn random() {
+        let mut rng = StdRng::from_seed(&[46][..]);
+        let mut st = SplayTree::new();
+        let mut map = BTreeMap::new();
+        for i in 0..2000 {
+            let k: u32 = rng.gen_range(0, 500);
+            match i % 4 {
+                0 => assert_eq!(map.remove(&k), st.remove(&k)),
+                1 => assert_eq!(map.get_mut(&k), st.get_mut(&k)),
+                _ => {
+                    st.put(k, i);
+                    map.insert(k, i);
+                    assert_eq!(Some(&k), st.root());
+                }
+            }
+        }
+        assert_eq!(None, st.check_error());
+        assert!(map.iter().eq(st.iter()));
+    }
+}
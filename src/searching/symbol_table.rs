@@ -0,0 +1,425 @@
+use std::marker::PhantomData;
+
+/// SymbolTable
+///
+/// Key-value table, implemented by all symbol tables in `searching`, so code
+/// can be written generic over the backend.
+pub trait SymbolTable<K, V> {
+    fn len(&self) -> usize;
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn get(&self, key: &K) -> Option<&V>;
+    /// insert or update
+    fn put(&mut self, key: K, value: V);
+    /// no-op if the key is not in the table
+    fn delete(&mut self, key: &K);
+
+    fn contains(&self, key: &K) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// in order for ordered tables, in any order otherwise
+    fn keys(&self) -> Box<dyn Iterator<Item = &K> + '_>;
+}
+
+/// OrderedSymbolTable
+///
+/// Symbol table with ordered keys, `rank(key)` is the number of keys less than
+/// `key`, and `select(rank)` is the inverse.
+pub trait OrderedSymbolTable<K: Ord, V>: SymbolTable<K, V> {
+    fn min(&self) -> Option<&K>;
+    fn max(&self) -> Option<&K>;
+    /// the largest key <= key
+    fn floor(&self, key: &K) -> Option<&K>;
+    /// the smallest key >= key
+    fn ceiling(&self, key: &K) -> Option<&K>;
+    fn rank(&self, key: &K) -> usize;
+    fn select(&self, i: usize) -> Option<&K>;
+
+    fn delete_min(&mut self);
+    fn delete_max(&mut self);
+
+    /// keys in [lo, hi], in order
+    fn keys_range<'a>(&'a self, lo: &'a K, hi: &'a K) -> Box<dyn Iterator<Item = &'a K> + 'a> {
+        Box::new(
+            self.keys()
+                .skip_while(move |k| *k < lo)
+                .take_while(move |k| *k <= hi),
+        )
+    }
+    /// number of keys in [lo, hi]
+    fn size_range(&self, lo: &K, hi: &K) -> usize {
+        if hi < lo {
+            0
+        } else if self.contains(hi) {
+            self.rank(hi) + 1 - self.rank(lo)
+        } else {
+            self.rank(hi) - self.rank(lo)
+        }
+    }
+}
+
+/// SymbolTableMut
+///
+/// Mutable access to values of an ordered symbol table, and the `entry` API
+/// like `std::collections::BTreeMap`.
+pub trait SymbolTableMut<K: Ord, V>: OrderedSymbolTable<K, V> {
+    fn get_mut(&mut self, key: &K) -> Option<&mut V>;
+    /// the i-th smallest key and its value
+    fn select_mut(&mut self, i: usize) -> Option<(&K, &mut V)>;
+    /// delete the key, returns its value
+    fn remove(&mut self, key: &K) -> Option<V>;
+    /// `Ok(rank)` if the key is in the table, `Err(rank)` otherwise, like
+    /// `slice::binary_search`, tables override it with a single search
+    fn search(&self, key: &K) -> Result<usize, usize> {
+        let rank = self.rank(key);
+        if self.contains(key) {
+            Ok(rank)
+        } else {
+            Err(rank)
+        }
+    }
+
+    fn entry(&mut self, key: K) -> Entry<'_, K, V, Self>
+    where
+        Self: Sized,
+    {
+        match self.search(&key) {
+            Ok(rank) => Entry::Occupied(OccupiedEntry {
+                table: self,
+                key,
+                rank,
+                _value: PhantomData,
+            }),
+            Err(rank) => Entry::Vacant(VacantEntry {
+                table: self,
+                key,
+                rank,
+                _value: PhantomData,
+            }),
+        }
+    }
+}
+
+/// A view into a single entry of a table, `T` is the table
+pub enum Entry<'a, K, V, T> {
+    Occupied(OccupiedEntry<'a, K, V, T>),
+    Vacant(VacantEntry<'a, K, V, T>),
+}
+
+/// An existing key, its value is located by `select_mut(rank)`, so an entry
+/// costs two searches, O(logN) each for trees
+pub struct OccupiedEntry<'a, K, V, T> {
+    table: &'a mut T,
+    key: K,
+    rank: usize,
+    _value: PhantomData<V>,
+}
+
+pub struct VacantEntry<'a, K, V, T> {
+    table: &'a mut T,
+    key: K,
+    // rank of the key after insertion
+    rank: usize,
+    _value: PhantomData<V>,
+}
+
+impl<'a, K: Ord + 'a, V: 'a, T: SymbolTableMut<K, V>> Entry<'a, K, V, T> {
+    pub fn key(&self) -> &K {
+        match self {
+            Entry::Occupied(e) => &e.key,
+            Entry::Vacant(e) => &e.key,
+        }
+    }
+
+    pub fn or_insert(self, default: V) -> &'a mut V {
+        self.or_insert_with(|| default)
+    }
+    pub fn or_insert_with<F: FnOnce() -> V>(self, default: F) -> &'a mut V {
+        match self {
+            Entry::Occupied(e) => e.into_mut(),
+            Entry::Vacant(e) => e.insert(default()),
+        }
+    }
+    pub fn or_default(self) -> &'a mut V
+    where
+        V: Default,
+    {
+        self.or_insert_with(V::default)
+    }
+
+    pub fn and_modify<F: FnOnce(&mut V)>(self, f: F) -> Self {
+        match self {
+            Entry::Occupied(mut e) => {
+                f(e.get_mut());
+                Entry::Occupied(e)
+            }
+            e => e,
+        }
+    }
+}
+
+impl<'a, K: Ord + 'a, V: 'a, T: SymbolTableMut<K, V>> OccupiedEntry<'a, K, V, T> {
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+    pub fn get(&self) -> &V {
+        self.table.get(&self.key).unwrap()
+    }
+    pub fn get_mut(&mut self) -> &mut V {
+        self.table.select_mut(self.rank).unwrap().1
+    }
+    pub fn into_mut(self) -> &'a mut V {
+        self.table.select_mut(self.rank).unwrap().1
+    }
+    /// replace the value, returns the old one
+    pub fn insert(&mut self, value: V) -> V {
+        std::mem::replace(self.get_mut(), value)
+    }
+    pub fn remove(self) -> V {
+        self.table.remove(&self.key).unwrap()
+    }
+}
+
+impl<'a, K: Ord + 'a, V: 'a, T: SymbolTableMut<K, V>> VacantEntry<'a, K, V, T> {
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+    pub fn into_key(self) -> K {
+        self.key
+    }
+    pub fn insert(self, value: V) -> &'a mut V {
+        self.table.put(self.key, value);
+        self.table.select_mut(self.rank).unwrap().1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::*;
+    use super::*;
+    use rand::{Rng, SeedableRng, StdRng};
+    use std::collections::BTreeMap;
+
+    // random operations against `BTreeMap`
+    fn conformance<T: SymbolTable<u32, u32>>(mut st: T, seed: usize) -> (T, BTreeMap<u32, u32>) {
+        assert!(st.is_empty());
+        assert_eq!(None, st.get(&0));
+        st.delete(&0);
+        assert_eq!(0, st.keys().count());
+
+        let mut rng = StdRng::from_seed(&[seed][..]);
+        let mut map = BTreeMap::new();
+        for i in 0..2000 {
+            let k = rng.gen_range(0, 200);
+            if rng.gen_range(0, 3) == 0 {
+                st.delete(&k);
+                map.remove(&k);
+            } else {
+                st.put(k, i);
+                map.insert(k, i);
+            }
+            assert_eq!(map.len(), st.len());
+            assert_eq!(map.get(&k), st.get(&k));
+            assert_eq!(map.contains_key(&k), st.contains(&k));
+        }
+        for k in 0..200 {
+            assert_eq!(map.get(&k), st.get(&k));
+        }
+        let mut keys = st.keys().cloned().collect::<Vec<_>>();
+        keys.sort_unstable();
+        assert_eq!(map.keys().cloned().collect::<Vec<_>>(), keys);
+
+        (st, map)
+    }
+
+    fn ordered_conformance<T: OrderedSymbolTable<u32, u32>>(st: T, seed: usize) {
+        assert_eq!(None, st.min());
+        assert_eq!(None, st.floor(&0));
+        assert_eq!(None, st.ceiling(&0));
+        assert_eq!(None, st.select(0));
+        assert_eq!(0, st.rank(&0));
+        assert_eq!(0, st.size_range(&0, &10));
+
+        let (mut st, mut map) = conformance(st, seed);
+        assert!(st.keys().zip(map.keys()).all(|(a, b)| a == b));
+        assert_eq!(map.keys().next(), st.min());
+        assert_eq!(map.keys().next_back(), st.max());
+        for (i, k) in map.keys().enumerate() {
+            assert_eq!(Some(k), st.select(i));
+            assert_eq!(i, st.rank(k));
+        }
+        assert_eq!(None, st.select(map.len()));
+
+        // queries on both existing and missing keys, and out of all keys
+        for k in 0..=200 {
+            assert_eq!(map.range(..=k).next_back().map(|e| e.0), st.floor(&k));
+            assert_eq!(map.range(k..).next().map(|e| e.0), st.ceiling(&k));
+            assert_eq!(map.range(..k).count(), st.rank(&k));
+
+            let hi = k + 17;
+            let expected = map.range(k..=hi).map(|e| e.0).collect::<Vec<_>>();
+            assert_eq!(expected, st.keys_range(&k, &hi).collect::<Vec<_>>());
+            assert_eq!(expected.len(), st.size_range(&k, &hi));
+        }
+        assert_eq!(0, st.size_range(&10, &9));
+
+        while !map.is_empty() {
+            let (&min, &max) = (map.keys().next().unwrap(), map.keys().next_back().unwrap());
+            st.delete_min();
+            map.remove(&min);
+            if !map.is_empty() {
+                st.delete_max();
+                map.remove(&max);
+            }
+            assert_eq!(map.len(), st.len());
+            assert_eq!(map.keys().next(), st.min());
+            assert_eq!(map.keys().next_back(), st.max());
+        }
+        st.delete_min();
+        st.delete_max();
+        assert!(st.is_empty());
+    }
+
+    #[test]
+    fn binary_search_st() {
+        ordered_conformance(BinarySearchST::new(), 1);
+    }
+
+    #[test]
+    fn bstree() {
+        ordered_conformance(BSTree::new(), 2);
+    }
+
+    #[test]
+    fn rbtree() {
+        ordered_conformance(RBTree::new(), 3);
+    }
+    #[test]
+    fn persistent_rbtree() {
+        ordered_conformance(PersistentRBTree::new(), 9);
+    }
+
+    #[test]
+    fn balanced_trees() {
+        ordered_conformance(AVLTree::new(), 10);
+        ordered_conformance(Treap::with_seed(11), 11);
+        ordered_conformance(SplayTree::new(), 12);
+        ordered_conformance(SkipList::with_seed(13), 13);
+    }
+
+    #[test]
+    fn btree() {
+        ordered_conformance(BTree::with_order(3), 14);
+        ordered_conformance(BTree::with_order(4), 15);
+        ordered_conformance(BTree::new(), 16);
+    }
+
+    #[test]
+    fn linear_probing_hash_st() {
+        conformance(LinearProbingHashST::new(1), 4);
+        conformance(LinearProbingHashST::new(64), 5);
+    }
+
+    #[test]
+    fn hash_tables() {
+        conformance(SeparateChainingHashST::new(1), 17);
+        conformance(RobinHoodHashST::new(1), 18);
+        conformance(CuckooHashST::new(1), 19);
+        let mut st = LinearProbingHashST::new(1);
+        st.set_max_load_factor(0.9);
+        conformance(st, 20);
+    }
+
+    fn mut_conformance<T: SymbolTableMut<u32, u32>>(mut st: T, seed: usize) {
+        assert_eq!(None, st.get_mut(&0));
+        assert_eq!(None, st.select_mut(0));
+        assert_eq!(None, st.remove(&0));
+
+        let mut rng = StdRng::from_seed(&[seed][..]);
+        let mut map = BTreeMap::new();
+        for _ in 0..2000 {
+            let k = rng.gen_range(0, 100);
+            match rng.gen_range(0, 4) {
+                0 => assert_eq!(map.remove(&k), st.remove(&k)),
+                1 => {
+                    if let Some(v) = st.get_mut(&k) {
+                        *v += 1;
+                    }
+                    if let Some(v) = map.get_mut(&k) {
+                        *v += 1;
+                    }
+                }
+                2 => {
+                    *st.entry(k).and_modify(|v| *v *= 2).or_insert(k) += 1;
+                    *map.entry(k).and_modify(|v| *v *= 2).or_insert(k) += 1;
+                }
+                _ => {
+                    *st.entry(k).or_default() += 3;
+                    *map.entry(k).or_default() += 3;
+                }
+            }
+            assert_eq!(map.len(), st.len());
+            assert_eq!(map.get(&k), st.get(&k));
+            let rank = map.range(..k).count();
+            let found = if map.contains_key(&k) {
+                Ok(rank)
+            } else {
+                Err(rank)
+            };
+            assert_eq!(found, st.search(&k));
+        }
+        for (i, (k, v)) in map.iter_mut().enumerate() {
+            assert_eq!(Some((k, &mut *v)), st.select_mut(i));
+        }
+        assert!(st.keys().zip(map.keys()).all(|(a, b)| a == b));
+    }
+
+    #[test]
+    fn symbol_table_mut() {
+        mut_conformance(BinarySearchST::new(), 6);
+        mut_conformance(BSTree::new(), 7);
+        mut_conformance(RBTree::new(), 8);
+    }
+
+    #[test]
+    fn entry() {
+        let mut st = RBTree::<String, usize>::new();
+        match SymbolTableMut::entry(&mut st, "a".into()) {
+            Entry::Vacant(e) => {
+                assert_eq!("a", e.key());
+                assert_eq!(1, *e.insert(1));
+            }
+            Entry::Occupied(_) => panic!("vacant expected"),
+        }
+        match SymbolTableMut::entry(&mut st, "a".into()) {
+            Entry::Occupied(mut e) => {
+                assert_eq!(&1, e.get());
+                assert_eq!(1, e.insert(5));
+                assert_eq!(5, e.remove());
+            }
+            Entry::Vacant(_) => panic!("occupied expected"),
+        }
+        assert!(st.is_empty());
+    }
+
+    fn count_words<T: SymbolTable<String, usize>>(mut st: T) -> T {
+        for w in "it was the best of times it was the worst of times".split(' ') {
+            let n = st.get(&w.to_string()).cloned().unwrap_or(0);
+            st.put(w.to_string(), n + 1);
+        }
+        st
+    }
+
+    #[test]
+    fn generic() {
+        let st = count_words(RBTree::new());
+        assert_eq!(Some(&2), st.get(&"times".into()));
+        assert_eq!(Some(&"best".into()), OrderedSymbolTable::min(&st));
+        let st = count_words(LinearProbingHashST::new(4));
+        assert_eq!(7, SymbolTable::len(&st));
+        assert_eq!(Some(&1), st.get(&"worst".into()));
+    }
+}
@@ -0,0 +1,460 @@
+use super::{OrderedSymbolTable, SymbolTable};
+
+use rand::{Rng, SeedableRng, XorShiftRng};
+use std::cmp::Ordering::{Equal, Greater, Less};
+
+type Tree<K, V> = Option<Box<Node<K, V>>>;
+struct Node<K, V> {
+    key: K,
+    value: V,
+    priority: u32,
+    left: Tree<K, V>,
+    right: Tree<K, V>,
+    size: usize,
+}
+impl<K, V> Node<K, V> {
+    fn new(key: K, value: V, priority: u32) -> Self {
+        Node {
+            key,
+            value,
+            priority,
+            left: None,
+            right: None,
+            size: 1,
+        }
+    }
+    fn update(&mut self) {
+        self.size = 1 + size(&self.left) + size(&self.right);
+    }
+}
+
+/// Randomized Treap
+///
+/// A BST by keys and a max-heap by random priorities at the same time, so
+/// the shape is a BST built by inserting keys in random order, whatever the
+/// order they really come in, O(logN) expected for all operations.
+pub struct Treap<K, V> {
+    root: Tree<K, V>,
+    rng: XorShiftRng,
+}
+impl<K: Ord, V> Default for Treap<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Ord, V> Treap<K, V> {
+    /// with priorities from an OS-seeded rng, see `with_seed` for tests
+    pub fn new() -> Self {
+        Treap {
+            root: None,
+            rng: rand::weak_rng(),
+        }
+    }
+    /// with reproducible priorities
+    pub fn with_seed(seed: u32) -> Self {
+        Treap {
+            root: None,
+            // xorshift requires a non-zero seed
+            rng: XorShiftRng::from_seed([seed, seed ^ 0x9e37_79b9, 1, 2]),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        size(&self.root)
+    }
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    pub fn height(&self) -> usize {
+        height(&self.root)
+    }
+
+    pub fn contains(&self, key: &K) -> bool {
+        self.get(key).is_some()
+    }
+    pub fn get(&self, key: &K) -> Option<&V> {
+        let mut x = self.root.as_ref();
+        while let Some(n) = x {
+            match key.cmp(&n.key) {
+                Equal => return Some(&n.value),
+                Less => x = n.left.as_ref(),
+                Greater => x = n.right.as_ref(),
+            }
+        }
+        None
+    }
+    pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        let mut x = self.root.as_mut();
+        while let Some(n) = x {
+            match key.cmp(&n.key) {
+                Equal => return Some(&mut n.value),
+                Less => x = n.left.as_mut(),
+                Greater => x = n.right.as_mut(),
+            }
+        }
+        None
+    }
+
+    pub fn min(&self) -> Option<&K> {
+        let mut x = self.root.as_ref()?;
+        while let Some(n) = x.left.as_ref() {
+            x = n;
+        }
+        Some(&x.key)
+    }
+    pub fn max(&self) -> Option<&K> {
+        let mut x = self.root.as_ref()?;
+        while let Some(n) = x.right.as_ref() {
+            x = n;
+        }
+        Some(&x.key)
+    }
+    pub fn floor(&self, key: &K) -> Option<&K> {
+        let (mut x, mut floor) = (self.root.as_ref(), None);
+        while let Some(n) = x {
+            match key.cmp(&n.key) {
+                Equal => return Some(&n.key),
+                Less => x = n.left.as_ref(),
+                Greater => {
+                    floor = Some(&n.key);
+                    x = n.right.as_ref();
+                }
+            }
+        }
+        floor
+    }
+    pub fn ceiling(&self, key: &K) -> Option<&K> {
+        let (mut x, mut ceiling) = (self.root.as_ref(), None);
+        while let Some(n) = x {
+            match key.cmp(&n.key) {
+                Equal => return Some(&n.key),
+                Less => {
+                    ceiling = Some(&n.key);
+                    x = n.left.as_ref();
+                }
+                Greater => x = n.right.as_ref(),
+            }
+        }
+        ceiling
+    }
+    /// number of keys less than key
+    pub fn rank(&self, key: &K) -> usize {
+        let (mut x, mut rank) = (self.root.as_ref(), 0);
+        while let Some(n) = x {
+            match key.cmp(&n.key) {
+                Equal => return rank + size(&n.left),
+                Less => x = n.left.as_ref(),
+                Greater => {
+                    rank += size(&n.left) + 1;
+                    x = n.right.as_ref();
+                }
+            }
+        }
+        rank
+    }
+    pub fn select(&self, mut i: usize) -> Option<&K> {
+        let mut x = self.root.as_ref();
+        while let Some(n) = x {
+            let ls = size(&n.left);
+            match i.cmp(&ls) {
+                Equal => return Some(&n.key),
+                Less => x = n.left.as_ref(),
+                Greater => {
+                    i -= ls + 1;
+                    x = n.right.as_ref();
+                }
+            }
+        }
+        None
+    }
+
+    pub fn put(&mut self, key: K, value: V) {
+        let priority = self.rng.gen();
+        self.root = Some(put(self.root.take(), key, value, priority));
+    }
+    pub fn delete(&mut self, key: &K) {
+        self.remove(key);
+    }
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        let (root, value) = remove(self.root.take(), key);
+        self.root = root;
+        value
+    }
+    pub fn delete_min(&mut self) {
+        if let Some(root) = self.root.take() {
+            self.root = delete_min(root);
+        }
+    }
+    pub fn delete_max(&mut self) {
+        if let Some(root) = self.root.take() {
+            self.root = delete_max(root);
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        let mut stack = Vec::new();
+        let mut x = self.root.as_deref();
+        std::iter::from_fn(move || {
+            while let Some(n) = x {
+                stack.push(n);
+                x = n.left.as_deref();
+            }
+            let n = stack.pop()?;
+            x = n.right.as_deref();
+            Some((&n.key, &n.value))
+        })
+    }
+
+    pub fn check(&self) -> bool {
+        self.check_error().is_none()
+    }
+    pub fn check_error(&self) -> Option<String> {
+        if !is_bst(&self.root, None, None) {
+            return Some("Not a BST".into());
+        }
+        if !is_heap(&self.root) {
+            return Some("Not a heap by priorities".into());
+        }
+        if !is_size_consistent(&self.root) {
+            return Some("Sizes are not consistent".into());
+        }
+        None
+    }
+}
+
+fn size<K, V>(t: &Tree<K, V>) -> usize {
+    t.as_ref().map_or(0, |n| n.size)
+}
+fn height<K, V>(t: &Tree<K, V>) -> usize {
+    t.as_ref()
+        .map_or(0, |n| 1 + height(&n.left).max(height(&n.right)))
+}
+
+fn is_bst<K: Ord, V>(t: &Tree<K, V>, min: Option<&K>, max: Option<&K>) -> bool {
+    t.as_ref().is_none_or(|n| {
+        min.is_none_or(|k| n.key > *k)
+            && max.is_none_or(|k| n.key < *k)
+            && is_bst(&n.left, min, Some(&n.key))
+            && is_bst(&n.right, Some(&n.key), max)
+    })
+}
+fn is_heap<K, V>(t: &Tree<K, V>) -> bool {
+    t.as_ref().is_none_or(|n| {
+        n.left.as_ref().is_none_or(|l| l.priority <= n.priority)
+            && n.right.as_ref().is_none_or(|r| r.priority <= n.priority)
+            && is_heap(&n.left)
+            && is_heap(&n.right)
+    })
+}
+fn is_size_consistent<K, V>(t: &Tree<K, V>) -> bool {
+    t.as_ref().is_none_or(|n| {
+        n.size == 1 + size(&n.left) + size(&n.right)
+            && is_size_consistent(&n.left)
+            && is_size_consistent(&n.right)
+    })
+}
+
+fn rotate_left<K, V>(mut n: Box<Node<K, V>>) -> Box<Node<K, V>> {
+    let mut x = n.right.take().unwrap();
+    n.right = x.left.take();
+    n.update();
+    x.left = Some(n);
+    x.update();
+    x
+}
+fn rotate_right<K, V>(mut n: Box<Node<K, V>>) -> Box<Node<K, V>> {
+    let mut x = n.left.take().unwrap();
+    n.left = x.right.take();
+    n.update();
+    x.right = Some(n);
+    x.update();
+    x
+}
+
+// insert as a leaf, then rotate it up while its priority is higher
+fn put<K: Ord, V>(t: Tree<K, V>, key: K, value: V, priority: u32) -> Box<Node<K, V>> {
+    let mut n = match t {
+        None => return Box::new(Node::new(key, value, priority)),
+        Some(n) => n,
+    };
+    match key.cmp(&n.key) {
+        Equal => n.value = value,
+        Less => {
+            let left = put(n.left.take(), key, value, priority);
+            let up = left.priority > n.priority;
+            n.left = Some(left);
+            n.update();
+            if up {
+                return rotate_right(n);
+            }
+        }
+        Greater => {
+            let right = put(n.right.take(), key, value, priority);
+            let up = right.priority > n.priority;
+            n.right = Some(right);
+            n.update();
+            if up {
+                return rotate_left(n);
+            }
+        }
+    }
+    n
+}
+// merge two treaps, keys of `a` are less than keys of `b`
+fn merge<K, V>(a: Tree<K, V>, b: Tree<K, V>) -> Tree<K, V> {
+    match (a, b) {
+        (None, t) | (t, None) => t,
+        (Some(mut a), Some(mut b)) => {
+            if a.priority > b.priority {
+                a.right = merge(a.right.take(), Some(b));
+                a.update();
+                Some(a)
+            } else {
+                b.left = merge(Some(a), b.left.take());
+                b.update();
+                Some(b)
+            }
+        }
+    }
+}
+fn remove<K: Ord, V>(t: Tree<K, V>, key: &K) -> (Tree<K, V>, Option<V>) {
+    let mut n = match t {
+        None => return (None, None),
+        Some(n) => n,
+    };
+    let value = match key.cmp(&n.key) {
+        Less => {
+            let (left, value) = remove(n.left.take(), key);
+            n.left = left;
+            value
+        }
+        Greater => {
+            let (right, value) = remove(n.right.take(), key);
+            n.right = right;
+            value
+        }
+        Equal => return (merge(n.left.take(), n.right.take()), Some(n.value)),
+    };
+    n.update();
+    (Some(n), value)
+}
+fn delete_min<K, V>(mut n: Box<Node<K, V>>) -> Tree<K, V> {
+    match n.left.take() {
+        None => n.right.take(),
+        Some(left) => {
+            n.left = delete_min(left);
+            n.update();
+            Some(n)
+        }
+    }
+}
+fn delete_max<K, V>(mut n: Box<Node<K, V>>) -> Tree<K, V> {
+    match n.right.take() {
+        None => n.left.take(),
+        Some(right) => {
+            n.right = delete_max(right);
+            n.update();
+            Some(n)
+        }
+    }
+}
+
+impl<K: Ord, V> SymbolTable<K, V> for Treap<K, V> {
+    fn len(&self) -> usize {
+        Treap::len(self)
+    }
+    fn get(&self, key: &K) -> Option<&V> {
+        Treap::get(self, key)
+    }
+    fn put(&mut self, key: K, value: V) {
+        Treap::put(self, key, value)
+    }
+    fn delete(&mut self, key: &K) {
+        Treap::delete(self, key)
+    }
+    fn keys(&self) -> Box<dyn Iterator<Item = &K> + '_> {
+        Box::new(self.iter().map(|(k, _)| k))
+    }
+}
+impl<K: Ord, V> OrderedSymbolTable<K, V> for Treap<K, V> {
+    fn min(&self) -> Option<&K> {
+        Treap::min(self)
+    }
+    fn max(&self) -> Option<&K> {
+        Treap::max(self)
+    }
+    fn floor(&self, key: &K) -> Option<&K> {
+        Treap::floor(self, key)
+    }
+    fn ceiling(&self, key: &K) -> Option<&K> {
+        Treap::ceiling(self, key)
+    }
+    fn rank(&self, key: &K) -> usize {
+        Treap::rank(self, key)
+    }
+    fn select(&self, i: usize) -> Option<&K> {
+        Treap::select(self, i)
+    }
+    fn delete_min(&mut self) {
+        Treap::delete_min(self)
+    }
+    fn delete_max(&mut self) {
+        Treap::delete_max(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{SeedableRng, StdRng};
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn sorted_inputs() {
+        // a plain BST would be a path of 1000
+        let n = 1000;
+        let mut st = Treap::with_seed(7);
+        for i in 0..n {
+            st.put(i, i);
+        }
+        assert_eq!(None, st.check_error());
+        assert!(st.height() < 50, "height {}", st.height());
+
+        for i in (0..n).step_by(2) {
+            st.delete(&i);
+        }
+        assert_eq!(None, st.check_error());
+        assert_eq!(n / 2, st.len());
+        assert!(st.iter().all(|(k, _)| k % 2 == 1));
+    }
+
+    #[test]
+    fn random() {
+        let mut rng = StdRng::from_seed(&[46][..]);
+        let mut st = Treap::with_seed(46);
+        let mut map = BTreeMap::new();
+        for i in 0..2000 {
+            let k: u32 = rng.gen_range(0, 500);
+            if i % 3 == 0 {
+                assert_eq!(map.remove(&k), st.remove(&k));
+            } else {
+                st.put(k, i);
+                map.insert(k, i);
+            }
+        }
+        assert_eq!(None, st.check_error());
+        assert!(map.iter().eq(st.iter()));
+    }
+
+    #[test]
+    fn seeded() {
+        // same seed, same priorities, same shape
+        let (mut a, mut b) = (Treap::with_seed(46), Treap::with_seed(46));
+        for i in 0..100 {
+            a.put(i, ());
+            b.put(i, ());
+        }
+        assert_eq!(a.height(), b.height());
+    }
+}
@@ -34,8 +34,13 @@ impl<E: PartialOrd + Clone + Default> IndexMinPQ<E> {
         }
 
         self.pq.swap_remove(pos);
-        self.sink(pos);
         self.pos[i] = 0;
+        // the last one was moved to pos
+        if pos < self.pq.len() {
+            self.pos[self.pq[pos]] = pos;
+            self.sink(pos);
+            self.swim(pos);
+        }
     }
 
     pub fn pop(&mut self) -> Option<(usize, E)> {
@@ -43,7 +48,10 @@ impl<E: PartialOrd + Clone + Default> IndexMinPQ<E> {
             let i = self.pq.swap_remove(1);
             let v = self.elements[i].clone();
             self.pos[i] = 0;
-            self.sink(1);
+            if !self.is_empty() {
+                self.pos[self.pq[1]] = 1;
+                self.sink(1);
+            }
             Some((i, v))
         } else {
             None
@@ -55,7 +63,7 @@ impl<E: PartialOrd + Clone + Default> IndexMinPQ<E> {
             self.pos[i] = self.pq.len();
             self.elements[i] = e;
             self.pq.push(i);
-            self.swim(self.len() - 1);
+            self.swim(self.len());
 
             true
         } else {
@@ -211,4 +219,26 @@ mod tests {
         assert_eq!(None, pq.pop());
         assert_eq!(0, pq.len());
     }
+    #[test]
+    fn update_after_pop_and_remove() {
+        let mut pq = IndexMinPQ::<isize>::new(10);
+        for i in 0..8 {
+            pq.upsert(i, 10 + i as isize);
+        }
+        assert_eq!(Some((0, 10)), pq.pop());
+        // the moved last one must still be tracked
+        pq.upsert(7, 1);
+        pq.remove(1);
+        pq.upsert(6, 0);
+        pq.upsert(8, 5);
+
+        assert_eq!(Some((6, 0)), pq.pop());
+        assert_eq!(Some((7, 1)), pq.pop());
+        assert_eq!(Some((8, 5)), pq.pop());
+        assert_eq!(Some((2, 12)), pq.pop());
+        assert_eq!(Some((3, 13)), pq.pop());
+        assert_eq!(Some((4, 14)), pq.pop());
+        assert_eq!(Some((5, 15)), pq.pop());
+        assert_eq!(None, pq.pop());
+    }
 }